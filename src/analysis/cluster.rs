@@ -0,0 +1,260 @@
+//! Grouping of articles that cover the same story.
+//!
+//! Each article's title and description are turned into a set of shingles, its words
+//! without stopwords and plural endings, and the set is summarised in a MinHash signature. The share of matching signature entries
+//! estimates how much two articles' shingle sets overlap. Articles that overlap
+//! enough and were published close together end up in the same group.
+
+use crate::analysis::is_stopword;
+use crate::analysis::stem;
+use crate::analysis::words;
+use crate::newsapi::article::Article;
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use std::collections::HashSet;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Number of hash functions in a signature, more make the similarity estimate more precise.
+const SIGNATURE_LEN: usize = 64;
+
+/// Estimated overlap of two articles' shingles from which they're taken to cover the same story.
+const SIMILARITY_THRESHOLD: f32 = 0.3;
+
+/// Longest time between two articles' publication for them to cover the same story.
+const MAX_TIME_APART: TimeDelta = TimeDelta::hours(36);
+
+/// MinHash signature of an article, None when it has no words to compare.
+type Signature = Option<[u64; SIGNATURE_LEN]>;
+
+/// Mix a hash with a seed into a new well-distributed hash (splitmix64).
+fn mix(hash: u64, seed: u64) -> u64 {
+    let mut z = hash ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Words of an article's title and description that say what it's about.
+///
+/// Different outlets word the same story differently, pairs of words rarely match,
+/// so the shingles are single words.
+fn shingles(article: &Article) -> HashSet<String> {
+    let text = format!(
+        "{} {}",
+        article.bare_title(),
+        article.description.as_deref().unwrap_or_default()
+    );
+    words(&text)
+        .filter(|word| !is_stopword(word))
+        .map(|word| stem(&word).to_string())
+        .collect()
+}
+
+/// MinHash signature of a set of shingles.
+fn signature(shingles: &HashSet<String>) -> Signature {
+    if shingles.is_empty() {
+        return None;
+    }
+
+    let hashes: Vec<u64> = shingles
+        .iter()
+        .map(|shingle| {
+            let mut hasher = DefaultHasher::new();
+            shingle.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+
+    let mut signature = [u64::MAX; SIGNATURE_LEN];
+    for (seed, min) in signature.iter_mut().enumerate() {
+        for hash in &hashes {
+            *min = (*min).min(mix(*hash, seed as u64 + 1));
+        }
+    }
+
+    Some(signature)
+}
+
+/// Estimated overlap of the shingle sets two signatures were made from, 0 to 1.
+fn similarity(a: &[u64; SIGNATURE_LEN], b: &[u64; SIGNATURE_LEN]) -> f32 {
+    let equal = a.iter().zip(b).filter(|(a, b)| a == b).count();
+    equal as f32 / SIGNATURE_LEN as f32
+}
+
+/// Representative of the group `i` is in, compressing the path along the way.
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Group articles that cover the same story.
+///
+/// Parameters:
+/// - `articles`: the articles to group
+/// - `same_story`: for every article an earlier article it's known to cover the same story as,
+///   like the one it duplicates
+///
+/// Returns:
+/// - groups of indices into `articles`, every article is in exactly one group. The groups are
+///   ordered by their first article and the indices in a group are ascending, so results keep
+///   their order with each story shown where its first article was.
+pub fn story_clusters(articles: &[Article], same_story: &[Option<usize>]) -> Vec<Vec<usize>> {
+    let signatures: Vec<Signature> = articles
+        .iter()
+        .map(|article| signature(&shingles(article)))
+        .collect();
+    let published: Vec<Option<DateTime<Utc>>> = articles
+        .iter()
+        .map(|article| article.published_at.as_ref()?.parse().ok())
+        .collect();
+
+    let mut parents: Vec<usize> = (0..articles.len()).collect();
+    for (i, first) in same_story.iter().enumerate() {
+        if let Some(first) = *first {
+            let (a, b) = (find(&mut parents, i), find(&mut parents, first));
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    for i in 0..articles.len() {
+        for j in i + 1..articles.len() {
+            let (Some(a), Some(b)) = (&signatures[i], &signatures[j]) else {
+                continue;
+            };
+
+            // articles without a publication time can match anything
+            let close = match (published[i], published[j]) {
+                (Some(a), Some(b)) => (a - b).abs() <= MAX_TIME_APART,
+                _ => true,
+            };
+
+            if close && similarity(a, b) >= SIMILARITY_THRESHOLD {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                // the earlier article represents the group, keeping groups in result order
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let mut cluster_of = vec![usize::MAX; articles.len()];
+    for i in 0..articles.len() {
+        let root = find(&mut parents, i);
+        if cluster_of[root] == usize::MAX {
+            cluster_of[root] = clusters.len();
+            clusters.push(Vec::new());
+        }
+        clusters[cluster_of[root]].push(i);
+    }
+
+    clusters
+}
+
+/// Number of different sources among a group of articles.
+pub fn source_count(articles: &[Article], cluster: &[usize]) -> usize {
+    cluster
+        .iter()
+        .filter_map(|&i| {
+            let source = &articles[i].source;
+            source.id.as_deref().or(source.name.as_deref())
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::newsapi::article::ArticleSource;
+
+    fn article(source: &str, title: &str, published_at: &str) -> Article {
+        Article {
+            source: ArticleSource {
+                id: None,
+                name: Some(source.to_string()),
+            },
+            author: None,
+            title: title.to_string(),
+            description: None,
+            url: None,
+            url_to_image: None,
+            published_at: Some(published_at.to_string()),
+            content: None,
+        }
+    }
+
+    #[test]
+    fn near_identical_titles_share_a_story() {
+        let articles = [
+            article(
+                "BBC News",
+                "Central bank raises interest rates to fight inflation",
+                "2024-05-02T10:00:00Z",
+            ),
+            article(
+                "Reuters",
+                "Volcano erupts in Iceland, flights grounded across Europe",
+                "2024-05-02T11:00:00Z",
+            ),
+            article(
+                "CNN",
+                "Central bank raises interest rate to fight rising inflation - CNN",
+                "2024-05-02T12:00:00Z",
+            ),
+            article(
+                "ESPN",
+                "Underdogs win the championship final after extra time",
+                "2024-05-02T13:00:00Z",
+            ),
+        ];
+
+        assert_eq!(
+            story_clusters(&articles, &[None; 4]),
+            vec![vec![0, 2], vec![1], vec![3]]
+        );
+        assert_eq!(source_count(&articles, &[0, 2]), 2);
+    }
+
+    #[test]
+    fn same_titles_days_apart_are_different_stories() {
+        let articles = [
+            article(
+                "BBC News",
+                "Central bank raises interest rates to fight inflation",
+                "2024-05-02T10:00:00Z",
+            ),
+            article(
+                "CNN",
+                "Central bank raises interest rates to fight inflation",
+                "2024-06-20T10:00:00Z",
+            ),
+        ];
+
+        assert_eq!(
+            story_clusters(&articles, &[None; 2]),
+            vec![vec![0], vec![1]]
+        );
+        // known duplicates are grouped regardless
+        assert_eq!(
+            story_clusters(&articles, &[None, Some(0)]),
+            vec![vec![0, 1]]
+        );
+    }
+
+    #[test]
+    fn similarity_of_identical_and_disjoint_sets() {
+        let set = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+        let a = signature(&set(&["bank", "rate", "inflation"])).unwrap();
+        let b = signature(&set(&["volcano", "iceland", "flight"])).unwrap();
+
+        assert_eq!(similarity(&a, &a), 1.0);
+        assert!(similarity(&a, &b) < SIMILARITY_THRESHOLD);
+        assert!(signature(&HashSet::new()).is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! Shared and unique wording between articles about the same story.
+//!
+//! Two neighbouring words that also appear next to each other in another article are
+//! a shared phrase. Words that no other article uses at all, stopwords aside, are
+//! unique to the article and show what it adds or how it frames the story.
+
+use crate::analysis::is_stopword;
+use crate::analysis::stem;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// How a highlighted part of a text relates to the other texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhraseKind {
+    /// Wording that also appears in another text
+    Shared,
+    /// A word no other text uses
+    Unique,
+}
+
+/// Highlighted byte ranges of a text, in order and without overlaps.
+pub type Highlights = Vec<(Range<usize>, PhraseKind)>;
+
+/// A word of a text with its byte range and comparison key.
+struct Token {
+    range: Range<usize>,
+    /// lowercased word without possessive or plural ending
+    key: String,
+    stopword: bool,
+}
+
+/// Split a text into words, keeping where each word is.
+fn tokens(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '’';
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, is_word(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                start = None;
+
+                let word = text[s..i].trim_matches(|c| c == '\'' || c == '’');
+                if word.is_empty() {
+                    continue;
+                }
+                let offset = s + text[s..i].find(word).unwrap_or_default();
+                let lower = word.to_lowercase();
+                let lower = lower
+                    .strip_suffix("'s")
+                    .or_else(|| lower.strip_suffix("’s"))
+                    .unwrap_or(&lower);
+
+                tokens.push(Token {
+                    range: offset..offset + word.len(),
+                    key: stem(lower).to_string(),
+                    stopword: is_stopword(lower),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Pairs of neighbouring words of a text that make a phrase, pairs of two stopwords don't count.
+fn phrases(tokens: &[Token]) -> impl Iterator<Item = (String, Range<usize>)> + '_ {
+    tokens
+        .windows(2)
+        .filter(|pair| !(pair[0].stopword && pair[1].stopword))
+        .map(|pair| {
+            (
+                format!("{} {}", pair[0].key, pair[1].key),
+                pair[0].range.start..pair[1].range.end,
+            )
+        })
+}
+
+/// Find the shared and unique wording of several articles.
+///
+/// Parameters:
+/// - `articles`: for every article the texts to compare, like its title and description
+///
+/// Returns:
+/// - for every article the highlights of each of its texts
+pub fn compare_articles(articles: &[Vec<&str>]) -> Vec<Vec<Highlights>> {
+    let tokens: Vec<Vec<Vec<Token>>> = articles
+        .iter()
+        .map(|texts| texts.iter().map(|text| tokens(text)).collect())
+        .collect();
+
+    let phrase_sets: Vec<HashSet<String>> = tokens
+        .iter()
+        .map(|texts| {
+            texts
+                .iter()
+                .flat_map(|tokens| phrases(tokens).map(|(phrase, _)| phrase))
+                .collect()
+        })
+        .collect();
+    let word_sets: Vec<HashSet<&str>> = tokens
+        .iter()
+        .map(|texts| texts.iter().flatten().map(|t| t.key.as_str()).collect())
+        .collect();
+
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(article, texts)| {
+            let others = |i: &usize| *i != article;
+            let shared_phrase = |phrase: &String| {
+                (0..articles.len())
+                    .filter(others)
+                    .any(|i| phrase_sets[i].contains(phrase))
+            };
+            let unique_word = |key: &str| {
+                !(0..articles.len())
+                    .filter(others)
+                    .any(|i| word_sets[i].contains(key))
+            };
+
+            texts
+                .iter()
+                .map(|tokens| {
+                    // shared phrases, with overlapping ones merged
+                    let mut highlights: Highlights = Vec::new();
+                    for (_, range) in phrases(tokens).filter(|(phrase, _)| shared_phrase(phrase)) {
+                        match highlights.last_mut() {
+                            Some((last, _)) if last.end >= range.start => last.end = range.end,
+                            _ => highlights.push((range, PhraseKind::Shared)),
+                        }
+                    }
+
+                    let shared = highlights.clone();
+                    let in_shared = |range: &Range<usize>| {
+                        shared
+                            .iter()
+                            .any(|(s, _)| s.start <= range.start && range.end <= s.end)
+                    };
+                    highlights.extend(
+                        tokens
+                            .iter()
+                            .filter(|t| !t.stopword && !in_shared(&t.range) && unique_word(&t.key))
+                            .map(|t| (t.range.clone(), PhraseKind::Unique)),
+                    );
+
+                    highlights.sort_by_key(|(range, _)| range.start);
+                    highlights
+                })
+                .collect()
+        })
+        .collect()
+}
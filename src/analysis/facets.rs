@@ -0,0 +1,771 @@
+//! Names and key phrases mentioned in the results, for narrowing them down.
+//!
+//! Names are runs of capitalized words, like "Federal Reserve" or "Joe Biden", and acronyms.
+//! A single capitalized word at the start of a sentence could be any word, so it only counts
+//! when the same word is capitalized elsewhere. Headlines in Title Case capitalize every word,
+//! so they only contribute names found in other texts. Words around a name hint at what it is:
+//! a title like "President" before a person, a suffix like "Bank" for an organization and
+//! "in" or "from" before a place. Names in the user's gazetteer are always found, with the
+//! kind given there.
+//!
+//! Key phrases are one or two words in a row, without stopwords or names, that several
+//! articles use.
+
+use crate::analysis::is_stopword;
+use crate::analysis::stem;
+use crate::newsapi::article::Article;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Words joining the capitalized words of a name, like "Bank of England".
+const CONNECTORS: &[&str] = &[
+    "al", "bin", "da", "de", "del", "der", "du", "for", "la", "of", "the", "van", "von",
+];
+
+/// Capitalized words that aren't names on their own.
+const NOT_NAMES: &[&str] = &[
+    "april",
+    "august",
+    "breaking",
+    "december",
+    "exclusive",
+    "february",
+    "friday",
+    "january",
+    "july",
+    "june",
+    "live",
+    "march",
+    "monday",
+    "november",
+    "october",
+    "opinion",
+    "saturday",
+    "september",
+    "sunday",
+    "thursday",
+    "tuesday",
+    "update",
+    "watch",
+    "wednesday",
+];
+
+/// Titles before a person's name, left out of the name.
+const PERSON_TITLES: &[&str] = &[
+    "ceo",
+    "chair",
+    "chancellor",
+    "coach",
+    "dr",
+    "gov",
+    "governor",
+    "judge",
+    "king",
+    "minister",
+    "mayor",
+    "mr",
+    "mrs",
+    "ms",
+    "pope",
+    "president",
+    "prime",
+    "prince",
+    "princess",
+    "queen",
+    "rep",
+    "sen",
+    "senator",
+    "secretary",
+];
+
+/// Last words of organization names.
+const ORGANIZATION_SUFFIXES: &[&str] = &[
+    "agency",
+    "airlines",
+    "army",
+    "association",
+    "bank",
+    "co",
+    "commission",
+    "committee",
+    "company",
+    "congress",
+    "corp",
+    "corporation",
+    "council",
+    "court",
+    "department",
+    "foundation",
+    "fund",
+    "group",
+    "inc",
+    "institute",
+    "league",
+    "llc",
+    "ltd",
+    "ministry",
+    "motors",
+    "network",
+    "parliament",
+    "party",
+    "police",
+    "senate",
+    "union",
+    "university",
+];
+
+/// Words before a place name.
+const PLACE_PREPOSITIONS: &[&str] = &["across", "at", "from", "in", "near", "outside"];
+
+/// Common news words that make poor key phrases, on top of the stopwords.
+const PHRASE_STOPWORDS: &[&str] = &[
+    "according",
+    "could",
+    "day",
+    "first",
+    "get",
+    "including",
+    "last",
+    "latest",
+    "like",
+    "make",
+    "many",
+    "may",
+    "much",
+    "news",
+    "one",
+    "people",
+    "report",
+    "say",
+    "time",
+    "told",
+    "two",
+    "week",
+    "year",
+];
+
+/// Fewest articles a key phrase has to appear in.
+const MIN_PHRASE_ARTICLES: usize = 2;
+
+/// Most facets listed of every kind.
+const MAX_PER_KIND: usize = 12;
+
+/// What a facet is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FacetKind {
+    Person,
+    Organization,
+    Place,
+    /// a name whose kind is unknown
+    Name,
+    Keyphrase,
+}
+
+impl FacetKind {
+    pub const ALL: [FacetKind; 5] = [
+        FacetKind::Person,
+        FacetKind::Organization,
+        FacetKind::Place,
+        FacetKind::Name,
+        FacetKind::Keyphrase,
+    ];
+
+    /// Read the kind of a gazetteer entry, like "person" or "org".
+    fn from_param(param: &str) -> Option<Self> {
+        match param.trim().to_lowercase().as_str() {
+            "person" | "people" => Some(FacetKind::Person),
+            "org" | "organization" | "organisation" | "company" => Some(FacetKind::Organization),
+            "place" | "location" | "country" | "city" => Some(FacetKind::Place),
+            "name" => Some(FacetKind::Name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FacetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FacetKind::Person => "People",
+            FacetKind::Organization => "Organizations",
+            FacetKind::Place => "Places",
+            FacetKind::Name => "Other names",
+            FacetKind::Keyphrase => "Key phrases",
+        })
+    }
+}
+
+/// A name or key phrase mentioned in the results.
+#[derive(Debug, Clone)]
+pub struct Facet {
+    pub kind: FacetKind,
+    /// lowercased form identifying the facet
+    pub key: String,
+    /// most common spelling
+    pub label: String,
+    /// indices of the articles mentioning it, ascending
+    pub articles: Vec<usize>,
+}
+
+/// Names that are always recognized, with what they are.
+#[derive(Debug, Clone, Default)]
+pub struct Gazetteer {
+    /// kind and lowercased words of every name
+    entries: Vec<(FacetKind, String)>,
+}
+
+impl Gazetteer {
+    /// Read gazetteer entries.
+    ///
+    /// Parameters:
+    /// - `entries`: names, optionally after a kind, like "place: Kyiv", "org: OpenAI" or "Jane Doe"
+    pub fn parse<'a>(entries: impl IntoIterator<Item = &'a str>) -> Self {
+        let entries = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let (kind, name) = match entry.split_once(':') {
+                    Some((kind, name)) => match FacetKind::from_param(kind) {
+                        Some(kind) => (kind, name),
+                        None => (FacetKind::Name, entry),
+                    },
+                    None => (FacetKind::Name, entry),
+                };
+
+                let key = name
+                    .split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (!key.is_empty() && kind != FacetKind::Keyphrase).then_some((kind, key))
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    fn kind(&self, key: &str) -> Option<FacetKind> {
+        self.entries
+            .iter()
+            .find(|(_, name)| name == key)
+            .map(|(kind, _)| *kind)
+    }
+}
+
+/// A word of a text.
+#[derive(Clone)]
+struct Token<'a> {
+    /// the word without surrounding punctuation and possessive ending
+    word: &'a str,
+    lower: String,
+    /// the first word of a sentence, capitalized whatever it is
+    sentence_start: bool,
+    /// punctuation after the word ends any name or phrase it's in
+    breaks: bool,
+    /// part of a found name
+    in_name: bool,
+}
+
+/// Split a text into words, noting sentence starts and punctuation between words.
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    let mut tokens: Vec<Token<'_>> = Vec::new();
+    let mut sentence_start = true;
+
+    for raw in text.split_whitespace() {
+        let Some(first) = raw.find(char::is_alphanumeric) else {
+            // lone punctuation like a dash
+            if let Some(last) = tokens.last_mut() {
+                last.breaks = true;
+            }
+            sentence_start |= raw.contains(['.', '!', '?']);
+            continue;
+        };
+        let last = raw.rfind(char::is_alphanumeric).map_or(raw.len(), |i| {
+            i + raw[i..].chars().next().map_or(1, char::len_utf8)
+        });
+
+        // an opening quote or bracket starts something new
+        if first > 0
+            && let Some(last) = tokens.last_mut()
+        {
+            last.breaks = true;
+        }
+
+        let word = &raw[first..last];
+        let word = word
+            .strip_suffix("'s")
+            .or_else(|| word.strip_suffix("’s"))
+            .unwrap_or(word);
+        let trailing = &raw[last..];
+
+        tokens.push(Token {
+            word,
+            lower: word.to_lowercase(),
+            sentence_start,
+            breaks: !trailing.is_empty() || word.len() < last - first,
+            in_name: false,
+        });
+        sentence_start = trailing.contains(['.', '!', '?', ':']);
+    }
+
+    tokens
+}
+
+fn capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// Whether a word is an acronym like "NASA" or "U.S".
+fn acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() >= 2
+        && word
+            .chars()
+            .all(|c| c.is_uppercase() || c.is_ascii_digit() || c == '.' || c == '&')
+}
+
+/// Whether every longer word of a text is capitalized, as in a Title Case headline.
+fn title_case(tokens: &[Token<'_>]) -> bool {
+    let words: Vec<&Token<'_>> = tokens
+        .iter()
+        .filter(|t| t.word.chars().count() > 3 && !is_stopword(&t.lower))
+        .collect();
+
+    // names like "iPhone" have a capital letter too
+    words.len() >= 3 && words.iter().all(|t| t.word.chars().any(char::is_uppercase))
+}
+
+/// A mention of a name.
+struct Mention {
+    key: String,
+    label: String,
+    article: usize,
+    /// a single word at the start of a sentence, only a name if it's capitalized elsewhere too
+    weak: bool,
+    /// what the words around the mention say the name is
+    hint: Option<FacetKind>,
+}
+
+impl Mention {
+    fn new(words: &[Token<'_>], article: usize, weak: bool, hint: Option<FacetKind>) -> Self {
+        Self {
+            key: words
+                .iter()
+                .map(|t| t.lower.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            label: words.iter().map(|t| t.word).collect::<Vec<_>>().join(" "),
+            article,
+            weak,
+            hint,
+        }
+    }
+}
+
+/// Find the capitalized runs of words in a text that isn't in Title Case.
+fn capitalized_names(tokens: &mut [Token<'_>], article: usize, mentions: &mut Vec<Mention>) {
+    let mut i = 0;
+    while i < tokens.len() {
+        if !capitalized(tokens[i].word) {
+            i += 1;
+            continue;
+        }
+
+        // extend over capitalized words and connectors followed by one
+        let mut end = i + 1;
+        while end < tokens.len() && !tokens[end - 1].breaks {
+            if capitalized(tokens[end].word) {
+                end += 1;
+            } else if CONNECTORS.contains(&tokens[end].lower.as_str())
+                && tokens[end].lower != "the"
+                && let Some(next) = (end + 1..tokens.len()).find(|&j| {
+                    !CONNECTORS.contains(&tokens[j].lower.as_str()) || tokens[j - 1].breaks
+                })
+                && capitalized(tokens[next].word)
+                && !tokens[end..next].iter().any(|t| t.breaks)
+            {
+                end = next + 1;
+            } else {
+                break;
+            }
+        }
+        let (run_start, run_end) = (i, end);
+        i = end;
+
+        // drop leading stopwords like "The" and titles, noting a title means a person
+        let mut start = run_start;
+        let mut hint = None;
+        while start < run_end {
+            let lower = tokens[start].lower.as_str();
+            if PERSON_TITLES.contains(&lower) {
+                hint = Some(FacetKind::Person);
+            } else if !is_stopword(lower) && !NOT_NAMES.contains(&lower) {
+                break;
+            }
+            start += 1;
+        }
+        let mut end = run_end;
+        while end > start && CONNECTORS.contains(&tokens[end - 1].lower.as_str()) {
+            end -= 1;
+        }
+        if start == end {
+            continue;
+        }
+
+        let words = &tokens[start..end];
+        if hint.is_none() {
+            hint = if words
+                .iter()
+                .any(|t| ORGANIZATION_SUFFIXES.contains(&t.lower.as_str()))
+            {
+                Some(FacetKind::Organization)
+            } else if start > 0
+                && !tokens[start - 1].breaks
+                && PLACE_PREPOSITIONS.contains(&tokens[start - 1].lower.as_str())
+            {
+                Some(FacetKind::Place)
+            } else {
+                None
+            };
+        }
+
+        let weak = words.len() == 1 && words[0].sentence_start && !acronym(words[0].word);
+        mentions.push(Mention::new(words, article, weak, hint));
+        for token in &mut tokens[start..end] {
+            token.in_name = true;
+        }
+    }
+}
+
+/// Find known names in a text, longest first, marking their words.
+///
+/// Parameters:
+/// - `known`: lowercased names to look for
+/// - `max_words`: words in the longest known name
+fn known_names(
+    tokens: &mut [Token<'_>],
+    known: &HashSet<&str>,
+    max_words: usize,
+    article: usize,
+    mentions: &mut Vec<Mention>,
+) {
+    let mut i = 0;
+    while i < tokens.len() {
+        let found = (1..=max_words.min(tokens.len() - i)).rev().find(|&len| {
+            !tokens[i..i + len - 1].iter().any(|t| t.breaks)
+                && known.contains(
+                    tokens[i..i + len]
+                        .iter()
+                        .map(|t| t.lower.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .as_str(),
+                )
+        });
+
+        let Some(len) = found else {
+            i += 1;
+            continue;
+        };
+
+        mentions.push(Mention::new(&tokens[i..i + len], article, false, None));
+        for token in &mut tokens[i..i + len] {
+            token.in_name = true;
+        }
+        i += len;
+    }
+}
+
+/// Most common of several spellings, the first one seen wins ties.
+fn most_common<'a>(spellings: impl Iterator<Item = &'a str>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for spelling in spellings {
+        match counts.iter_mut().find(|(s, _)| *s == spelling) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((spelling, 1)),
+        }
+    }
+
+    // max_by_key picks the last of equal counts, so look from the end for the first
+    counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(s, _)| s.to_string())
+        .unwrap_or_default()
+}
+
+/// Find the names and key phrases mentioned in titles and descriptions.
+///
+/// Parameters:
+/// - `articles`: the results
+/// - `gazetteer`: names to always recognize
+///
+/// Returns:
+/// - the facets, grouped by kind in the order of `FacetKind::ALL` and most mentioned first
+pub fn extract_facets(articles: &[Article], gazetteer: &Gazetteer) -> Vec<Facet> {
+    let texts: Vec<(usize, &str)> = articles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, article)| {
+            [
+                Some((i, article.bare_title())),
+                article.description.as_deref().map(|d| (i, d)),
+            ]
+        })
+        .flatten()
+        .collect();
+    let mut tokenized: Vec<(usize, Vec<Token<'_>>, bool)> = texts
+        .iter()
+        .map(|(i, text)| {
+            let tokens = tokens(text);
+            let title_case = title_case(&tokens);
+            (*i, tokens, title_case)
+        })
+        .collect();
+
+    // names in running text, then the ones of them, and of the gazetteer, in Title Case headlines
+    let mut mentions = Vec::new();
+    for (article, tokens, title_case) in &mut tokenized {
+        if !*title_case {
+            capitalized_names(tokens, *article, &mut mentions);
+        }
+    }
+
+    let confirmed: HashSet<String> = mentions
+        .iter()
+        .filter(|m| !m.weak)
+        .map(|m| m.key.clone())
+        .collect();
+    let mut known: HashSet<&str> = confirmed.iter().map(String::as_str).collect();
+    let mut from_gazetteer: HashSet<&str> = HashSet::new();
+    for (_, key) in &gazetteer.entries {
+        known.insert(key);
+        from_gazetteer.insert(key);
+    }
+    let max_words = known
+        .iter()
+        .map(|key| key.split(' ').count())
+        .max()
+        .unwrap_or_default();
+
+    for (article, tokens, title_case) in &mut tokenized {
+        if *title_case {
+            known_names(tokens, &known, max_words, *article, &mut mentions);
+        } else if !from_gazetteer.is_empty() {
+            // gazetteer names can be written in lowercase, or inside a longer capitalized run
+            let mut unmarked = tokens.clone();
+            for token in &mut unmarked {
+                token.in_name = false;
+            }
+            let mut found = Vec::new();
+            known_names(
+                &mut unmarked,
+                &from_gazetteer,
+                max_words,
+                *article,
+                &mut found,
+            );
+            for (token, marked) in tokens.iter_mut().zip(&unmarked) {
+                token.in_name |= marked.in_name;
+            }
+            mentions.extend(found.into_iter().filter(|m| !confirmed.contains(&m.key)));
+        }
+    }
+
+    let mut names: HashMap<&str, Vec<&Mention>> = HashMap::new();
+    for mention in &mentions {
+        if confirmed.contains(&mention.key) || from_gazetteer.contains(mention.key.as_str()) {
+            names.entry(&mention.key).or_default().push(mention);
+        }
+    }
+
+    let mut facets: Vec<Facet> = names
+        .into_iter()
+        .map(|(key, mentions)| {
+            let kind = gazetteer.kind(key).unwrap_or_else(|| {
+                let mut votes: HashMap<FacetKind, usize> = HashMap::new();
+                for hint in mentions.iter().filter_map(|m| m.hint) {
+                    *votes.entry(hint).or_default() += 1;
+                }
+                votes
+                    .into_iter()
+                    .max_by_key(|(kind, votes)| (*votes, std::cmp::Reverse(*kind)))
+                    .map_or(FacetKind::Name, |(kind, _)| kind)
+            });
+
+            let mut articles: Vec<usize> = mentions.iter().map(|m| m.article).collect();
+            articles.sort_unstable();
+            articles.dedup();
+
+            Facet {
+                kind,
+                key: key.to_string(),
+                label: most_common(mentions.iter().map(|m| m.label.as_str())),
+                articles,
+            }
+        })
+        .collect();
+
+    facets.extend(keyphrases(&tokenized));
+
+    facets.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then(b.articles.len().cmp(&a.articles.len()))
+            .then(a.label.cmp(&b.label))
+    });
+
+    let mut listed: HashMap<FacetKind, usize> = HashMap::new();
+    facets.retain(|facet| {
+        let count = listed.entry(facet.kind).or_default();
+        *count += 1;
+        *count <= MAX_PER_KIND
+    });
+
+    facets
+}
+
+/// Find phrases of one or two words, outside names, that several articles use.
+fn keyphrases(tokenized: &[(usize, Vec<Token<'_>>, bool)]) -> Vec<Facet> {
+    let phrase_word = |t: &Token<'_>| {
+        !t.in_name
+            && t.word.chars().all(char::is_alphabetic)
+            && !is_stopword(&t.lower)
+            && !PHRASE_STOPWORDS.contains(&t.lower.as_str())
+    };
+
+    // for every phrase the articles using it and its spellings
+    let mut phrases: HashMap<String, (HashSet<usize>, Vec<String>)> = HashMap::new();
+    for (article, tokens, _) in tokenized {
+        for (i, token) in tokens.iter().enumerate() {
+            if !phrase_word(token) {
+                continue;
+            }
+
+            if token.word.chars().count() >= 4 {
+                let entry = phrases.entry(stem(&token.lower).to_string()).or_default();
+                entry.0.insert(*article);
+                entry.1.push(token.lower.clone());
+            }
+
+            if let Some(next) = tokens.get(i + 1)
+                && !token.breaks
+                && phrase_word(next)
+            {
+                let entry = phrases
+                    .entry(format!("{} {}", stem(&token.lower), stem(&next.lower)))
+                    .or_default();
+                entry.0.insert(*article);
+                entry.1.push(format!("{} {}", token.lower, next.lower));
+            }
+        }
+    }
+
+    let mut ranked: Vec<(String, HashSet<usize>, Vec<String>)> = phrases
+        .into_iter()
+        .filter(|(_, (articles, _))| articles.len() >= MIN_PHRASE_ARTICLES)
+        .map(|(key, (articles, spellings))| (key, articles, spellings))
+        .collect();
+    // two word phrases first, so words only used as part of one can be left out
+    ranked.sort_by(|a, b| {
+        let words = |key: &str| key.split(' ').count();
+        words(&b.0)
+            .cmp(&words(&a.0))
+            .then(b.1.len().cmp(&a.1.len()))
+    });
+
+    let mut facets: Vec<Facet> = Vec::new();
+    for (key, articles, spellings) in ranked {
+        let within_phrase = !key.contains(' ')
+            && facets.iter().any(|f| {
+                f.articles.len() == articles.len() && f.key.split(' ').any(|word| word == key)
+            });
+        if within_phrase {
+            continue;
+        }
+
+        let mut articles: Vec<usize> = articles.into_iter().collect();
+        articles.sort_unstable();
+        facets.push(Facet {
+            kind: FacetKind::Keyphrase,
+            key,
+            label: most_common(spellings.iter().map(String::as_str)),
+            articles,
+        });
+    }
+
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::newsapi::article::ArticleSource;
+
+    fn article(title: &str, description: &str) -> Article {
+        Article {
+            source: ArticleSource {
+                id: None,
+                name: None,
+            },
+            author: None,
+            title: title.to_string(),
+            description: Some(description.to_string()),
+            url: None,
+            url_to_image: None,
+            published_at: None,
+            content: None,
+        }
+    }
+
+    fn articles() -> Vec<Article> {
+        vec![
+            article(
+                "Fed Holds Interest Rates Steady as Inflation Cools",
+                "The Federal Reserve kept interest rates unchanged, Chair Jerome Powell said in Washington.",
+            ),
+            article(
+                "Markets rally after the Federal Reserve decision",
+                "Investors cheered as Jerome Powell hinted at lower interest rates later this year.",
+            ),
+            article(
+                "Drone strikes hit Kyiv overnight",
+                "Officials in Kyiv said air defenses shot down most drones.",
+            ),
+        ]
+    }
+
+    fn summary(facets: &[Facet]) -> Vec<(FacetKind, &str, &[usize])> {
+        facets
+            .iter()
+            .map(|f| (f.kind, f.label.as_str(), f.articles.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn counts_names_and_key_phrases() {
+        let facets = extract_facets(&articles(), &Gazetteer::default());
+
+        assert_eq!(
+            summary(&facets),
+            [
+                (FacetKind::Person, "Jerome Powell", &[0, 1][..]),
+                (FacetKind::Place, "Kyiv", &[2]),
+                (FacetKind::Place, "Washington", &[0]),
+                (FacetKind::Name, "Federal Reserve", &[0, 1]),
+                (FacetKind::Keyphrase, "interest rates", &[0, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn gazetteer_names_are_found_with_their_kind() {
+        let gazetteer = Gazetteer::parse(["org: Federal Reserve", "drone strikes", "nonsense: x"]);
+        let facets = extract_facets(&articles(), &gazetteer);
+
+        let kind_of = |label: &str| facets.iter().find(|f| f.label == label).map(|f| f.kind);
+        assert_eq!(kind_of("Federal Reserve"), Some(FacetKind::Organization));
+        assert_eq!(kind_of("Drone strikes"), Some(FacetKind::Name));
+        // an unknown kind is part of the name
+        assert!(gazetteer.kind("nonsense: x").is_some());
+    }
+}
@@ -0,0 +1,52 @@
+//! Local text analysis of fetched articles, none of it makes requests.
+
+pub mod cluster;
+pub mod compare;
+pub mod facets;
+pub mod sentiment;
+pub mod summary;
+
+/// Common English words that say little about what a text is about.
+pub const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "again", "against", "all", "also", "am", "an", "and", "any", "are",
+    "as", "at", "be", "because", "been", "before", "being", "between", "both", "but", "by", "can",
+    "could", "did", "do", "does", "doing", "down", "during", "each", "few", "for", "from",
+    "further", "had", "has", "have", "having", "he", "her", "here", "hers", "him", "his", "how",
+    "i", "if", "in", "into", "is", "it", "its", "itself", "just", "me", "more", "most", "my",
+    "new", "no", "nor", "not", "now", "of", "off", "on", "once", "only", "or", "other", "our",
+    "ours", "out", "over", "own", "said", "same", "says", "she", "should", "so", "some", "such",
+    "than", "that", "the", "their", "theirs", "them", "then", "there", "these", "they", "this",
+    "those", "through", "to", "too", "under", "until", "up", "very", "was", "we", "were", "what",
+    "when", "where", "which", "while", "who", "whom", "why", "will", "with", "would", "you",
+    "your", "yours",
+];
+
+/// Lowercased words of a text, split on anything that isn't a letter, digit or apostrophe.
+///
+/// Possessive endings are dropped, so "Apple's" and "Apple" are the same word.
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .map(|word| {
+            let word = word.trim_matches(|c| c == '\'' || c == '’').to_lowercase();
+            match word.strip_suffix("'s").or_else(|| word.strip_suffix("’s")) {
+                Some(stem) => stem.to_string(),
+                None => word,
+            }
+        })
+        .filter(|word| !word.is_empty())
+}
+
+/// Whether a lowercased word is a stopword.
+pub fn is_stopword(word: &str) -> bool {
+    STOPWORDS.binary_search(&word).is_ok()
+}
+
+/// Strip the plural ending of a lowercased word, so "rates" and "rate" are the same word.
+///
+/// Short words and words ending in "ss" are kept as they are.
+pub fn stem(word: &str) -> &str {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.chars().count() >= 3 && !stem.ends_with('s') => stem,
+        _ => word,
+    }
+}
@@ -0,0 +1,499 @@
+//! Tone of articles, scored with a word list like VADER does.
+//!
+//! Every word in the lexicon has a valence from -4 (very negative) to 4 (very positive). Words
+//! before it can strengthen ("very"), weaken ("slightly") or flip ("not") it, writing it in
+//! capitals stresses it, and what follows "but" counts more than what comes before. The sum is
+//! squashed into a score from -1 to 1, with exclamation marks pushing it further out.
+
+use crate::analysis::stem;
+use crate::newsapi::article::Article;
+use std::fmt;
+
+/// Valence of words that carry a tone, sorted so they can be binary searched.
+const LEXICON: &[(&str, f32)] = &[
+    ("abuse", -3.2),
+    ("abused", -3.2),
+    ("accident", -2.1),
+    ("accomplish", 1.8),
+    ("accomplished", 1.9),
+    ("accused", -1.9),
+    ("achieve", 1.9),
+    ("achievement", 2.1),
+    ("afraid", -2.2),
+    ("aggression", -2.4),
+    ("agree", 1.5),
+    ("agreement", 2.2),
+    ("alarm", -1.4),
+    ("alarming", -2.0),
+    ("anger", -2.7),
+    ("angry", -2.3),
+    ("applaud", 2.0),
+    ("approve", 2.0),
+    ("approved", 1.8),
+    ("arrest", -1.4),
+    ("arrested", -2.1),
+    ("assault", -2.8),
+    ("attack", -2.1),
+    ("attacked", -2.0),
+    ("award", 2.5),
+    ("awful", -2.0),
+    ("bad", -2.5),
+    ("ban", -2.6),
+    ("bankrupt", -2.6),
+    ("bankruptcy", -2.6),
+    ("banned", -2.0),
+    ("beautiful", 2.9),
+    ("benefit", 2.0),
+    ("best", 3.2),
+    ("better", 1.9),
+    ("blame", -1.4),
+    ("bleak", -2.5),
+    ("bomb", -2.2),
+    ("bombing", -2.4),
+    ("boost", 1.7),
+    ("boosted", 1.5),
+    ("brave", 2.4),
+    ("breakthrough", 2.2),
+    ("brutal", -3.1),
+    ("burden", -1.9),
+    ("calm", 1.3),
+    ("catastrophe", -3.4),
+    ("catastrophic", -3.2),
+    ("celebrate", 2.7),
+    ("celebrated", 2.7),
+    ("celebration", 2.9),
+    ("champion", 2.9),
+    ("chaos", -2.7),
+    ("cheer", 2.3),
+    ("clash", -1.5),
+    ("collapse", -2.2),
+    ("collapsed", -2.1),
+    ("concern", -0.4),
+    ("condemn", -1.6),
+    ("condemned", -1.9),
+    ("confident", 2.2),
+    ("conflict", -1.3),
+    ("corrupt", -3.0),
+    ("corruption", -1.9),
+    ("crash", -1.7),
+    ("crashed", -2.0),
+    ("crime", -2.5),
+    ("crisis", -3.1),
+    ("critical", -1.3),
+    ("criticism", -1.9),
+    ("criticized", -1.9),
+    ("cruel", -2.8),
+    ("cure", 1.9),
+    ("cut", -1.1),
+    ("damage", -2.2),
+    ("damaged", -1.9),
+    ("danger", -2.4),
+    ("dangerous", -2.1),
+    ("dead", -3.3),
+    ("deadly", -2.4),
+    ("death", -2.9),
+    ("decline", -1.1),
+    ("declined", -0.9),
+    ("defeat", -2.0),
+    ("defeated", -2.1),
+    ("deficit", -1.7),
+    ("delay", -1.3),
+    ("delayed", -1.2),
+    ("denied", -1.9),
+    ("destroy", -2.5),
+    ("destroyed", -2.2),
+    ("destruction", -2.7),
+    ("devastating", -3.1),
+    ("died", -2.6),
+    ("disaster", -3.1),
+    ("disease", -2.2),
+    ("dispute", -1.7),
+    ("disrupt", -1.7),
+    ("disruption", -1.5),
+    ("doubt", -1.5),
+    ("drop", -1.1),
+    ("dropped", -1.2),
+    ("easy", 1.9),
+    ("emergency", -1.6),
+    ("encouraging", 2.4),
+    ("enemy", -2.5),
+    ("epidemic", -2.5),
+    ("evacuate", -1.0),
+    ("excellent", 2.7),
+    ("excited", 1.4),
+    ("exciting", 2.2),
+    ("explosion", -2.2),
+    ("fail", -2.5),
+    ("failed", -2.3),
+    ("failure", -2.3),
+    ("fair", 1.3),
+    ("fake", -2.1),
+    ("fatal", -2.5),
+    ("fear", -2.2),
+    ("fight", -1.6),
+    ("fighting", -1.9),
+    ("fine", 0.8),
+    ("fire", -1.4),
+    ("flood", -1.9),
+    ("fraud", -2.8),
+    ("gain", 2.4),
+    ("good", 1.9),
+    ("great", 3.1),
+    ("grief", -2.2),
+    ("growth", 1.6),
+    ("guilty", -1.8),
+    ("happy", 2.7),
+    ("harm", -2.5),
+    ("hate", -2.7),
+    ("heal", 1.4),
+    ("help", 1.7),
+    ("helped", 1.6),
+    ("hero", 2.6),
+    ("hope", 1.9),
+    ("hopeful", 2.3),
+    ("horrible", -2.5),
+    ("hostage", -2.8),
+    ("hurt", -2.4),
+    ("illegal", -2.6),
+    ("improve", 1.9),
+    ("improved", 2.1),
+    ("improvement", 2.0),
+    ("injured", -1.7),
+    ("injury", -1.8),
+    ("innovative", 1.9),
+    ("jail", -2.2),
+    ("kill", -3.7),
+    ("killed", -3.5),
+    ("killing", -3.4),
+    ("lawsuit", -1.5),
+    ("layoff", -2.2),
+    ("lose", -1.6),
+    ("losing", -1.6),
+    ("loss", -1.3),
+    ("lost", -1.3),
+    ("love", 3.2),
+    ("lucky", 1.8),
+    ("massacre", -3.6),
+    ("murder", -3.7),
+    ("murdered", -3.5),
+    ("outrage", -2.8),
+    ("pain", -2.3),
+    ("panic", -2.3),
+    ("peace", 2.5),
+    ("peaceful", 2.2),
+    ("plunge", -1.5),
+    ("plunged", -1.6),
+    ("poor", -2.1),
+    ("popular", 1.8),
+    ("positive", 2.6),
+    ("poverty", -2.3),
+    ("praise", 2.6),
+    ("praised", 2.2),
+    ("problem", -1.7),
+    ("progress", 1.8),
+    ("promising", 1.7),
+    ("prosper", 2.2),
+    ("protect", 1.3),
+    ("protest", -1.0),
+    ("proud", 2.1),
+    ("rally", 1.0),
+    ("recession", -2.1),
+    ("recover", 1.9),
+    ("recovery", 1.4),
+    ("relief", 2.1),
+    ("rescue", 2.3),
+    ("rescued", 1.8),
+    ("risk", -1.1),
+    ("sad", -2.1),
+    ("safe", 1.9),
+    ("safety", 1.8),
+    ("save", 2.2),
+    ("saved", 1.8),
+    ("scandal", -1.9),
+    ("scare", -2.2),
+    ("shooting", -1.4),
+    ("shortage", -1.7),
+    ("slump", -1.7),
+    ("soar", 1.6),
+    ("soared", 1.6),
+    ("strong", 2.3),
+    ("stronger", 1.6),
+    ("struggle", -1.5),
+    ("struggling", -1.6),
+    ("succeed", 2.2),
+    ("success", 2.7),
+    ("successful", 2.8),
+    ("suffer", -2.5),
+    ("suffering", -2.1),
+    ("suicide", -3.5),
+    ("support", 1.7),
+    ("terrible", -2.1),
+    ("terror", -3.2),
+    ("terrorist", -3.7),
+    ("threat", -2.4),
+    ("threaten", -1.6),
+    ("threatened", -2.0),
+    ("thrive", 2.3),
+    ("tragedy", -3.4),
+    ("tragic", -3.4),
+    ("triumph", 2.9),
+    ("trouble", -1.7),
+    ("tumble", -1.2),
+    ("uncertain", -1.2),
+    ("uncertainty", -1.4),
+    ("unemployment", -1.9),
+    ("victim", -2.6),
+    ("victory", 2.8),
+    ("violence", -3.1),
+    ("violent", -2.9),
+    ("war", -2.9),
+    ("warn", -1.4),
+    ("warning", -1.4),
+    ("weak", -1.9),
+    ("welcome", 2.0),
+    ("win", 2.8),
+    ("winner", 2.8),
+    ("won", 2.7),
+    ("wonderful", 2.7),
+    ("worried", -1.2),
+    ("worry", -1.9),
+    ("worse", -2.1),
+    ("worst", -3.1),
+    ("wounded", -2.3),
+];
+
+/// Words making the next words stronger.
+const BOOSTERS: &[&str] = &[
+    "absolutely",
+    "deeply",
+    "especially",
+    "extremely",
+    "greatly",
+    "highly",
+    "hugely",
+    "incredibly",
+    "massive",
+    "most",
+    "really",
+    "severely",
+    "sharply",
+    "so",
+    "totally",
+    "very",
+];
+
+/// Words making the next words weaker.
+const DAMPENERS: &[&str] = &[
+    "barely",
+    "hardly",
+    "marginally",
+    "partly",
+    "slightly",
+    "somewhat",
+];
+
+/// Words reversing the tone of the next words, on top of words ending in "n't".
+const NEGATIONS: &[&str] = &[
+    "cannot", "neither", "never", "no", "nobody", "none", "nor", "not", "nothing", "without",
+];
+
+/// Valence a booster or dampener adds or takes away.
+const BOOST: f32 = 0.293;
+
+/// Valence added to a word written in capitals in a text that isn't.
+const CAPS_BOOST: f32 = 0.733;
+
+/// Factor the valence of a negated word is multiplied by, flipping and weakening it.
+const NEGATION: f32 = -0.74;
+
+/// Valence added by every exclamation mark, up to `MAX_EXCLAMATIONS`.
+const EXCLAMATION_BOOST: f32 = 0.292;
+const MAX_EXCLAMATIONS: usize = 4;
+
+/// How quickly the sum of valences approaches -1 or 1 in the score.
+const NORMALIZATION: f32 = 15.0;
+
+/// Score over which a text is positive, and under the negative of which it's negative.
+pub const NEUTRAL_BAND: f32 = 0.05;
+
+/// Rough tone of a score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Negative,
+    Neutral,
+    Positive,
+}
+
+impl Tone {
+    pub fn of(score: f32) -> Self {
+        if score >= NEUTRAL_BAND {
+            Tone::Positive
+        } else if score <= -NEUTRAL_BAND {
+            Tone::Negative
+        } else {
+            Tone::Neutral
+        }
+    }
+}
+
+impl fmt::Display for Tone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tone::Negative => "Negative",
+            Tone::Neutral => "Neutral",
+            Tone::Positive => "Positive",
+        })
+    }
+}
+
+/// Valence of a lowercased word, trying it without plural ending too.
+fn valence(word: &str) -> Option<f32> {
+    [word, stem(word)].into_iter().find_map(|word| {
+        LEXICON
+            .binary_search_by_key(&word, |(w, _)| w)
+            .ok()
+            .map(|i| LEXICON[i].1)
+    })
+}
+
+fn negation(word: &str) -> bool {
+    NEGATIONS.contains(&word) || word.ends_with("n't") || word.ends_with("n’t")
+}
+
+/// Score the tone of a text.
+///
+/// Returns:
+/// - a score from -1 (very negative) to 1 (very positive), 0 when no word carries a tone
+pub fn sentiment(text: &str) -> f32 {
+    let raw: Vec<&str> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .map(|word| word.trim_matches(|c| c == '\'' || c == '’'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let lower: Vec<String> = raw.iter().map(|word| word.to_lowercase()).collect();
+
+    let shouting = |word: &str| {
+        word.chars().count() > 1
+            && word.chars().any(char::is_alphabetic)
+            && !word.chars().any(char::is_lowercase)
+    };
+    // capitals only stress a word when the rest of the text isn't in capitals too
+    let mixed_case = raw.iter().any(|word| !shouting(word));
+
+    let mut valences: Vec<f32> = vec![0.0; raw.len()];
+    for (i, word) in lower.iter().enumerate() {
+        let Some(mut v) = valence(word) else {
+            continue;
+        };
+
+        if mixed_case && shouting(raw[i]) {
+            v += CAPS_BOOST * v.signum();
+        }
+
+        // the three words before, less and less influential
+        for (distance, damping) in [(1, 1.0), (2, 0.95), (3, 0.9)] {
+            let Some(before) = i.checked_sub(distance).map(|j| lower[j].as_str()) else {
+                break;
+            };
+
+            if BOOSTERS.contains(&before) {
+                v += BOOST * damping * v.signum();
+            } else if DAMPENERS.contains(&before) {
+                v -= BOOST * damping * v.signum();
+            } else if negation(before) {
+                v *= NEGATION;
+            }
+        }
+
+        valences[i] = v;
+    }
+
+    // what follows "but" matters more than what comes before
+    if let Some(but) = lower.iter().position(|word| word == "but") {
+        for (i, v) in valences.iter_mut().enumerate() {
+            *v *= if i < but { 0.5 } else { 1.5 };
+        }
+    }
+
+    let mut sum: f32 = valences.iter().sum();
+    if sum == 0.0 {
+        return 0.0;
+    }
+
+    let exclamations = text.matches('!').count().min(MAX_EXCLAMATIONS);
+    sum += EXCLAMATION_BOOST * exclamations as f32 * sum.signum();
+
+    sum / (sum * sum + NORMALIZATION).sqrt()
+}
+
+/// Score the tone of an article from its title and description.
+pub fn article_sentiment(article: &Article) -> f32 {
+    match &article.description {
+        Some(description) => sentiment(&format!("{}. {description}", article.bare_title())),
+        None => sentiment(article.bare_title()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lexicon_is_sorted() {
+        assert!(LEXICON.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn polarity_of_plain_text() {
+        assert_eq!(
+            Tone::of(sentiment("Rescue teams celebrate a great victory")),
+            Tone::Positive
+        );
+        assert_eq!(
+            Tone::of(sentiment("Deadly flood leaves dozens injured")),
+            Tone::Negative
+        );
+        assert_eq!(sentiment("The council meets on Tuesday"), 0.0);
+        // plural endings are found through the stem
+        assert!(sentiment("Two bombings") < 0.0);
+    }
+
+    #[test]
+    fn negation_flips_and_weakens() {
+        let good = sentiment("The plan is good");
+        let not_good = sentiment("The plan is not good");
+        let isnt_good = sentiment("The plan isn't good");
+
+        assert!(not_good < 0.0);
+        assert!(not_good.abs() < good);
+        assert_eq!(not_good, isnt_good);
+        // still negated two words later
+        assert!(sentiment("It was never a very good plan") < 0.0);
+    }
+
+    #[test]
+    fn boosters_dampeners_and_capitals() {
+        let good = sentiment("The results were good");
+
+        assert!(sentiment("The results were very good") > good);
+        assert!(sentiment("The results were slightly good") < good);
+        assert!(sentiment("The results were GOOD") > good);
+        // a text all in capitals isn't stressed
+        assert_eq!(sentiment("THE RESULTS WERE GOOD"), good);
+        assert!(sentiment("The results were good!!") > good);
+    }
+
+    #[test]
+    fn what_follows_but_counts_more() {
+        // "bad" outweighs "good" until "but" puts the stress on the second half
+        assert!(sentiment("The food was bad and the service was good") < 0.0);
+        assert!(sentiment("The food was bad but the service was good") > 0.0);
+        assert!(sentiment("The food was good but the service was bad") < 0.0);
+    }
+
+    #[test]
+    fn scores_stay_within_range() {
+        let score = sentiment("Killed! Murder! Massacre! Terrorist! Catastrophe! Worst tragedy!");
+        assert!((-1.0..-0.5).contains(&score));
+    }
+}
@@ -0,0 +1,211 @@
+//! Extractive summaries with TextRank.
+//!
+//! Sentences are the nodes of a graph whose edges are weighted by how many words two sentences
+//! share. Ranking the graph like PageRank scores highest the sentences the rest of the text
+//! agrees with the most, and the best of them make the summary, in the order they were written.
+
+use crate::analysis::is_stopword;
+use crate::analysis::stem;
+use crate::analysis::words;
+use std::collections::HashSet;
+
+/// Words that end in a full stop without ending the sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "apr", "aug", "co", "corp", "dec", "dr", "etc", "feb", "gen", "gov", "inc", "jan", "jr", "jul",
+    "jun", "lt", "ltd", "mar", "mr", "mrs", "ms", "no", "nov", "oct", "prof", "rep", "sen", "sep",
+    "sept", "sgt", "sr", "st", "vs",
+];
+
+/// Shortest sentence that can be part of a summary, in words.
+const MIN_WORDS: usize = 5;
+
+/// Most sentences ranked, later ones are left out to keep long texts quick.
+const MAX_SENTENCES: usize = 200;
+
+/// Probability of following an edge in the ranking, the usual PageRank value.
+const DAMPING: f32 = 0.85;
+
+const MAX_ITERATIONS: usize = 50;
+
+/// Change in every score under which the ranking is done.
+const CONVERGED: f32 = 1e-4;
+
+/// Whether the full stop at the end of `before` ends a sentence.
+fn ends_sentence(before: &str) -> bool {
+    let word = before
+        .rsplit(|c: char| c.is_whitespace() || c == '(' || c == '"')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.');
+
+    // initials like "J. Smith" and "U.S." don't end a sentence
+    let initial = word.split('.').all(|part| part.chars().count() <= 1);
+    !initial && !ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+/// Split text into sentences, line breaks always end a sentence.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            if !matches!(c, '.' | '!' | '?' | '…') {
+                continue;
+            }
+
+            // closing quotes and brackets belong to the sentence they end
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if matches!(next, '"' | '\'' | '”' | '’' | ')' | '.' | '!' | '?') {
+                    end = j + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            let followed_by_space = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if followed_by_space && (c != '.' || ends_sentence(&line[start..i])) {
+                sentences.push(line[start..end].trim());
+                start = end;
+            }
+        }
+
+        sentences.push(line[start..].trim());
+    }
+
+    sentences.retain(|sentence| !sentence.is_empty());
+    sentences
+}
+
+/// Pick the sentences that best summarize a text.
+///
+/// Parameters:
+/// - `text`: plain text, paragraphs separated by line breaks
+/// - `count`: number of sentences to pick
+///
+/// Returns:
+/// - the picked sentences in the order they appear in the text, empty when the text doesn't have
+///   more than `count` sentences worth ranking
+pub fn summarize(text: &str, count: usize) -> Vec<String> {
+    let sentences: Vec<&str> = sentences(text)
+        .into_iter()
+        .filter(|sentence| sentence.split_whitespace().count() >= MIN_WORDS)
+        .take(MAX_SENTENCES)
+        .collect();
+
+    if sentences.len() <= count {
+        return Vec::new();
+    }
+
+    let keys: Vec<HashSet<String>> = sentences
+        .iter()
+        .map(|sentence| {
+            words(sentence)
+                .filter(|word| !is_stopword(word))
+                .map(|word| stem(&word).to_string())
+                .collect()
+        })
+        .collect();
+
+    // similarity of two sentences: shared words, normalized by length so long sentences don't win by size
+    let n = sentences.len();
+    let mut weights = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let shared = keys[i].intersection(&keys[j]).count();
+            let length = (keys[i].len() as f32).ln() + (keys[j].len() as f32).ln();
+            if shared > 0 && length > 0.0 {
+                weights[i][j] = shared as f32 / length;
+                weights[j][i] = weights[i][j];
+            }
+        }
+    }
+    let totals: Vec<f32> = weights.iter().map(|row| row.iter().sum()).collect();
+
+    let mut scores = vec![1.0f32; n];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<f32> = (0..n)
+            .map(|i| {
+                let votes: f32 = (0..n)
+                    .filter(|&j| totals[j] > 0.0)
+                    .map(|j| weights[j][i] / totals[j] * scores[j])
+                    .sum();
+                1.0 - DAMPING + DAMPING * votes
+            })
+            .collect();
+
+        let change = next
+            .iter()
+            .zip(&scores)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max);
+        scores = next;
+        if change < CONVERGED {
+            break;
+        }
+    }
+
+    // best first, earlier sentences win ties
+    let mut ranked: Vec<usize> = (0..n).collect();
+    ranked.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]).then(a.cmp(b)));
+    ranked.truncate(count);
+    ranked.sort_unstable();
+
+    ranked
+        .into_iter()
+        .map(|i| sentences[i].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sentences_around_abbreviations_and_initials() {
+        assert_eq!(
+            sentences(
+                "Mr. Smith met J. R. Doe in the U.S. on Monday. They talked (briefly.) \"Why?\" he asked.\nNew line"
+            ),
+            [
+                "Mr. Smith met J. R. Doe in the U.S. on Monday.",
+                "They talked (briefly.)",
+                "\"Why?\"",
+                "he asked.",
+                "New line",
+            ]
+        );
+        assert_eq!(
+            sentences("Prices rose 2.5 percent."),
+            ["Prices rose 2.5 percent."]
+        );
+    }
+
+    #[test]
+    fn picks_the_central_sentences_in_text_order() {
+        let text = "The city council approved the new transit budget on Tuesday night.\n\
+                    Weather was pleasant for most of the afternoon in the region.\n\
+                    The transit budget funds new buses and longer train service for the city.\n\
+                    A local bakery celebrated its fiftieth anniversary with free bread.\n\
+                    Council members said the transit budget was the largest in city history.";
+
+        assert_eq!(
+            summarize(text, 3),
+            [
+                "The city council approved the new transit budget on Tuesday night.",
+                "The transit budget funds new buses and longer train service for the city.",
+                "Council members said the transit budget was the largest in city history.",
+            ]
+        );
+    }
+
+    #[test]
+    fn short_texts_have_no_summary() {
+        assert!(summarize("Too short. Only two sentences here at all.", 3).is_empty());
+        assert!(summarize("", 1).is_empty());
+    }
+}
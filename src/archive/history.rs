@@ -0,0 +1,70 @@
+use crate::archive::Archive;
+use crate::archive::parse_timestamp;
+use crate::newsapi::NewsAPIError;
+use chrono::DateTime;
+use chrono::Utc;
+use rusqlite::params;
+
+/// Number of history entries loaded, older entries are kept but not shown.
+const HISTORY_LIMIT: usize = 500;
+
+/// A submitted search, de-duplicated by query and sources.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Archive id of the entry
+    pub id: i64,
+    /// Query string, empty for the top headlines
+    pub query: String,
+    /// Comma-separated source ids, empty when unfiltered
+    pub sources: String,
+    /// When the search was last submitted
+    pub searched_at: DateTime<Utc>,
+}
+
+impl Archive {
+    /// List the search history, most recently submitted first.
+    pub fn search_history(&self) -> Result<Vec<HistoryEntry>, NewsAPIError> {
+        Ok(self
+            .conn()
+            .prepare(&format!(
+                "SELECT id, query, sources, searched_at FROM search_history
+                 ORDER BY searched_at DESC LIMIT {HISTORY_LIMIT}"
+            ))?
+            .query_map([], |row| {
+                Ok(HistoryEntry {
+                    id: row.get(0)?,
+                    query: row.get(1)?,
+                    sources: row.get(2)?,
+                    searched_at: parse_timestamp(&row.get::<_, String>(3)?),
+                })
+            })?
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Record a submitted search, an earlier entry with the same query and sources is moved to the top.
+    pub fn record_search(&self, query: &str, sources: &str) -> Result<HistoryEntry, NewsAPIError> {
+        let searched_at = Utc::now();
+
+        let id = self.conn().query_row(
+            "INSERT INTO search_history (query, sources, searched_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(query, sources) DO UPDATE SET searched_at = excluded.searched_at
+             RETURNING id",
+            params![query, sources, searched_at.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+
+        Ok(HistoryEntry {
+            id,
+            query: query.to_string(),
+            sources: sources.to_string(),
+            searched_at,
+        })
+    }
+
+    /// Forget every submitted search.
+    pub fn clear_search_history(&self) -> Result<(), NewsAPIError> {
+        self.conn().execute("DELETE FROM search_history", [])?;
+
+        Ok(())
+    }
+}
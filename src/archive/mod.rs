@@ -0,0 +1,756 @@
+pub mod history;
+mod quota;
+mod reader;
+pub mod saved;
+mod search;
+mod settings;
+pub mod trends;
+pub mod watch;
+
+use crate::newsapi::NewsAPIArticlesSuccess;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::article::Article;
+use crate::newsapi::article::ArticleSource;
+use crate::newsapi::article::canonical_url;
+use crate::newsapi::query::Query;
+use chrono::DateTime;
+use chrono::Utc;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::Row;
+use rusqlite::params;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Schema of the archive database.
+///
+/// `articles` holds one row per canonical url, `fetches` records every response we received
+/// and `fetch_articles` links the two, keeping the order the API returned the articles in.
+/// The remaining tables hold application state: key/value settings, saved searches
+/// with the articles each one has returned, watch terms with the articles they matched,
+/// the number of NewsAPI requests made per key and UTC day, the search history
+/// and the reader mode content extracted from article pages.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS articles (
+    id INTEGER PRIMARY KEY,
+    canonical_url TEXT NOT NULL UNIQUE,
+    source_id TEXT,
+    source_name TEXT,
+    author TEXT,
+    title TEXT NOT NULL,
+    description TEXT,
+    url TEXT,
+    url_to_image TEXT,
+    published_at TEXT,
+    content TEXT,
+    first_fetched_at TEXT NOT NULL,
+    last_fetched_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS fetches (
+    id INTEGER PRIMARY KEY,
+    query TEXT NOT NULL,
+    sources TEXT NOT NULL,
+    fetched_at TEXT NOT NULL,
+    total_results INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS fetch_articles (
+    fetch_id INTEGER NOT NULL REFERENCES fetches(id) ON DELETE CASCADE,
+    article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (fetch_id, article_id)
+);
+CREATE INDEX IF NOT EXISTS articles_source ON articles(source_id);
+CREATE INDEX IF NOT EXISTS articles_published ON articles(published_at);
+CREATE INDEX IF NOT EXISTS fetches_query ON fetches(query, sources);
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    sources TEXT NOT NULL,
+    sort_by TEXT NOT NULL,
+    language TEXT,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS saved_search_articles (
+    saved_id INTEGER NOT NULL REFERENCES saved_searches(id) ON DELETE CASCADE,
+    article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    seen INTEGER NOT NULL,
+    PRIMARY KEY (saved_id, article_id)
+);
+CREATE TABLE IF NOT EXISTS watch_terms (
+    id INTEGER PRIMARY KEY,
+    term TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS alerts (
+    term_id INTEGER NOT NULL REFERENCES watch_terms(id) ON DELETE CASCADE,
+    article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    matched_at TEXT NOT NULL,
+    read INTEGER NOT NULL,
+    PRIMARY KEY (term_id, article_id)
+);
+CREATE TABLE IF NOT EXISTS request_counts (
+    api_key TEXT NOT NULL,
+    day TEXT NOT NULL,
+    interactive INTEGER NOT NULL,
+    background INTEGER NOT NULL,
+    PRIMARY KEY (api_key, day)
+);
+CREATE TABLE IF NOT EXISTS search_history (
+    id INTEGER PRIMARY KEY,
+    query TEXT NOT NULL,
+    sources TEXT NOT NULL,
+    searched_at TEXT NOT NULL,
+    UNIQUE (query, sources)
+);
+CREATE TABLE IF NOT EXISTS reader_articles (
+    article_id INTEGER PRIMARY KEY REFERENCES articles(id) ON DELETE CASCADE,
+    title TEXT,
+    byline TEXT,
+    blocks TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
+";
+
+/// Full-text index over the archived articles, kept in sync with `articles` by triggers.
+///
+/// The index uses `articles` as external content, so the text itself is only stored once.
+const FTS_SCHEMA: &str = "
+CREATE VIRTUAL TABLE IF NOT EXISTS articles_fts USING fts5(
+    title, description, content, author,
+    content = 'articles', content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+CREATE TRIGGER IF NOT EXISTS articles_fts_insert AFTER INSERT ON articles BEGIN
+    INSERT INTO articles_fts (rowid, title, description, content, author)
+    VALUES (new.id, new.title, new.description, new.content, new.author);
+END;
+CREATE TRIGGER IF NOT EXISTS articles_fts_delete AFTER DELETE ON articles BEGIN
+    INSERT INTO articles_fts (articles_fts, rowid, title, description, content, author)
+    VALUES ('delete', old.id, old.title, old.description, old.content, old.author);
+END;
+CREATE TRIGGER IF NOT EXISTS articles_fts_update AFTER UPDATE ON articles BEGIN
+    INSERT INTO articles_fts (articles_fts, rowid, title, description, content, author)
+    VALUES ('delete', old.id, old.title, old.description, old.content, old.author);
+    INSERT INTO articles_fts (rowid, title, description, content, author)
+    VALUES (new.id, new.title, new.description, new.content, new.author);
+END;
+";
+
+/// Columns selected whenever an `Article` is read back, in the order `article_from_row` expects.
+const ARTICLE_COLUMNS: &str = "a.source_id, a.source_name, a.author, a.title, a.description, a.url, a.url_to_image, a.published_at, a.content";
+
+/// Upper bound on the number of articles returned by a single browse query.
+const BROWSE_LIMIT: usize = 500;
+
+/// Number of recent articles checked when a query has to be evaluated locally.
+const SCAN_LIMIT: usize = 5000;
+
+/// Local SQLite archive of every article the application has fetched.
+///
+/// The connection is shared behind a mutex, so the archive can be cheaply cloned into async tasks.
+/// All methods are blocking, call them from `tokio::task::spawn_blocking` when inside a task.
+#[derive(Debug, Clone)]
+pub struct Archive {
+    conn: Arc<Mutex<Connection>>,
+}
+
+/// Ways to browse the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveFilter {
+    /// Articles published on a day, formatted as `YYYY-MM-DD`
+    Date(String),
+    /// Articles from a source, by source id or display name when the id is missing
+    Source(String),
+    /// Articles returned by a query, an empty query means the top headlines
+    Query(String),
+}
+
+/// Overview of the archive contents, used to build the browse menu.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveIndex {
+    /// Publication days with article counts, newest first
+    pub days: Vec<(String, usize)>,
+    /// Sources as (filter key, display name, article count), most articles first
+    pub sources: Vec<(String, String, usize)>,
+    /// Previously run queries with article counts, most recently fetched first
+    pub queries: Vec<(String, usize)>,
+}
+
+/// Directory holding persistent application data.
+///
+/// Falls back to the temporary directory when the platform has no data directory.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("newsapi_demo")
+}
+
+impl Archive {
+    /// Open the archive in the default data directory, creating it if needed.
+    pub fn open() -> Result<Self, NewsAPIError> {
+        let dir = data_dir();
+        std::fs::create_dir_all(&dir)?;
+
+        Self::open_at(dir.join("archive.sqlite3"))
+    }
+
+    /// Open or create an archive database at `path` and apply the schema.
+    pub fn open_at(path: impl AsRef<Path>) -> Result<Self, NewsAPIError> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
+        conn.execute_batch(SCHEMA)?;
+
+        // archives created before the full-text index existed need it built from the stored articles
+        let has_fts: bool = conn.query_row(
+            "SELECT count(*) > 0 FROM sqlite_master WHERE name = 'articles_fts'",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(FTS_SCHEMA)?;
+        if !has_fts {
+            conn.execute(
+                "INSERT INTO articles_fts (articles_fts) VALUES ('rebuild')",
+                [],
+            )?;
+        }
+
+        // canonical urls used to keep tracking parameters, www. and the http scheme,
+        // articles stored back then are re-keyed so new fetches of them match
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < 1 {
+            recanonicalize(&conn)?;
+            conn.execute_batch("PRAGMA user_version = 1")?;
+        }
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // a panic while holding the lock leaves the connection itself usable
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Store a response, de-duplicating articles by canonical url.
+    ///
+    /// The stored articles are also checked against the watch terms, see `watch::match_watch_terms`.
+    ///
+    /// Parameters:
+    /// - `query`: the query the response was fetched with, empty for top headlines
+    /// - `sources`: comma-separated source ids the request was filtered by, empty when unfiltered
+    /// - `data`: the successful response
+    ///
+    /// Returns:
+    /// - the archive ids of the stored articles, in response order
+    pub fn store(
+        &self,
+        query: &str,
+        sources: &str,
+        data: &NewsAPIArticlesSuccess,
+    ) -> Result<Vec<i64>, NewsAPIError> {
+        let now = Utc::now().to_rfc3339();
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO fetches (query, sources, fetched_at, total_results) VALUES (?1, ?2, ?3, ?4)",
+            params![query, sources, now, data.total_results],
+        )?;
+        let fetch_id = tx.last_insert_rowid();
+        let mut ids = Vec::with_capacity(data.articles.len());
+
+        {
+            let mut upsert = tx.prepare(
+                "INSERT INTO articles (canonical_url, source_id, source_name, author, title, description, url, url_to_image, published_at, content, first_fetched_at, last_fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11)
+                 ON CONFLICT(canonical_url) DO UPDATE SET
+                    source_id = coalesce(excluded.source_id, source_id),
+                    source_name = coalesce(excluded.source_name, source_name),
+                    author = coalesce(excluded.author, author),
+                    title = excluded.title,
+                    description = coalesce(excluded.description, description),
+                    url_to_image = coalesce(excluded.url_to_image, url_to_image),
+                    published_at = coalesce(excluded.published_at, published_at),
+                    content = coalesce(excluded.content, content),
+                    last_fetched_at = excluded.last_fetched_at
+                 RETURNING id",
+            )?;
+            let mut link = tx.prepare(
+                "INSERT OR IGNORE INTO fetch_articles (fetch_id, article_id, position) VALUES (?1, ?2, ?3)",
+            )?;
+
+            for (position, article) in data.articles.iter().enumerate() {
+                let id: i64 = upsert.query_row(
+                    params![
+                        archive_key(article),
+                        article.source.id,
+                        article.source.name,
+                        article.author,
+                        article.title,
+                        article.description,
+                        article.url,
+                        article.url_to_image,
+                        article.published_at,
+                        article.content,
+                        now,
+                    ],
+                    |row| row.get(0),
+                )?;
+
+                link.execute(params![fetch_id, id, position as i64])?;
+                ids.push(id);
+            }
+        }
+
+        let stored: Vec<(i64, &Article)> = ids.iter().copied().zip(&data.articles).collect();
+        watch::match_watch_terms(&tx, &stored, &now)?;
+
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    /// Read back archived articles matching `filter`, newest first.
+    ///
+    /// The result is wrapped in a `NewsAPIArticlesSuccess` so it can be shown like any API response.
+    pub fn browse(&self, filter: &ArchiveFilter) -> Result<NewsAPIArticlesSuccess, NewsAPIError> {
+        let conn = self.conn();
+
+        let articles = match filter {
+            ArchiveFilter::Date(day) => query_articles(
+                &conn,
+                &format!(
+                    "SELECT {ARTICLE_COLUMNS} FROM articles a
+                     WHERE substr(coalesce(a.published_at, a.first_fetched_at), 1, 10) = ?1
+                     ORDER BY a.published_at DESC LIMIT {BROWSE_LIMIT}"
+                ),
+                params![day],
+            )?,
+            ArchiveFilter::Source(source) => query_articles(
+                &conn,
+                &format!(
+                    "SELECT {ARTICLE_COLUMNS} FROM articles a
+                     WHERE coalesce(a.source_id, a.source_name) = ?1
+                     ORDER BY a.published_at DESC LIMIT {BROWSE_LIMIT}"
+                ),
+                params![source],
+            )?,
+            ArchiveFilter::Query(query) => query_articles(
+                &conn,
+                &format!(
+                    "SELECT {ARTICLE_COLUMNS} FROM articles a
+                     WHERE a.id IN (
+                        SELECT fa.article_id FROM fetch_articles fa
+                        JOIN fetches f ON f.id = fa.fetch_id
+                        WHERE f.query = ?1
+                     )
+                     ORDER BY a.published_at DESC LIMIT {BROWSE_LIMIT}"
+                ),
+                params![query],
+            )?,
+        };
+
+        Ok(wrap_articles("archive", articles))
+    }
+
+    /// Find cached results for a request, for use when NewsAPI is unavailable.
+    ///
+    /// Looks for the most recent response to the same query and sources, then to the same
+    /// query with any sources, keeping only its articles from the requested sources. When the
+    /// query was never fetched for them, falls back to recently archived articles from the
+    /// sources matching the query, or the newest articles for an empty query.
+    ///
+    /// Returns:
+    /// - `Ok(Some((data, time)))` with the cached articles and when they were fetched
+    /// - `Ok(None)` when nothing in the archive fits the request
+    pub fn cached(
+        &self,
+        query: &str,
+        sources: &str,
+    ) -> Result<Option<(NewsAPIArticlesSuccess, DateTime<Utc>)>, NewsAPIError> {
+        let conn = self.conn();
+
+        let exact: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, fetched_at FROM fetches WHERE query = ?1 AND sources = ?2
+                 ORDER BY fetched_at DESC LIMIT 1",
+                params![query, sources],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let fetch = match exact {
+            Some(fetch) => Some(fetch),
+            None => conn
+                .query_row(
+                    "SELECT id, fetched_at FROM fetches WHERE query = ?1
+                     ORDER BY fetched_at DESC LIMIT 1",
+                    params![query],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?,
+        };
+
+        if let Some((fetch_id, fetched_at)) = fetch {
+            let articles = query_articles(
+                &conn,
+                &format!(
+                    "SELECT {ARTICLE_COLUMNS} FROM fetch_articles fa
+                     JOIN articles a ON a.id = fa.article_id
+                     WHERE fa.fetch_id = ?1 AND {}
+                     ORDER BY fa.position",
+                    source_filter("?2")
+                ),
+                params![fetch_id, sources],
+            )?;
+
+            if !articles.is_empty() {
+                return Ok(Some((
+                    wrap_articles("cache", articles),
+                    parse_timestamp(&fetched_at),
+                )));
+            }
+        }
+
+        // the query was never fetched, evaluate it against the newest archived articles instead
+        let filter = match query.trim() {
+            "" => None,
+            query => match Query::parse(query) {
+                Ok(parsed) => Some(parsed),
+                Err(_) => return Ok(None),
+            },
+        };
+
+        let rows: Vec<(Article, String)> = conn
+            .prepare(&format!(
+                "SELECT {ARTICLE_COLUMNS}, a.last_fetched_at FROM articles a
+                 WHERE {}
+                 ORDER BY a.last_fetched_at DESC LIMIT {SCAN_LIMIT}",
+                source_filter("?1")
+            ))?
+            .query_map(params![sources], |row| {
+                Ok((article_from_row(row)?, row.get(9)?))
+            })?
+            .filter(|row| match (row, &filter) {
+                (Ok((article, _)), Some(filter)) => filter.matches(article),
+                _ => true,
+            })
+            .take(BROWSE_LIMIT)
+            .collect::<Result<_, _>>()?;
+
+        let Some(newest) = rows.first().map(|(_, t)| parse_timestamp(t)) else {
+            return Ok(None);
+        };
+
+        Ok(Some((
+            wrap_articles("archive", rows.into_iter().map(|(a, _)| a).collect()),
+            newest,
+        )))
+    }
+
+    /// Summarize the archive contents for the browse menu.
+    pub fn index(&self) -> Result<ArchiveIndex, NewsAPIError> {
+        let conn = self.conn();
+
+        let days = conn
+            .prepare(
+                "SELECT substr(coalesce(published_at, first_fetched_at), 1, 10) AS day, count(*)
+                 FROM articles GROUP BY day ORDER BY day DESC",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<_, _>>()?;
+
+        let sources = conn
+            .prepare(
+                "SELECT coalesce(source_id, source_name) AS key, coalesce(max(source_name), coalesce(source_id, source_name)), count(*)
+                 FROM articles WHERE coalesce(source_id, source_name) IS NOT NULL
+                 GROUP BY key ORDER BY count(*) DESC",
+            )?
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? as usize))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let queries = conn
+            .prepare(
+                "SELECT f.query, count(DISTINCT fa.article_id)
+                 FROM fetches f LEFT JOIN fetch_articles fa ON fa.fetch_id = f.id
+                 GROUP BY f.query ORDER BY max(f.fetched_at) DESC",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(ArchiveIndex {
+            days,
+            sources,
+            queries,
+        })
+    }
+}
+
+/// Recompute the canonical url of every stored article.
+///
+/// Articles whose new key is already taken by another stored copy keep their old key.
+fn recanonicalize(conn: &Connection) -> Result<(), NewsAPIError> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut select = tx.prepare("SELECT id, url FROM articles WHERE url IS NOT NULL")?;
+        let rows = select
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut update =
+            tx.prepare("UPDATE OR IGNORE articles SET canonical_url = ?1 WHERE id = ?2")?;
+        for (id, url) in rows {
+            update.execute(params![canonical_url(&url), id])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// De-duplication key for an article.
+///
+/// Articles without a url fall back to their source and title, which is the best identity we have.
+fn archive_key(article: &Article) -> String {
+    article.canonical_url().unwrap_or_else(|| {
+        format!(
+            "untitled:{}:{}",
+            article
+                .source
+                .id
+                .as_deref()
+                .or(article.source.name.as_deref())
+                .unwrap_or_default(),
+            article.title
+        )
+    })
+}
+
+/// Build an `Article` from a row selected with `ARTICLE_COLUMNS`.
+/// SQL condition matching `a.source_id` against the comma-separated source ids bound to
+/// `param`, an empty list matches everything.
+fn source_filter(param: &str) -> String {
+    format!("({param} = '' OR instr(',' || {param} || ',', ',' || a.source_id || ',') > 0)")
+}
+
+fn article_from_row(row: &Row<'_>) -> rusqlite::Result<Article> {
+    Ok(Article {
+        source: ArticleSource {
+            id: row.get(0)?,
+            name: row.get(1)?,
+        },
+        author: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        url: row.get(5)?,
+        url_to_image: row.get(6)?,
+        published_at: row.get(7)?,
+        content: row.get(8)?,
+    })
+}
+
+fn query_articles(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<Article>, NewsAPIError> {
+    Ok(conn
+        .prepare(sql)?
+        .query_map(params, article_from_row)?
+        .collect::<Result<_, _>>()?)
+}
+
+/// Parse a timestamp written by the archive, falling back to the current time if it's somehow malformed.
+fn parse_timestamp(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// Wrap locally sourced articles in the API response type used throughout the UI.
+fn wrap_articles(status: &str, articles: Vec<Article>) -> NewsAPIArticlesSuccess {
+    NewsAPIArticlesSuccess {
+        status: status.to_string(),
+        total_results: articles.len() as i32,
+        articles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::newsapi::article::ArticleSource;
+
+    fn article(source: &str, title: &str) -> Article {
+        Article {
+            source: ArticleSource {
+                id: Some(source.to_string()),
+                name: Some(source.to_string()),
+            },
+            author: None,
+            title: title.to_string(),
+            description: None,
+            url: Some(format!(
+                "https://{source}.example/{}",
+                title.replace(' ', "-")
+            )),
+            url_to_image: None,
+            published_at: None,
+            content: None,
+        }
+    }
+
+    #[test]
+    fn cached_fallback_keeps_to_the_requested_sources() {
+        let archive = Archive::open_at(":memory:").unwrap();
+        archive
+            .store(
+                "markets",
+                "",
+                &NewsAPIArticlesSuccess {
+                    status: "ok".to_string(),
+                    total_results: 2,
+                    articles: vec![
+                        article("bbc-news", "Bitcoin falls again"),
+                        article("cnn", "Bitcoin rises again"),
+                    ],
+                },
+            )
+            .unwrap();
+
+        let titles = |query: &str, sources: &str| -> Option<Vec<String>> {
+            let (data, _) = archive.cached(query, sources).unwrap()?;
+            Some(data.articles.into_iter().map(|a| a.title).collect())
+        };
+
+        // the query was never fetched, the archive is scanned
+        assert_eq!(titles("bitcoin", "cnn").unwrap(), ["Bitcoin rises again"]);
+        assert_eq!(titles("bitcoin", "").unwrap().len(), 2);
+        assert!(titles("bitcoin", "reuters").is_none());
+
+        // fetched for any source, only the articles of the requested one are kept
+        assert_eq!(
+            titles("markets", "bbc-news").unwrap(),
+            ["Bitcoin falls again"]
+        );
+        assert_eq!(
+            titles("markets", "bbc-news,cnn").unwrap(),
+            ["Bitcoin falls again", "Bitcoin rises again"]
+        );
+        assert!(titles("markets", "reuters").is_none());
+    }
+
+    fn expression(query: &str) -> Result<String, String> {
+        search::fts_expression(&Query::parse(query).unwrap())
+    }
+
+    #[test]
+    fn translates_queries_into_fts_expressions() {
+        assert_eq!(expression("bitcoin").unwrap(), "\"bitcoin\"");
+        assert_eq!(
+            expression("\"climate change\" policy").unwrap(),
+            "(\"climate change\" AND \"policy\")"
+        );
+        assert_eq!(
+            expression("crypto AND (ethereum OR litecoin)").unwrap(),
+            "(\"crypto\" AND ((\"ethereum\" OR \"litecoin\")))"
+        );
+        // exclusions are gathered into one NOT, + only means the term must appear
+        assert_eq!(
+            expression("+apple -pie NOT \"apple tart\"").unwrap(),
+            "(\"apple\") NOT (\"pie\" OR \"apple tart\")"
+        );
+        // punctuation is quoted rather than read as FTS5 syntax
+        assert_eq!(expression("covid-19").unwrap(), "\"covid-19\"");
+        assert!(expression("-bitcoin").is_err());
+        assert!(expression("a OR -b").is_err());
+    }
+
+    #[test]
+    fn local_search_round_trips_through_the_index() {
+        let archive = Archive::open_at(":memory:").unwrap();
+        archive
+            .store(
+                "",
+                "",
+                &NewsAPIArticlesSuccess {
+                    status: "ok".to_string(),
+                    total_results: 3,
+                    articles: vec![
+                        article("bbc-news", "Climate change policy unveiled"),
+                        article("cnn", "Apple tart recipe for the holidays"),
+                        article("cnn", "Apple shares climb on record sales"),
+                    ],
+                },
+            )
+            .unwrap();
+
+        let titles = |query: &str, sources: &str| -> Vec<String> {
+            let mut titles: Vec<String> = archive
+                .search(query, sources)
+                .unwrap()
+                .articles
+                .into_iter()
+                .map(|a| a.title)
+                .collect();
+            titles.sort();
+            titles
+        };
+
+        assert_eq!(
+            titles("\"climate change\"", ""),
+            ["Climate change policy unveiled"]
+        );
+        assert!(titles("\"change climate\"", "").is_empty());
+        assert_eq!(
+            titles("apple -\"apple tart\"", ""),
+            ["Apple shares climb on record sales"]
+        );
+        assert_eq!(
+            titles("tart OR policy", ""),
+            [
+                "Apple tart recipe for the holidays",
+                "Climate change policy unveiled"
+            ]
+        );
+        assert_eq!(titles("", "bbc-news"), ["Climate change policy unveiled"]);
+        assert!(titles("climate", "cnn").is_empty());
+        assert!(matches!(
+            archive.search("-apple", ""),
+            Err(NewsAPIError::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn only_fts_syntax_errors_blame_the_query() {
+        let failure = |code, message: &str| {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(code),
+                Some(message.to_string()),
+            )
+        };
+
+        assert!(matches!(
+            search::fts_error(failure(1, "fts5: syntax error near \"AND\"")),
+            NewsAPIError::InvalidQuery(_)
+        ));
+        assert!(matches!(
+            search::fts_error(failure(5, "database is locked")),
+            NewsAPIError::Sqlite(_)
+        ));
+        assert!(matches!(
+            search::fts_error(failure(11, "database disk image is malformed")),
+            NewsAPIError::Sqlite(_)
+        ));
+    }
+}
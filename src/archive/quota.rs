@@ -0,0 +1,81 @@
+use crate::archive::Archive;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::quota::QuotaUsage;
+use crate::newsapi::quota::RequestKind;
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+
+impl Archive {
+    /// Requests made today with an API key.
+    ///
+    /// Parameters:
+    /// - `key_id`: the key, as returned by `quota::key_id`
+    pub fn quota_usage(&self, key_id: &str) -> Result<QuotaUsage, NewsAPIError> {
+        let day = Utc::now().date_naive();
+
+        let counts: Option<(u32, u32)> = self
+            .conn()
+            .query_row(
+                "SELECT interactive, background FROM request_counts WHERE api_key = ?1 AND day = ?2",
+                params![key_id, day.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let (interactive, background) = counts.unwrap_or_default();
+        Ok(QuotaUsage {
+            day,
+            interactive,
+            background,
+        })
+    }
+
+    /// Count a request made with an API key against today's quota.
+    ///
+    /// Returns:
+    /// - today's usage including the recorded request
+    pub fn record_request(
+        &self,
+        key_id: &str,
+        kind: RequestKind,
+    ) -> Result<QuotaUsage, NewsAPIError> {
+        self.add_requests(key_id, kind, 1)
+    }
+
+    /// Take back a request counted with `record_request` that turned out to never reach NewsAPI.
+    pub fn refund_request(
+        &self,
+        key_id: &str,
+        kind: RequestKind,
+    ) -> Result<QuotaUsage, NewsAPIError> {
+        self.add_requests(key_id, kind, -1)
+    }
+
+    fn add_requests(
+        &self,
+        key_id: &str,
+        kind: RequestKind,
+        delta: i64,
+    ) -> Result<QuotaUsage, NewsAPIError> {
+        let (interactive, background) = match kind {
+            RequestKind::Interactive => (delta, 0),
+            RequestKind::Background => (0, delta),
+        };
+
+        self.conn().execute(
+            "INSERT INTO request_counts (api_key, day, interactive, background) VALUES (?1, ?2, max(?3, 0), max(?4, 0))
+             ON CONFLICT(api_key, day) DO UPDATE SET
+                interactive = max(interactive + ?3, 0),
+                background = max(background + ?4, 0)",
+            params![
+                key_id,
+                Utc::now().date_naive().to_string(),
+                interactive,
+                background
+            ],
+        )?;
+
+        self.quota_usage(key_id)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::archive::Archive;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::article::canonical_url;
+use crate::reader::ReaderArticle;
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+
+impl Archive {
+    /// Reader mode content extracted earlier from an article's page.
+    ///
+    /// Parameters:
+    /// - `url`: the article url
+    ///
+    /// Returns:
+    /// - None when the article was never opened in reader mode
+    pub fn reader_article(&self, url: &str) -> Result<Option<ReaderArticle>, NewsAPIError> {
+        let row: Option<(Option<String>, Option<String>, String)> = self
+            .conn()
+            .query_row(
+                "SELECT r.title, r.byline, r.blocks FROM reader_articles r
+                 JOIN articles a ON a.id = r.article_id
+                 WHERE a.canonical_url = ?1",
+                params![canonical_url(url)],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        row.map(|(title, byline, blocks)| {
+            Ok(ReaderArticle {
+                title,
+                byline,
+                blocks: serde_json::from_str(&blocks)?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Keep reader mode content with its article, replacing content stored before.
+    ///
+    /// Nothing is stored when the article isn't in the archive.
+    pub fn store_reader_article(
+        &self,
+        url: &str,
+        article: &ReaderArticle,
+    ) -> Result<(), NewsAPIError> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO reader_articles (article_id, title, byline, blocks, fetched_at)
+             SELECT id, ?2, ?3, ?4, ?5 FROM articles WHERE canonical_url = ?1",
+            params![
+                canonical_url(url),
+                article.title,
+                article.byline,
+                serde_json::to_string(&article.blocks)?,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(())
+    }
+}
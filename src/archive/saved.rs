@@ -0,0 +1,154 @@
+use crate::archive::Archive;
+use crate::newsapi::EverythingParams;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::SortBy;
+use chrono::Utc;
+use rusqlite::params;
+
+/// A named search the user follows, refreshed periodically in the background.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearch {
+    /// Archive id of the saved search
+    pub id: i64,
+    /// Display name
+    pub name: String,
+    /// Query string, empty for the top headlines
+    pub query: String,
+    /// Comma-separated source ids, empty when unfiltered
+    pub sources: String,
+    /// Everything endpoint parameters
+    pub params: EverythingParams,
+    /// Articles returned by refreshes since the search was last viewed
+    pub unseen: usize,
+}
+
+impl Archive {
+    /// List saved searches in the order they were created, with their unseen article counts.
+    pub fn saved_searches(&self) -> Result<Vec<SavedSearch>, NewsAPIError> {
+        Ok(self
+            .conn()
+            .prepare(
+                "SELECT s.id, s.name, s.query, s.sources, s.sort_by, s.language,
+                    (SELECT count(*) FROM saved_search_articles sa WHERE sa.saved_id = s.id AND sa.seen = 0)
+                 FROM saved_searches s ORDER BY s.created_at",
+            )?
+            .query_map([], |row| {
+                Ok(SavedSearch {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    query: row.get(2)?,
+                    sources: row.get(3)?,
+                    params: EverythingParams {
+                        sort_by: SortBy::from_param(&row.get::<_, String>(4)?),
+                        language: row.get(5)?,
+                    },
+                    unseen: row.get::<_, i64>(6)? as usize,
+                })
+            })?
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Save a search.
+    ///
+    /// The articles from the latest archived response to the same query and sources are
+    /// recorded as already seen, so only articles found by later refreshes count as new.
+    pub fn save_search(
+        &self,
+        name: &str,
+        query: &str,
+        sources: &str,
+        params: &EverythingParams,
+    ) -> Result<SavedSearch, NewsAPIError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO saved_searches (name, query, sources, sort_by, language, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                name,
+                query,
+                sources,
+                params.sort_by.as_param(),
+                params.language,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        tx.execute(
+            "INSERT OR IGNORE INTO saved_search_articles (saved_id, article_id, seen)
+             SELECT ?1, fa.article_id, 1 FROM fetch_articles fa
+             WHERE fa.fetch_id = (
+                SELECT id FROM fetches WHERE query = ?2 AND sources = ?3
+                ORDER BY fetched_at DESC LIMIT 1
+             )",
+            params![id, query, sources],
+        )?;
+
+        tx.commit()?;
+
+        Ok(SavedSearch {
+            id,
+            name: name.to_string(),
+            query: query.to_string(),
+            sources: sources.to_string(),
+            params: params.clone(),
+            unseen: 0,
+        })
+    }
+
+    /// Delete a saved search and its record of returned articles.
+    pub fn delete_saved_search(&self, id: i64) -> Result<(), NewsAPIError> {
+        self.conn()
+            .execute("DELETE FROM saved_searches WHERE id = ?1", params![id])?;
+
+        Ok(())
+    }
+
+    /// Record articles returned for a saved search.
+    ///
+    /// Parameters:
+    /// - `id`: the saved search
+    /// - `article_ids`: archive ids of the returned articles, as returned by `store`
+    /// - `seen`: whether the user is looking at these results right now
+    ///
+    /// Returns:
+    /// - the number of unseen articles for the saved search afterwards
+    pub fn record_saved_results(
+        &self,
+        id: i64,
+        article_ids: &[i64],
+        seen: bool,
+    ) -> Result<usize, NewsAPIError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO saved_search_articles (saved_id, article_id, seen) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(saved_id, article_id) DO UPDATE SET seen = max(seen, excluded.seen)",
+            )?;
+
+            for article_id in article_ids {
+                insert.execute(params![id, article_id, seen])?;
+            }
+        }
+
+        if seen {
+            tx.execute(
+                "UPDATE saved_search_articles SET seen = 1 WHERE saved_id = ?1",
+                params![id],
+            )?;
+        }
+
+        let unseen: i64 = tx.query_row(
+            "SELECT count(*) FROM saved_search_articles WHERE saved_id = ?1 AND seen = 0",
+            params![id],
+            |row| row.get(0),
+        )?;
+
+        tx.commit()?;
+        Ok(unseen as usize)
+    }
+}
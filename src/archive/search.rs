@@ -0,0 +1,144 @@
+use crate::archive::ARTICLE_COLUMNS;
+use crate::archive::Archive;
+use crate::archive::BROWSE_LIMIT;
+use crate::archive::query_articles;
+use crate::archive::source_filter;
+use crate::archive::wrap_articles;
+use crate::newsapi::NewsAPIArticlesSuccess;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::query::Query;
+use rusqlite::params;
+
+/// Quote a word or phrase as an FTS5 string, so punctuation in it isn't read as syntax.
+fn fts_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Translate a parsed query into an FTS5 match expression.
+///
+/// FTS5 has no unary exclusion, so the excluded and NOT parts of each AND are gathered
+/// into a single `NOT (...)` applied to the rest of it.
+///
+/// Returns:
+/// - `Ok(expression)` ready for `MATCH`
+/// - `Err(message)` for exclusions with nothing to exclude them from, like `-bitcoin` on its own
+pub(super) fn fts_expression(query: &Query) -> Result<String, String> {
+    match query {
+        Query::Term(text) | Query::Phrase(text) => Ok(fts_string(text)),
+        Query::Required(inner) => fts_expression(inner),
+        Query::Group(inner) => Ok(format!("({})", fts_expression(inner)?)),
+        Query::Or(parts) => Ok(format!(
+            "({})",
+            parts
+                .iter()
+                .map(fts_expression)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" OR ")
+        )),
+        Query::And(parts) => {
+            let (excluded, included): (Vec<&Query>, Vec<&Query>) = parts
+                .iter()
+                .partition(|p| matches!(p, Query::Excluded(_) | Query::Not(_)));
+
+            if included.is_empty() {
+                return Err(ONLY_EXCLUDED.into());
+            }
+
+            let included = included
+                .into_iter()
+                .map(fts_expression)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" AND ");
+
+            if excluded.is_empty() {
+                return Ok(format!("({included})"));
+            }
+
+            let excluded = excluded
+                .into_iter()
+                .map(|p| match p {
+                    Query::Excluded(inner) | Query::Not(inner) => fts_expression(inner),
+                    p => fts_expression(p),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" OR ");
+
+            Ok(format!("({included}) NOT ({excluded})"))
+        }
+        Query::Excluded(_) | Query::Not(_) => Err(ONLY_EXCLUDED.into()),
+    }
+}
+
+/// Turn an error running a match expression into the error to report.
+///
+/// Only FTS5 rejecting the expression means the query is at fault, a locked or corrupt
+/// database is reported as the archive error it is.
+pub(super) fn fts_error(error: rusqlite::Error) -> NewsAPIError {
+    match &error {
+        rusqlite::Error::SqliteFailure(_, Some(message))
+            if message.contains("fts5: syntax error") =>
+        {
+            NewsAPIError::InvalidQuery(message.clone())
+        }
+        _ => NewsAPIError::Sqlite(error),
+    }
+}
+
+const ONLY_EXCLUDED: &str =
+    "Local search needs a term that must appear next to every excluded term";
+
+impl Archive {
+    /// Search archived titles, descriptions, contents and authors with the full-text index.
+    ///
+    /// Parameters:
+    /// - `query`: query in the same syntax NewsAPI accepts, an empty query lists the newest articles
+    /// - `sources`: comma-separated source ids to restrict the search to, empty for all sources
+    ///
+    /// Returns:
+    /// - the matching articles, best match first, wrapped like an API response
+    /// - `Err(NewsAPIError::InvalidQuery)` when the query doesn't parse, can't be translated or FTS5 rejects it
+    pub fn search(
+        &self,
+        query: &str,
+        sources: &str,
+    ) -> Result<NewsAPIArticlesSuccess, NewsAPIError> {
+        let conn = self.conn();
+
+        if query.trim().is_empty() {
+            let articles = query_articles(
+                &conn,
+                &format!(
+                    "SELECT {ARTICLE_COLUMNS} FROM articles a
+                     WHERE {}
+                     ORDER BY a.published_at DESC LIMIT {BROWSE_LIMIT}",
+                    source_filter("?1")
+                ),
+                params![sources],
+            )?;
+
+            return Ok(wrap_articles("local", articles));
+        }
+
+        let parsed = Query::parse(query).map_err(|e| NewsAPIError::InvalidQuery(e.to_string()))?;
+        let expression = fts_expression(&parsed).map_err(NewsAPIError::InvalidQuery)?;
+
+        // title matches weigh the most, then description, author and content
+        let articles = query_articles(
+            &conn,
+            &format!(
+                "SELECT {ARTICLE_COLUMNS} FROM articles_fts
+                 JOIN articles a ON a.id = articles_fts.rowid
+                 WHERE articles_fts MATCH ?1 AND {}
+                 ORDER BY bm25(articles_fts, 10.0, 4.0, 1.0, 2.0) LIMIT {BROWSE_LIMIT}",
+                source_filter("?2")
+            ),
+            params![expression, sources],
+        )
+        .map_err(|e| match e {
+            NewsAPIError::Sqlite(e) => fts_error(e),
+            e => e,
+        })?;
+
+        Ok(wrap_articles("local", articles))
+    }
+}
@@ -0,0 +1,29 @@
+use crate::archive::Archive;
+use crate::newsapi::NewsAPIError;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+
+impl Archive {
+    /// Read a persisted setting, None when it was never set.
+    pub fn setting(&self, key: &str) -> Result<Option<String>, NewsAPIError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Persist a setting, replacing any previous value.
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<(), NewsAPIError> {
+        self.conn().execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+
+        Ok(())
+    }
+}
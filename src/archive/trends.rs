@@ -0,0 +1,117 @@
+use crate::archive::Archive;
+use crate::archive::search::fts_error;
+use crate::archive::search::fts_expression;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::query::Query;
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use rusqlite::params;
+use std::fmt;
+
+/// Length of the time buckets articles are counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrendInterval {
+    Hour,
+    #[default]
+    Day,
+}
+
+impl TrendInterval {
+    pub const ALL: [TrendInterval; 2] = [TrendInterval::Hour, TrendInterval::Day];
+
+    /// `strftime` format truncating a local time to the start of its bucket.
+    fn bucket_format(&self) -> &'static str {
+        match self {
+            TrendInterval::Hour => "%Y-%m-%d %H:00",
+            TrendInterval::Day => "%Y-%m-%d 00:00",
+        }
+    }
+}
+
+impl fmt::Display for TrendInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrendInterval::Hour => "Per hour",
+            TrendInterval::Day => "Per day",
+        })
+    }
+}
+
+/// Number of archived articles from one source matching a term within one time bucket.
+#[derive(Debug, Clone)]
+pub struct TermCount {
+    /// Start of the bucket, in local time
+    pub bucket: NaiveDateTime,
+    /// Name of the source, or its id when the name is missing
+    pub source: String,
+    /// Matching articles
+    pub count: usize,
+}
+
+impl Archive {
+    /// Count archived articles matching a term, per source and time bucket.
+    ///
+    /// Parameters:
+    /// - `term`: the term, in the same syntax as searches
+    /// - `since`: articles published before this are left out
+    /// - `interval`: length of the time buckets, which start at local hours or midnights
+    ///
+    /// Returns:
+    /// - the non-empty buckets, oldest first. Articles without a publishing time aren't counted.
+    /// - `Err(NewsAPIError::InvalidQuery)` when the term doesn't parse or can't be searched locally
+    pub fn term_counts(
+        &self,
+        term: &str,
+        since: DateTime<Utc>,
+        interval: TrendInterval,
+    ) -> Result<Vec<TermCount>, NewsAPIError> {
+        let parsed = Query::parse(term).map_err(|e| NewsAPIError::InvalidQuery(e.to_string()))?;
+        let expression = fts_expression(&parsed).map_err(NewsAPIError::InvalidQuery)?;
+
+        let conn = self.conn();
+        let mut statement = conn.prepare(
+            "SELECT strftime(?3, a.published_at, 'localtime') AS bucket,
+                coalesce(a.source_name, a.source_id, 'Unknown source') AS source,
+                count(*)
+             FROM articles_fts
+             JOIN articles a ON a.id = articles_fts.rowid
+             WHERE articles_fts MATCH ?1 AND julianday(a.published_at) >= julianday(?2)
+             GROUP BY bucket, source
+             ORDER BY bucket",
+        )?;
+
+        let rows = statement
+            .query_map(
+                params![
+                    expression,
+                    since.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    interval.bucket_format()
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, usize>(2)?,
+                    ))
+                },
+            )
+            .map_err(fts_error)?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            let (bucket, source, count) = row.map_err(fts_error)?;
+
+            if let Ok(bucket) = NaiveDateTime::parse_from_str(&bucket, "%Y-%m-%d %H:%M") {
+                counts.push(TermCount {
+                    bucket,
+                    source,
+                    count,
+                });
+            }
+        }
+
+        Ok(counts)
+    }
+}
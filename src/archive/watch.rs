@@ -0,0 +1,139 @@
+use crate::archive::ARTICLE_COLUMNS;
+use crate::archive::Archive;
+use crate::archive::article_from_row;
+use crate::archive::parse_timestamp;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::article::Article;
+use crate::newsapi::query::Query;
+use chrono::DateTime;
+use chrono::Utc;
+use rusqlite::Transaction;
+use rusqlite::params;
+
+/// A watch term, matched against every fetched article.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchTerm {
+    /// Archive id of the term
+    pub id: i64,
+    /// The term, in the query syntax
+    pub term: String,
+    /// Matched articles that haven't been looked at yet
+    pub unread: usize,
+}
+
+/// An article that matched a watch term.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    /// The matched article
+    pub article: Article,
+    /// When the match was found
+    pub matched_at: DateTime<Utc>,
+    /// Whether the alert has been looked at
+    pub read: bool,
+}
+
+/// Check freshly stored articles against every watch term and record the matches.
+///
+/// Called by `Archive::store` inside its transaction, so any fetch, interactive or background,
+/// is matched without extra requests. Terms that no longer parse are skipped.
+pub(super) fn match_watch_terms(
+    tx: &Transaction<'_>,
+    stored: &[(i64, &Article)],
+    now: &str,
+) -> Result<(), NewsAPIError> {
+    let terms: Vec<(i64, Query)> = tx
+        .prepare("SELECT id, term FROM watch_terms")?
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?
+        .filter_map(|row| match row {
+            Ok((id, term)) => Query::parse(&term).ok().map(|query| Ok((id, query))),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    if terms.is_empty() {
+        return Ok(());
+    }
+
+    let mut insert = tx.prepare(
+        "INSERT OR IGNORE INTO alerts (term_id, article_id, matched_at, read) VALUES (?1, ?2, ?3, 0)",
+    )?;
+
+    for (article_id, article) in stored {
+        for (term_id, query) in &terms {
+            if query.matches(article) {
+                insert.execute(params![term_id, article_id, now])?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Archive {
+    /// List watch terms in the order they were added, with their unread alert counts.
+    pub fn watch_terms(&self) -> Result<Vec<WatchTerm>, NewsAPIError> {
+        Ok(self
+            .conn()
+            .prepare(
+                "SELECT w.id, w.term,
+                    (SELECT count(*) FROM alerts a WHERE a.term_id = w.id AND a.read = 0)
+                 FROM watch_terms w ORDER BY w.id",
+            )?
+            .query_map([], |row| {
+                Ok(WatchTerm {
+                    id: row.get(0)?,
+                    term: row.get(1)?,
+                    unread: row.get::<_, i64>(2)? as usize,
+                })
+            })?
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Add a watch term, the caller is expected to have validated it with `Query::parse`.
+    pub fn add_watch_term(&self, term: &str) -> Result<(), NewsAPIError> {
+        self.conn().execute(
+            "INSERT OR IGNORE INTO watch_terms (term) VALUES (?1)",
+            params![term],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a watch term together with its alerts.
+    pub fn remove_watch_term(&self, id: i64) -> Result<(), NewsAPIError> {
+        self.conn()
+            .execute("DELETE FROM watch_terms WHERE id = ?1", params![id])?;
+
+        Ok(())
+    }
+
+    /// Alerts for a watch term, newest match first.
+    pub fn alerts(&self, term_id: i64) -> Result<Vec<Alert>, NewsAPIError> {
+        Ok(self
+            .conn()
+            .prepare(&format!(
+                "SELECT {ARTICLE_COLUMNS}, al.matched_at, al.read FROM alerts al
+                 JOIN articles a ON a.id = al.article_id
+                 WHERE al.term_id = ?1
+                 ORDER BY al.matched_at DESC, a.published_at DESC"
+            ))?
+            .query_map(params![term_id], |row| {
+                Ok(Alert {
+                    article: article_from_row(row)?,
+                    matched_at: parse_timestamp(&row.get::<_, String>(9)?),
+                    read: row.get(10)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Mark every alert of a watch term as read.
+    pub fn mark_alerts_read(&self, term_id: i64) -> Result<(), NewsAPIError> {
+        self.conn().execute(
+            "UPDATE alerts SET read = 1 WHERE term_id = ?1",
+            params![term_id],
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,18 @@
-use crate::newsapi::fetch_top;
+use crate::ui::APP_FONT;
 use crate::ui::App;
-use iced::Size;
 
+mod analysis;
+mod archive;
 mod newsapi;
+mod reader;
 mod ui;
 
 pub const DEFAULT_SIZE: (f32, f32) = (800.0, 600.0);
 
 fn main() -> iced::Result {
-    iced::application("NewsAPI Demo", App::update, App::view)
+    // the main window is opened by App::new, articles can be popped out into more windows
+    iced::daemon(App::title, App::update, App::view)
         .subscription(App::subscription)
-        .window(iced::window::Settings {
-            size: Size::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1),
-            ..Default::default()
-        })
         .settings(iced::Settings {
             antialiasing: true,
             ..Default::default()
@@ -24,6 +23,6 @@ fn main() -> iced::Result {
         .font(include_bytes!(
             "../assets/NotoSansArabic-VariableFont_wdth,wght.ttf"
         ))
-        .default_font(iced::Font::with_name("Roboto"))
+        .default_font(APP_FONT)
         .run_with(App::new)
 }
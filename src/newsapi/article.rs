@@ -33,6 +33,149 @@ pub struct Article {
     #[serde(rename(deserialize = "publishedAt"))]
     pub published_at: Option<String>,
     /// Truncated portion of the content of the article
-    /// This may contain html formatting
+    /// This may contain html formatting, `html::content_blocks` turns it into styled text
     pub content: Option<String>,
 }
+
+impl Article {
+    /// Canonical form of the article url, used as the de-duplication key in the archive.
+    ///
+    /// Returns None when the article has no url.
+    pub fn canonical_url(&self) -> Option<String> {
+        self.url.as_deref().map(canonical_url)
+    }
+
+    /// Title without the " - Source" suffix top headlines add to it.
+    pub fn bare_title(&self) -> &str {
+        self.source
+            .name
+            .as_deref()
+            .and_then(|source| self.title.strip_suffix(source))
+            .and_then(|title| title.trim_end().strip_suffix(['-', '|', '–']))
+            .map(str::trim_end)
+            .unwrap_or(&self.title)
+    }
+}
+
+/// Query parameters added by newsletters, social networks and ad platforms to track where a visit came from.
+/// Parameters starting with `utm_` are removed as well.
+const TRACKING_PARAMS: &[&str] = &[
+    "_ga",
+    "_gl",
+    "cmpid",
+    "dclid",
+    "fbclid",
+    "gclid",
+    "guccounter",
+    "icid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "msclkid",
+    "ns_campaign",
+    "ns_mchannel",
+    "ns_source",
+    "ocid",
+    "ref",
+    "ref_src",
+    "s_cid",
+    "smid",
+    "spm",
+    "taid",
+    "yclid",
+];
+
+/// Whether a query parameter only tracks where a visit came from.
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+/// Normalize an article url so the same article fetched through different queries maps to the same key.
+///
+/// The fragment, tracking parameters, a leading "www." and any trailing slash are removed, http becomes
+/// https and the remaining query parameters are sorted. The scheme and host are lowercased by the url parser.
+/// Urls that fail to parse are returned trimmed but otherwise untouched.
+pub fn canonical_url(url: &str) -> String {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+
+            if parsed.scheme() == "http" {
+                // http and https only differ in the default port, which the parser leaves out
+                let _ = parsed.set_scheme("https");
+            }
+            if let Some(host) = parsed.host_str().and_then(|h| h.strip_prefix("www.")) {
+                let host = host.to_string();
+                let _ = parsed.set_host(Some(&host));
+            }
+
+            let mut params: Vec<(String, String)> = parsed
+                .query_pairs()
+                .filter(|(name, _)| !is_tracking_param(name))
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect();
+            params.sort();
+            if params.is_empty() {
+                parsed.set_query(None);
+            } else {
+                parsed.query_pairs_mut().clear().extend_pairs(params);
+            }
+
+            let path = parsed.path().trim_end_matches('/').to_string();
+            parsed.set_path(&path);
+
+            let mut canonical = parsed.to_string();
+            while canonical.ends_with('/') {
+                canonical.pop();
+            }
+
+            canonical
+        }
+        Err(_) => url.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_url_drops_tracking_and_normalizes() {
+        assert_eq!(
+            canonical_url(
+                " http://WWW.Example.com/world/story/?utm_source=twitter&b=2&fbclid=x&a=1#comments "
+            ),
+            "https://example.com/world/story?a=1&b=2"
+        );
+        assert_eq!(
+            canonical_url("https://example.com/story?UTM_Medium=email"),
+            canonical_url("http://www.example.com/story/")
+        );
+        // parameters that pick the article are kept
+        assert_ne!(
+            canonical_url("https://example.com/article?id=1"),
+            canonical_url("https://example.com/article?id=2")
+        );
+        assert_eq!(canonical_url(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn bare_title_strips_the_source_suffix() {
+        let article = Article {
+            source: ArticleSource {
+                id: None,
+                name: Some("BBC News".to_string()),
+            },
+            author: None,
+            title: "Markets rally on rate cut - BBC News".to_string(),
+            description: None,
+            url: None,
+            url_to_image: None,
+            published_at: None,
+            content: None,
+        };
+
+        assert_eq!(article.bare_title(), "Markets rally on rate cut");
+    }
+}
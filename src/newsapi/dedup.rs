@@ -0,0 +1,232 @@
+//! Detection of duplicate articles in a result list.
+//!
+//! `everything` results often hold the same article more than once: under urls that only differ
+//! in tracking parameters, or syndicated to many sites with the same title and description.
+
+use crate::newsapi::article::Article;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Fewest words a title needs for its fingerprint to count, short titles like "Live updates" repeat too often.
+const MIN_TITLE_WORDS: usize = 4;
+
+/// Fewest words a description needs to identify an article on its own.
+const MIN_DESCRIPTION_WORDS: usize = 12;
+
+/// How duplicates are shown in the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateMode {
+    /// Grouped with the first copy, like other versions of a story
+    #[default]
+    Collapse,
+    /// Left out of the results
+    Hide,
+}
+
+impl DuplicateMode {
+    pub const ALL: [DuplicateMode; 2] = [DuplicateMode::Collapse, DuplicateMode::Hide];
+
+    /// Value the mode is stored under in the settings.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            DuplicateMode::Collapse => "collapse",
+            DuplicateMode::Hide => "hide",
+        }
+    }
+
+    /// Parse a stored setting, unknown values fall back to the default.
+    pub fn from_param(param: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_param() == param)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for DuplicateMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DuplicateMode::Collapse => "Group duplicates",
+            DuplicateMode::Hide => "Hide duplicates",
+        })
+    }
+}
+
+/// Hash of the words of a text, ignoring case, punctuation and spacing.
+///
+/// Returns None when the text has fewer than `min_words` words.
+fn fingerprint(text: &str, min_words: usize) -> Option<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.len() < min_words {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    words.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Find the articles that duplicate an earlier article in the list.
+///
+/// An article is a duplicate when its canonical url matches, when its title matches and its
+/// description matches or is missing on either side, or when it has the same long description.
+///
+/// Parameters:
+/// - `articles`: the result list
+///
+/// Returns:
+/// - for every article the index of the first article it duplicates, None for originals
+pub fn find_duplicates(articles: &[Article]) -> Vec<Option<usize>> {
+    let mut urls: HashMap<String, usize> = HashMap::new();
+    let mut titles: HashMap<u64, Vec<(usize, Option<u64>)>> = HashMap::new();
+    let mut descriptions: HashMap<u64, usize> = HashMap::new();
+
+    articles
+        .iter()
+        .enumerate()
+        .map(|(i, article)| {
+            let description = article.description.as_deref().unwrap_or_default();
+            let title = fingerprint(article.bare_title(), MIN_TITLE_WORDS);
+            let short_description = fingerprint(description, 1);
+            let long_description = fingerprint(description, MIN_DESCRIPTION_WORDS);
+
+            let by_url = article
+                .canonical_url()
+                .and_then(|url| match urls.entry(url) {
+                    Entry::Occupied(first) => Some(*first.get()),
+                    Entry::Vacant(entry) => {
+                        entry.insert(i);
+                        None
+                    }
+                });
+
+            let by_title = title.and_then(|title| {
+                let earlier = titles.entry(title).or_default();
+                let first = earlier
+                    .iter()
+                    .find(|(_, other)| {
+                        short_description.is_none()
+                            || other.is_none()
+                            || *other == short_description
+                    })
+                    .map(|(first, _)| *first);
+                earlier.push((i, short_description));
+                first
+            });
+
+            let by_description =
+                long_description.and_then(|description| match descriptions.entry(description) {
+                    Entry::Occupied(first) => Some(*first.get()),
+                    Entry::Vacant(entry) => {
+                        entry.insert(i);
+                        None
+                    }
+                });
+
+            [by_url, by_title, by_description]
+                .into_iter()
+                .flatten()
+                .min()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::newsapi::article::ArticleSource;
+
+    fn article(source: &str, title: &str, description: Option<&str>, url: &str) -> Article {
+        Article {
+            source: ArticleSource {
+                id: None,
+                name: Some(source.to_string()),
+            },
+            author: None,
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            url: Some(url.to_string()),
+            url_to_image: None,
+            published_at: None,
+            content: None,
+        }
+    }
+
+    #[test]
+    fn finds_syndicated_copies() {
+        let description = "The central bank raised its key interest rate by half a point on Thursday, \
+                           the largest increase in two decades.";
+        let articles = [
+            article(
+                "AP",
+                "Central bank makes its largest rate hike in decades",
+                Some(description),
+                "https://apnews.com/article/rates",
+            ),
+            // same url with tracking parameters
+            article(
+                "AP",
+                "Central bank makes its largest rate hike in decades",
+                Some(description),
+                "https://www.apnews.com/article/rates?utm_source=feed",
+            ),
+            // syndicated under another title
+            article(
+                "Local Herald",
+                "Rates go up again",
+                Some(description),
+                "https://herald.example/business/rates",
+            ),
+            // syndicated with the title but without a description
+            article(
+                "Daily Times",
+                "Central Bank Makes Its Largest Rate Hike in Decades!",
+                None,
+                "https://times.example/rates",
+            ),
+            article(
+                "Reuters",
+                "Volcano erupts in Iceland, flights grounded",
+                Some("Ash clouds closed airports across northern Europe."),
+                "https://reuters.example/volcano",
+            ),
+        ];
+
+        assert_eq!(
+            find_duplicates(&articles),
+            vec![None, Some(0), Some(0), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn different_stories_with_a_common_title_are_kept() {
+        let articles = [
+            article(
+                "AP",
+                "What we know so far about the storm",
+                Some("The storm made landfall in Florida overnight."),
+                "https://apnews.com/article/storm",
+            ),
+            article(
+                "CNN",
+                "What we know so far about the storm",
+                Some("Forecasters expect the storm to weaken over Texas."),
+                "https://cnn.example/storm",
+            ),
+            // too short to tell apart from other live coverage
+            article("BBC News", "Live updates", None, "https://bbc.example/live"),
+            article("CNN", "Live updates", None, "https://cnn.example/live"),
+        ];
+
+        assert_eq!(find_duplicates(&articles), vec![None; 4]);
+    }
+}
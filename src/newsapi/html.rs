@@ -0,0 +1,469 @@
+//! Conversion of the HTML NewsAPI puts in article content into styled text blocks.
+//!
+//! Entities are decoded by the HTML parser. Scripts, styles, embedded frames, hidden
+//! elements and tracking pixels are dropped, links are only kept when they lead to a
+//! web page or mail address so nothing else can be opened from article content.
+
+use reqwest::Url;
+use scraper::ElementRef;
+use scraper::Html;
+use scraper::Node;
+
+/// Elements left out together with everything in them.
+const SKIPPED_TAGS: &[&str] = &[
+    "button", "embed", "form", "head", "iframe", "input", "link", "meta", "noscript", "object",
+    "script", "select", "style", "svg", "template", "textarea", "title",
+];
+
+/// Elements that start a new block of text, everything else is laid out inline.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "header",
+    "hr",
+    "main",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+];
+
+/// A run of text sharing the same style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    /// Absolute url the run links to
+    pub link: Option<String>,
+}
+
+/// How a block of text is laid out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextKind {
+    Paragraph,
+    Heading,
+    Quote,
+    /// Entry of a list, `depth` 1 for a top level list, `marker` is the bullet or number shown before it
+    ListItem {
+        depth: usize,
+        marker: String,
+    },
+}
+
+/// A piece of article content, in reading order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentBlock {
+    Text(TextKind, Vec<TextRun>),
+    /// Image with its absolute url
+    Image(String),
+}
+
+/// Style of the text currently being collected.
+#[derive(Debug, Clone, Default)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    link: Option<String>,
+}
+
+/// Absolute url of an image, None for tracking pixels, inline data and unparsable urls.
+///
+/// Parameters:
+/// - `image`: the img element
+/// - `base`: url relative image urls are resolved against, they are dropped without one
+pub fn image_url(image: ElementRef, base: Option<&Url>) -> Option<String> {
+    let tiny = |attr| {
+        image
+            .attr(attr)
+            .and_then(|v: &str| v.trim_end_matches("px").parse::<u32>().ok())
+            .is_some_and(|v| v <= 2)
+    };
+    if tiny("width") || tiny("height") {
+        return None;
+    }
+
+    // lazy loading scripts keep the real url in a data attribute
+    let src = ["data-src", "data-original", "src"]
+        .iter()
+        .filter_map(|attr| image.attr(attr))
+        .map(str::trim)
+        .find(|src| !src.is_empty() && !src.starts_with("data:"))?;
+
+    let url = match base {
+        Some(base) => base.join(src).ok()?,
+        None => Url::parse(src).ok()?,
+    };
+
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Absolute url of a link, None for anything but web pages and mail addresses.
+fn link_url(href: &str, base: Option<&Url>) -> Option<String> {
+    let url = match base {
+        Some(base) => base.join(href.trim()).ok()?,
+        None => Url::parse(href.trim()).ok()?,
+    };
+
+    matches!(url.scheme(), "http" | "https" | "mailto").then(|| url.to_string())
+}
+
+/// Whether an element is hidden from view with an attribute or inline style.
+fn is_hidden(element: ElementRef) -> bool {
+    let style = element
+        .attr("style")
+        .unwrap_or_default()
+        .replace(char::is_whitespace, "")
+        .to_lowercase();
+
+    element.attr("hidden").is_some()
+        || element.attr("aria-hidden") == Some("true")
+        || style.contains("display:none")
+        || style.contains("visibility:hidden")
+}
+
+/// Convert article content into blocks of styled text and images.
+///
+/// Content without any tags is taken as plain text, its line breaks separate paragraphs.
+///
+/// Parameters:
+/// - `html`: the content, HTML or plain text
+/// - `base`: the article url, relative links and images are resolved against it
+///
+/// Returns:
+/// - the blocks of the content, empty when it has no visible text or images
+pub fn content_blocks(html: &str, base: Option<&str>) -> Vec<ContentBlock> {
+    let fragment = Html::parse_fragment(html);
+    let root = fragment.root_element();
+
+    let mut converter = Converter {
+        base: base.and_then(|base| Url::parse(base).ok()),
+        plain: !root
+            .descendants()
+            .skip(1)
+            .any(|node| node.value().is_element()),
+        kind: TextKind::Paragraph,
+        lists: Vec::new(),
+        runs: Vec::new(),
+        blocks: Vec::new(),
+    };
+    converter.children(root, &Style::default());
+    converter.flush();
+
+    converter.blocks
+}
+
+/// Visible text of article content, with its blocks separated by spaces.
+pub fn content_text(html: &str) -> String {
+    content_blocks(html, None)
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text(_, runs) => {
+                Some(runs.into_iter().map(|run| run.text).collect::<String>())
+            }
+            ContentBlock::Image(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Walks the parsed content, collecting text runs into blocks.
+struct Converter {
+    base: Option<Url>,
+    /// the content has no tags, line breaks end paragraphs
+    plain: bool,
+    /// layout of the block being collected
+    kind: TextKind,
+    /// open lists, innermost last, with the number of the next item for ordered lists
+    lists: Vec<Option<usize>>,
+    /// text of the block being collected
+    runs: Vec<TextRun>,
+    blocks: Vec<ContentBlock>,
+}
+
+impl Converter {
+    fn children(&mut self, element: ElementRef, style: &Style) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) if self.plain => {
+                    for (i, line) in text.lines().enumerate() {
+                        if i > 0 {
+                            self.flush();
+                        }
+                        self.text(line, style);
+                    }
+                }
+                Node::Text(text) => self.text(text, style),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child, style);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef, style: &Style) {
+        let name = element.value().name();
+        if SKIPPED_TAGS.contains(&name) || is_hidden(element) {
+            return;
+        }
+
+        match name {
+            "br" => {
+                if let Some(last) = self.runs.last_mut() {
+                    last.text.push('\n');
+                }
+            }
+            "img" => {
+                if let Some(url) = image_url(element, self.base.as_ref()) {
+                    self.flush();
+                    self.blocks.push(ContentBlock::Image(url));
+                }
+            }
+            "b" | "strong" => self.children(
+                element,
+                &Style {
+                    bold: true,
+                    ..style.clone()
+                },
+            ),
+            "i" | "em" | "cite" => self.children(
+                element,
+                &Style {
+                    italic: true,
+                    ..style.clone()
+                },
+            ),
+            "a" => {
+                let link = element
+                    .attr("href")
+                    .and_then(|href| link_url(href, self.base.as_ref()));
+                self.children(
+                    element,
+                    &Style {
+                        link: link.or_else(|| style.link.clone()),
+                        ..style.clone()
+                    },
+                );
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block(element, style, TextKind::Heading)
+            }
+            "blockquote" => self.block(element, style, TextKind::Quote),
+            "ul" | "ol" => {
+                self.flush();
+                self.lists.push((name == "ol").then(|| {
+                    element
+                        .attr("start")
+                        .and_then(|start| start.parse().ok())
+                        .unwrap_or(1)
+                }));
+                self.children(element, style);
+                self.lists.pop();
+                self.flush();
+            }
+            "li" => {
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{number}.");
+                        *number = number.saturating_add(1);
+                        marker
+                    }
+                    _ => "•".to_string(),
+                };
+                let depth = self.lists.len().max(1);
+                self.block(element, style, TextKind::ListItem { depth, marker });
+            }
+            _ if BLOCK_TAGS.contains(&name) => {
+                let kind = self.kind.clone();
+                self.block(element, style, kind);
+            }
+            _ => self.children(element, style),
+        }
+    }
+
+    /// Collect an element as a block of its own.
+    fn block(&mut self, element: ElementRef, style: &Style, kind: TextKind) {
+        self.flush();
+        let outer = std::mem::replace(&mut self.kind, kind);
+        self.children(element, style);
+        self.flush();
+        self.kind = outer;
+    }
+
+    /// Add text to the block being collected, with whitespace collapsed like a browser does.
+    fn text(&mut self, text: &str, style: &Style) {
+        let mut space = self
+            .runs
+            .last()
+            .is_none_or(|run| run.text.ends_with(char::is_whitespace));
+
+        let mut collapsed = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !space {
+                    collapsed.push(' ');
+                    space = true;
+                }
+            } else {
+                collapsed.push(c);
+                space = false;
+            }
+        }
+
+        if collapsed.is_empty() {
+            return;
+        }
+
+        match self.runs.last_mut() {
+            Some(run)
+                if run.bold == style.bold
+                    && run.italic == style.italic
+                    && run.link == style.link =>
+            {
+                run.text.push_str(&collapsed);
+            }
+            _ => self.runs.push(TextRun {
+                text: collapsed,
+                bold: style.bold,
+                italic: style.italic,
+                link: style.link.clone(),
+            }),
+        }
+    }
+
+    /// End the block being collected, dropping it when it has no visible text.
+    fn flush(&mut self) {
+        while let Some(run) = self.runs.last_mut() {
+            let trimmed = run.text.trim_end().len();
+            run.text.truncate(trimmed);
+            if !run.text.is_empty() {
+                break;
+            }
+            self.runs.pop();
+        }
+
+        if !self.runs.is_empty() {
+            let runs = std::mem::take(&mut self.runs);
+            self.blocks
+                .push(ContentBlock::Text(self.kind.clone(), runs));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph(runs: &[(&str, Option<&str>)]) -> ContentBlock {
+        ContentBlock::Text(
+            TextKind::Paragraph,
+            runs.iter()
+                .map(|(text, link)| TextRun {
+                    text: text.to_string(),
+                    bold: false,
+                    italic: false,
+                    link: link.map(str::to_string),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            content_blocks(
+                "<p>Fish &amp; chips &lt;3 &quot;caf&eacute;&quot; &#8212; &#x263A;</p>",
+                None
+            ),
+            [paragraph(&[("Fish & chips <3 \"café\" — ☺", None)])]
+        );
+        // plain text is decoded too
+        assert_eq!(
+            content_blocks("AT&amp;T", None),
+            [paragraph(&[("AT&T", None)])]
+        );
+    }
+
+    #[test]
+    fn drops_scripts_styles_and_hidden_elements() {
+        let html = r#"<p>Visible</p>
+            <script>alert("x")</script>
+            <style>p { color: red }</style>
+            <noscript>Enable scripts</noscript>
+            <p hidden>Hidden attribute</p>
+            <p aria-hidden="true">Hidden from screen readers</p>
+            <div style="DISPLAY: none">Hidden by style</div>
+            <span style="visibility:hidden">Invisible</span>
+            <p>Also visible</p>"#;
+
+        assert_eq!(content_text(html), "Visible Also visible");
+    }
+
+    #[test]
+    fn strips_tracking_pixels_and_inline_images() {
+        let html = r#"<img src="https://t.example/pixel.gif" width="1" height="1">
+            <img src="https://t.example/pixel.gif" height="0px">
+            <img src="data:image/gif;base64,R0lGOD">
+            <img src="javascript:alert(1)">
+            <img src="/photo.jpg" data-src="/full.jpg">"#;
+
+        assert_eq!(
+            content_blocks(html, Some("https://news.example/story/1")),
+            [ContentBlock::Image(
+                "https://news.example/full.jpg".to_string()
+            )]
+        );
+        // relative images can't be resolved without the article url
+        assert!(content_blocks(r#"<img src="/photo.jpg">"#, None).is_empty());
+    }
+
+    #[test]
+    fn keeps_only_web_and_mail_links() {
+        let html = r#"<p><a href="javascript:alert(1)">script</a>
+            <a href="data:text/html,x">data</a>
+            <a href="file:///etc/passwd">file</a>
+            <a href=" JavaScript:alert(1)">mixed case</a>
+            <a href="mailto:desk@news.example">mail</a>
+            <a href="/more">relative</a></p>"#;
+
+        assert_eq!(
+            content_blocks(html, Some("https://news.example/story/1")),
+            [paragraph(&[
+                ("script data file mixed case ", None),
+                ("mail", Some("mailto:desk@news.example")),
+                (" ", None),
+                ("relative", Some("https://news.example/more")),
+            ])]
+        );
+    }
+
+    #[test]
+    fn list_numbers_stop_at_the_largest_start() {
+        let blocks = content_blocks(
+            r#"<ol start="18446744073709551615"><li>last</li><li>after</li></ol>"#,
+            None,
+        );
+
+        let markers: Vec<&str> = blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text(TextKind::ListItem { marker, .. }, _) => Some(marker.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(markers, ["18446744073709551615.", "18446744073709551615."]);
+    }
+}
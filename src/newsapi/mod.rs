@@ -1,4 +1,8 @@
 pub mod article;
+pub mod dedup;
+pub mod html;
+pub mod query;
+pub mod quota;
 pub mod source;
 
 use crate::newsapi::article::Article;
@@ -54,6 +58,57 @@ pub enum NewsAPIError {
     /// `HeaderValue`.
     #[error("Invalid Header Value")]
     HeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+    /// Error from the local SQLite article archive.
+    ///
+    /// Covers failures opening the database, running migrations and
+    /// reading or writing archived articles.
+    #[error("Archive error: {0:?}")]
+    Sqlite(#[from] rusqlite::Error),
+    /// A search query that can't be run.
+    ///
+    /// Holds a human-readable description of what is wrong with the query.
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    /// A network request was refused because the application is in offline mode.
+    #[error("Working offline")]
+    Offline,
+    /// An article page was downloaded but no article text could be found in it.
+    #[error("No article text found on the page")]
+    NoReadableContent,
+    /// An article page is larger than reader mode is willing to download.
+    #[error("The page is too large to read")]
+    PageTooLarge,
+}
+
+impl NewsAPIError {
+    /// Whether the error means NewsAPI can't currently be reached or used,
+    /// in which case cached results are a reasonable substitute.
+    ///
+    /// This covers transport failures (no connection, timeouts), exhausted or
+    /// rate limited keys and errors NewsAPI reports as being on their end.
+    /// Mistakes in the request itself, like invalid parameters or keys, are not included.
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            NewsAPIError::Reqwest(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            NewsAPIError::Api { code, .. } => matches!(
+                code.as_str(),
+                "rateLimited" | "apiKeyExhausted" | "unexpectedError"
+            ),
+            NewsAPIError::Offline => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the failed request still reached NewsAPI, and so counts against the daily quota.
+    ///
+    /// Requests that got any response count, ones that never left or never got an answer don't.
+    pub fn reached_api(&self) -> bool {
+        match self {
+            NewsAPIError::Reqwest(e) => !(e.is_connect() || e.is_timeout() || e.is_request()),
+            NewsAPIError::Api { .. } | NewsAPIError::Serde(_) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Response returned by the articles endpoints (top-headlines and everything).
@@ -163,12 +218,70 @@ pub async fn fetch_top(
         .into()
 }
 
+/// Sort order for the `v2/everything` endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Newest articles first
+    #[default]
+    PublishedAt,
+    /// Articles more closely related to the query first
+    Relevancy,
+    /// Articles from popular sources and publishers first
+    Popularity,
+}
+
+impl SortBy {
+    pub const ALL: [SortBy; 3] = [SortBy::PublishedAt, SortBy::Relevancy, SortBy::Popularity];
+
+    /// Value of the `sortBy` query parameter.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            SortBy::PublishedAt => "publishedAt",
+            SortBy::Relevancy => "relevancy",
+            SortBy::Popularity => "popularity",
+        }
+    }
+
+    /// Parse a `sortBy` parameter value, unknown values fall back to the default.
+    pub fn from_param(param: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.as_param() == param)
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for SortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SortBy::PublishedAt => "Newest",
+            SortBy::Relevancy => "Relevance",
+            SortBy::Popularity => "Popularity",
+        })
+    }
+}
+
+/// Languages the `v2/everything` endpoint can filter by, as ISO-639-1 codes.
+pub const LANGUAGES: [&str; 14] = [
+    "ar", "de", "en", "es", "fr", "he", "it", "nl", "no", "pt", "ru", "sv", "ud", "zh",
+];
+
+/// Optional parameters for the `v2/everything` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EverythingParams {
+    /// Order of the returned articles
+    pub sort_by: SortBy,
+    /// ISO-639-1 code of the language to restrict results to, None for all languages
+    pub language: Option<String>,
+}
+
 /// Search for articles using the `v2/everything` endpoint.
 ///
 /// Parameters:
 /// - `client`: Reqwest client configured with the NewsAPI API key
 /// - `query`: search query string
 /// - `sources`: optional comma-separated source ids, empty string is treated as omitted
+/// - `params`: sort order and language filter
 ///
 /// Expectations:
 /// - when sources is Some it should contain a valid comma-separated list of source ids accepted by the API
@@ -176,12 +289,20 @@ pub async fn search_articles(
     client: &Client,
     query: &str,
     sources: Option<String>,
+    params: &EverythingParams,
 ) -> Result<NewsAPIArticlesSuccess, NewsAPIError> {
-    let request = client.get("https://newsapi.org/v2/everything");
+    let request = client
+        .get("https://newsapi.org/v2/everything")
+        .query(&[("q", query), ("sortBy", params.sort_by.as_param())]);
 
     let request = match sources.as_deref() {
-        None | Some("") => request.query(&[("q", query)]),
-        Some(s) => request.query(&[("q", query), ("sources", s)]),
+        None | Some("") => request,
+        Some(s) => request.query(&[("sources", s)]),
+    };
+
+    let request = match params.language.as_deref() {
+        None => request,
+        Some(language) => request.query(&[("language", language)]),
     };
 
     request
@@ -192,6 +313,26 @@ pub async fn search_articles(
         .into()
 }
 
+/// Run a search the way the search box does: an empty query fetches the top headlines,
+/// anything else searches the `v2/everything` endpoint.
+///
+/// Parameters:
+/// - `client`: Reqwest client configured with the NewsAPI API key
+/// - `query`: search query string, may be empty
+/// - `sources`: comma-separated source ids, empty string is treated as omitted
+/// - `params`: everything endpoint parameters, unused for the top headlines
+pub async fn fetch_articles(
+    client: &Client,
+    query: &str,
+    sources: &str,
+    params: &EverythingParams,
+) -> Result<NewsAPIArticlesSuccess, NewsAPIError> {
+    match query {
+        "" => fetch_top(client, Some(sources.to_string())).await,
+        query => search_articles(client, query, Some(sources.to_string()), params).await,
+    }
+}
+
 /// Fetch available sources from the NewsAPI `top-headlines/sources` endpoint.
 ///
 /// Parameters:
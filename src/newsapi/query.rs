@@ -0,0 +1,618 @@
+use crate::newsapi::article::Article;
+use std::fmt;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Longest query NewsAPI accepts for the `q` parameter, in characters.
+pub const MAX_QUERY_LENGTH: usize = 500;
+
+/// Parsed form of a query in the NewsAPI advanced search syntax.
+///
+/// Juxtaposed terms (`bitcoin ethereum`) parse into `And` just like explicit `AND`,
+/// `a NOT b` parses into `And([a, Not(b)])`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// A single word
+    Term(String),
+    /// Words that must appear together in this order, written in quotes
+    Phrase(String),
+    /// A term, phrase or group prefixed with +
+    Required(Box<Query>),
+    /// A term, phrase or group prefixed with -
+    Excluded(Box<Query>),
+    /// A parenthesized sub-query
+    Group(Box<Query>),
+    /// Every part must match
+    And(Vec<Query>),
+    /// At least one part must match
+    Or(Vec<Query>),
+    /// The part must not match
+    Not(Box<Query>),
+}
+
+/// A query that failed to parse.
+///
+/// `column` is the 1-based character position the problem was found at.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} at column {column}")]
+pub struct QueryError {
+    /// 1-based character column of the problem
+    pub column: usize,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Word(String),
+    Phrase(String),
+    Plus,
+    Minus,
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character column the token starts at
+    column: usize,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Word(w) => write!(f, "\"{w}\""),
+            TokenKind::Phrase(p) => write!(f, "phrase \"{p}\""),
+            TokenKind::Plus => write!(f, "+"),
+            TokenKind::Minus => write!(f, "-"),
+            TokenKind::And => write!(f, "AND"),
+            TokenKind::Or => write!(f, "OR"),
+            TokenKind::Not => write!(f, "NOT"),
+            TokenKind::Open => write!(f, "("),
+            TokenKind::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn error(column: usize, message: impl Into<String>) -> QueryError {
+    QueryError {
+        column,
+        message: message.into(),
+    }
+}
+
+/// Split a query into tokens, tracking the column each one starts at.
+///
+/// The `+` and `-` prefixes are only operators directly in front of a word, phrase
+/// or parenthesis, so hyphenated words like `covid-19` stay intact.
+fn tokenize(query: &str) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token {
+                    kind: TokenKind::Open,
+                    column,
+                });
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token {
+                    kind: TokenKind::Close,
+                    column,
+                });
+                i += 1;
+            }
+            c @ ('+' | '-') => {
+                match chars.get(i + 1) {
+                    Some(next) if !next.is_whitespace() && *next != ')' => (),
+                    _ => return Err(error(column, format!("Expected a term after \"{c}\""))),
+                }
+
+                tokens.push(Token {
+                    kind: if c == '+' {
+                        TokenKind::Plus
+                    } else {
+                        TokenKind::Minus
+                    },
+                    column,
+                });
+                i += 1;
+            }
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or_else(|| error(column, "Unterminated phrase, missing closing quote"))?;
+
+                let phrase: String = chars[i + 1..i + 1 + end].iter().collect();
+                if phrase.trim().is_empty() {
+                    return Err(error(column, "Empty phrase"));
+                }
+
+                tokens.push(Token {
+                    kind: TokenKind::Phrase(phrase.trim().to_string()),
+                    column,
+                });
+                i += end + 2;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()\"".contains(chars[i]) {
+                    i += 1;
+                }
+
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(Token {
+                    kind: match word.as_str() {
+                        "AND" => TokenKind::And,
+                        "OR" => TokenKind::Or,
+                        "NOT" => TokenKind::Not,
+                        _ => TokenKind::Word(word),
+                    },
+                    column,
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over the token list.
+///
+/// Grammar, from lowest to highest precedence:
+/// ```text
+/// or    := and ("OR" and)*
+/// and   := unary (["AND"] unary | "NOT" unary)*
+/// unary := "NOT" unary | ("+" | "-") primary | primary
+/// primary := WORD | PHRASE | "(" or ")"
+/// ```
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// column just past the end of the query, reported for errors at the end of input
+    end_column: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.position).map(|t| &t.kind)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map(|t| t.column)
+            .unwrap_or(self.end_column)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut parts = vec![self.and()?];
+
+        while self.peek() == Some(&TokenKind::Or) {
+            self.next();
+            parts.push(self.and()?);
+        }
+
+        Ok(match parts.len() {
+            1 => parts.remove(0),
+            _ => Query::Or(parts),
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut parts = vec![self.unary()?];
+
+        loop {
+            match self.peek() {
+                None | Some(TokenKind::Or | TokenKind::Close) => break,
+                Some(TokenKind::And) => {
+                    self.next();
+                    parts.push(self.unary()?);
+                }
+                Some(TokenKind::Not) => {
+                    self.next();
+                    parts.push(Query::Not(Box::new(self.unary()?)));
+                }
+                Some(_) => parts.push(self.unary()?),
+            }
+        }
+
+        Ok(match parts.len() {
+            1 => parts.remove(0),
+            _ => Query::And(parts),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Query, QueryError> {
+        match self.peek() {
+            Some(TokenKind::Not) => {
+                self.next();
+                Ok(Query::Not(Box::new(self.unary()?)))
+            }
+            Some(TokenKind::Plus) => {
+                self.next();
+                Ok(Query::Required(Box::new(self.primary()?)))
+            }
+            Some(TokenKind::Minus) => {
+                self.next();
+                Ok(Query::Excluded(Box::new(self.primary()?)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Query, QueryError> {
+        let column = self.column();
+
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(word),
+                ..
+            }) => Ok(Query::Term(word)),
+            Some(Token {
+                kind: TokenKind::Phrase(phrase),
+                ..
+            }) => Ok(Query::Phrase(phrase)),
+            Some(Token {
+                kind: TokenKind::Open,
+                ..
+            }) => {
+                if self.peek() == Some(&TokenKind::Close) {
+                    return Err(error(column, "Empty parentheses"));
+                }
+
+                let inner = self.or()?;
+
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::Close,
+                        ..
+                    }) => Ok(Query::Group(Box::new(inner))),
+                    _ => Err(error(column, "Unclosed parenthesis")),
+                }
+            }
+            Some(Token { kind, column }) => Err(error(
+                column,
+                format!("Expected a term, phrase or group, found {kind}"),
+            )),
+            None => Err(error(column, "Expected a term, phrase or group")),
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query written in the NewsAPI advanced search syntax.
+    ///
+    /// Parameters:
+    /// - `query`: the raw query string, as typed in the search box
+    ///
+    /// Returns:
+    /// - `Ok(Query)` with the parsed syntax tree
+    /// - `Err(QueryError)` pointing at the column of the first problem, including
+    ///   empty queries and queries longer than NewsAPI accepts
+    pub fn parse(query: &str) -> Result<Query, QueryError> {
+        let length = query.chars().count();
+        if length > MAX_QUERY_LENGTH {
+            return Err(error(
+                MAX_QUERY_LENGTH + 1,
+                format!("Query is longer than {MAX_QUERY_LENGTH} characters"),
+            ));
+        }
+
+        let tokens = tokenize(query)?;
+        if tokens.is_empty() {
+            return Err(error(1, "Empty query"));
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            end_column: length + 1,
+        };
+
+        let parsed = parser.or()?;
+
+        match parser.next() {
+            None => Ok(parsed),
+            Some(Token {
+                kind: TokenKind::Close,
+                column,
+            }) => Err(error(column, "Unmatched closing parenthesis")),
+            Some(Token { kind, column }) => Err(error(column, format!("Unexpected {kind}"))),
+        }
+    }
+
+    /// Check whether the query matches an article.
+    ///
+    /// Like NewsAPI, the title, description and content are searched. Matching is
+    /// case-insensitive and works on whole words, phrases must appear as consecutive words.
+    pub fn matches(&self, article: &Article) -> bool {
+        let text = format!(
+            "{} {} {}",
+            article.title,
+            article.description.as_deref().unwrap_or_default(),
+            article.content.as_deref().unwrap_or_default()
+        );
+
+        self.matches_words(&words(&text))
+    }
+
+    /// Find where the query's terms and phrases appear in a text, for highlighting.
+    ///
+    /// Terms under `-` or `NOT` are skipped, they never appear in a matching article.
+    /// Uses the same word splitting as `matches`.
+    ///
+    /// Returns:
+    /// - byte ranges into `text`, sorted and non-overlapping
+    pub fn match_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut needles = Vec::new();
+        self.positive_terms(&mut needles);
+
+        let spans: Vec<(Range<usize>, String)> = text
+            .char_indices()
+            .filter(|(_, c)| c.is_alphanumeric())
+            .fold(
+                Vec::new(),
+                |mut spans: Vec<(Range<usize>, String)>, (i, c)| {
+                    match spans.last_mut() {
+                        Some((range, word)) if range.end == i => {
+                            range.end = i + c.len_utf8();
+                            word.extend(c.to_lowercase());
+                        }
+                        _ => spans.push((i..i + c.len_utf8(), c.to_lowercase().collect())),
+                    }
+                    spans
+                },
+            );
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for start in 0..spans.len() {
+            let longest = needles
+                .iter()
+                .filter(|needle| {
+                    spans.len() - start >= needle.len()
+                        && spans[start..start + needle.len()]
+                            .iter()
+                            .zip(needle.iter())
+                            .all(|((_, word), n)| word == n)
+                })
+                .map(Vec::len)
+                .max();
+
+            if let Some(length) = longest {
+                let range = spans[start].0.start..spans[start + length - 1].0.end;
+                match ranges.last_mut() {
+                    Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                    _ => ranges.push(range),
+                }
+            }
+        }
+
+        ranges
+    }
+
+    fn positive_terms(&self, out: &mut Vec<Vec<String>>) {
+        match self {
+            Query::Term(term) | Query::Phrase(term) => {
+                let needle = words(term);
+                if !needle.is_empty() {
+                    out.push(needle);
+                }
+            }
+            Query::Required(inner) | Query::Group(inner) => inner.positive_terms(out),
+            Query::Excluded(_) | Query::Not(_) => (),
+            Query::And(parts) | Query::Or(parts) => {
+                parts.iter().for_each(|p| p.positive_terms(out))
+            }
+        }
+    }
+
+    fn matches_words(&self, haystack: &[String]) -> bool {
+        match self {
+            Query::Term(term) | Query::Phrase(term) => contains_sequence(haystack, &words(term)),
+            Query::Required(inner) | Query::Group(inner) => inner.matches_words(haystack),
+            Query::Excluded(inner) | Query::Not(inner) => !inner.matches_words(haystack),
+            Query::And(parts) => parts.iter().all(|p| p.matches_words(haystack)),
+            Query::Or(parts) => parts.iter().any(|p| p.matches_words(haystack)),
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    /// Write the query back out in the NewsAPI syntax.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, parts: &[Query], separator: &str| {
+            for (i, part) in parts.iter().enumerate() {
+                match (i, part) {
+                    (0, _) => write!(f, "{part}")?,
+                    // "a NOT b" reads better than "a AND NOT b"
+                    (_, Query::Not(_)) if separator == " AND " => write!(f, " {part}")?,
+                    _ => write!(f, "{separator}{part}")?,
+                }
+            }
+            Ok(())
+        };
+
+        match self {
+            Query::Term(term) => write!(f, "{term}"),
+            Query::Phrase(phrase) => write!(f, "\"{phrase}\""),
+            Query::Required(inner) => write!(f, "+{inner}"),
+            Query::Excluded(inner) => write!(f, "-{inner}"),
+            Query::Group(inner) => write!(f, "({inner})"),
+            Query::And(parts) => join(f, parts, " AND "),
+            Query::Or(parts) => join(f, parts, " OR "),
+            Query::Not(inner) => write!(f, "NOT {inner}"),
+        }
+    }
+}
+
+/// Lowercase words of a text, split on anything that isn't alphanumeric.
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `needle` appears as consecutive words in `haystack`, an empty needle never matches.
+fn contains_sequence(haystack: &[String], needle: &[String]) -> bool {
+    !needle.is_empty()
+        && haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::newsapi::article::ArticleSource;
+
+    fn term(word: &str) -> Query {
+        Query::Term(word.to_string())
+    }
+
+    fn article(title: &str, description: &str) -> Article {
+        Article {
+            source: ArticleSource {
+                id: None,
+                name: None,
+            },
+            author: None,
+            title: title.to_string(),
+            description: Some(description.to_string()),
+            url: None,
+            url_to_image: None,
+            published_at: None,
+            content: None,
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            Query::parse("a OR b AND c").unwrap(),
+            Query::Or(vec![term("a"), Query::And(vec![term("b"), term("c")])])
+        );
+        assert_eq!(
+            Query::parse("a b OR c").unwrap(),
+            Query::Or(vec![Query::And(vec![term("a"), term("b")]), term("c")])
+        );
+    }
+
+    #[test]
+    fn not_applies_to_the_next_part() {
+        assert_eq!(
+            Query::parse("a NOT b OR c").unwrap(),
+            Query::Or(vec![
+                Query::And(vec![term("a"), Query::Not(Box::new(term("b")))]),
+                term("c"),
+            ])
+        );
+        assert_eq!(
+            Query::parse("NOT (a OR b)").unwrap(),
+            Query::Not(Box::new(Query::Group(Box::new(Query::Or(vec![
+                term("a"),
+                term("b")
+            ])))))
+        );
+    }
+
+    #[test]
+    fn prefixes_and_phrases() {
+        assert_eq!(
+            Query::parse("+bitcoin -\"price drop\" covid-19").unwrap(),
+            Query::And(vec![
+                Query::Required(Box::new(term("bitcoin"))),
+                Query::Excluded(Box::new(Query::Phrase("price drop".to_string()))),
+                term("covid-19"),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_column() {
+        let column = |query: &str| Query::parse(query).unwrap_err().column;
+
+        // unclosed and unmatched parentheses
+        assert_eq!(column("a AND (b OR c"), 7);
+        assert_eq!(column("a OR b)"), 7);
+        // unterminated phrase, counted in characters rather than bytes
+        assert_eq!(column("héllo \"wörld"), 7);
+        // dangling operators
+        assert_eq!(column("a AND"), 6);
+        assert_eq!(column("a OR OR b"), 6);
+        assert_eq!(column("a + b"), 3);
+        assert_eq!(column(""), 1);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for query in [
+            "bitcoin",
+            "\"climate change\"",
+            "crypto AND (ethereum OR litecoin) NOT bitcoin",
+            "+apple -\"apple pie\" OR (pear AND NOT plum)",
+        ] {
+            let parsed = Query::parse(query).unwrap();
+            assert_eq!(
+                Query::parse(&parsed.to_string()).unwrap(),
+                parsed,
+                "{query}"
+            );
+        }
+
+        assert_eq!(
+            Query::parse("a b NOT c").unwrap().to_string(),
+            "a AND b NOT c"
+        );
+    }
+
+    #[test]
+    fn matches_whole_words_and_phrases() {
+        let article = article(
+            "Bitcoin rallies as markets recover",
+            "The price of bitcoin rose sharply on Monday.",
+        );
+        let matches = |query: &str| Query::parse(query).unwrap().matches(&article);
+
+        assert!(matches("BITCOIN"));
+        assert!(matches("\"rose sharply\""));
+        assert!(!matches("\"sharply rose\""));
+        assert!(!matches("coin"));
+        assert!(matches("ethereum OR bitcoin"));
+        assert!(!matches("bitcoin AND ethereum"));
+        assert!(!matches("bitcoin NOT markets"));
+        assert!(matches("+bitcoin -ethereum"));
+    }
+
+    #[test]
+    fn match_ranges_skip_excluded_terms() {
+        let text = "Bitcoin and Ethereum: bitcoin price falls";
+        let query = Query::parse("bitcoin OR \"price falls\" -ethereum").unwrap();
+
+        let ranges: Vec<&str> = query
+            .match_ranges(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+        assert_eq!(ranges, vec!["Bitcoin", "bitcoin", "price falls"]);
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::TimeDelta;
+use chrono::Utc;
+use std::time::Duration;
+
+/// Requests per day allowed by the NewsAPI developer plan.
+pub const DAILY_REQUEST_LIMIT: u32 = 100;
+
+/// Requests background refreshes always leave for interactive searches, regardless of their share.
+pub const INTERACTIVE_RESERVE: u32 = 5;
+
+/// Requests left below which search-as-you-type stops querying NewsAPI, submitted searches still go through.
+pub const LIVE_SEARCH_RESERVE: u32 = 10;
+
+/// What a request was made for, background requests are held to a share of the daily budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    /// Searches and source lists the user asked for
+    Interactive,
+    /// Saved search refreshes
+    Background,
+}
+
+/// Requests made with one API key during one UTC day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaUsage {
+    /// The UTC day the counts are for, NewsAPI resets its counters at UTC midnight
+    pub day: NaiveDate,
+    /// Requests made for interactive searches
+    pub interactive: u32,
+    /// Requests made by background refreshes
+    pub background: u32,
+}
+
+impl QuotaUsage {
+    /// Total requests made.
+    pub fn used(&self) -> u32 {
+        self.interactive + self.background
+    }
+
+    /// Requests left for the day.
+    pub fn remaining(&self) -> u32 {
+        DAILY_REQUEST_LIMIT.saturating_sub(self.used())
+    }
+
+    /// The usage at `now`, with the counters reset when a UTC midnight passed since they were loaded.
+    pub fn as_of(self, now: DateTime<Utc>) -> Self {
+        let day = now.date_naive();
+
+        if day > self.day {
+            QuotaUsage {
+                day,
+                interactive: 0,
+                background: 0,
+            }
+        } else {
+            self
+        }
+    }
+
+    /// When the counters reset, the next UTC midnight.
+    pub fn resets_at(&self) -> DateTime<Utc> {
+        self.day
+            .succ_opt()
+            .unwrap_or(self.day)
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+    }
+
+    /// How many saved searches a single background refresh may fetch.
+    ///
+    /// Background refreshes get `share_percent` of the daily limit, never touching the
+    /// `INTERACTIVE_RESERVE`. The share is earned evenly by the refreshes of the day, rounding
+    /// down, and what earlier refreshes left unused carries over, so the budget lasts the
+    /// whole day instead of running out in the morning.
+    ///
+    /// Parameters:
+    /// - `share_percent`: share of `DAILY_REQUEST_LIMIT` background refreshes may use
+    /// - `interval`: time between background refreshes
+    /// - `now`: the current time
+    pub fn background_allowance(
+        &self,
+        share_percent: u32,
+        interval: Duration,
+        now: DateTime<Utc>,
+    ) -> usize {
+        const DAY: u64 = 24 * 60 * 60;
+
+        let share = (DAILY_REQUEST_LIMIT * share_percent / 100) as u64;
+        let interval = interval.as_secs().clamp(1, DAY);
+
+        let day_start = self.resets_at() - TimeDelta::days(1);
+        let elapsed = ((now - day_start).num_seconds().max(0) as u64).min(DAY - 1);
+        let refreshes = DAY.div_ceil(interval);
+        // this refresh and the ones before it today
+        let refresh = elapsed / interval + 1;
+
+        let earned = share * refresh / refreshes;
+        earned
+            .saturating_sub(self.background as u64)
+            .min(self.remaining().saturating_sub(INTERACTIVE_RESERVE) as u64) as usize
+    }
+}
+
+/// Stable identifier for an API key, so usage can be stored per key without storing the key itself.
+///
+/// Uses 64-bit FNV-1a, which unlike the standard library hasher is guaranteed to stay the same between builds.
+pub fn key_id(token: &str) -> String {
+    let hash = token.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn usage(interactive: u32, background: u32) -> QuotaUsage {
+        QuotaUsage {
+            day: NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(),
+            interactive,
+            background,
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2024, 5, 2)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn counters_reset_at_utc_midnight() {
+        assert_eq!(usage(40, 10).as_of(at(23, 59)), usage(40, 10));
+
+        let next_day = usage(40, 10).as_of(at(0, 0) + TimeDelta::days(1));
+        assert_eq!(next_day.remaining(), DAILY_REQUEST_LIMIT);
+        assert_eq!(next_day.resets_at(), at(0, 0) + TimeDelta::days(2));
+    }
+
+    #[test]
+    fn fresh_day_spreads_the_share() {
+        // 50 requests over 24 hourly refreshes
+        assert_eq!(usage(0, 0).background_allowance(50, HOUR, at(0, 0)), 2);
+        // 50 requests over 96 refreshes every 15 minutes, the first one earns nothing yet
+        let quarter = HOUR / 4;
+        assert_eq!(usage(0, 0).background_allowance(50, quarter, at(0, 0)), 0);
+        assert_eq!(usage(0, 0).background_allowance(50, quarter, at(0, 15)), 1);
+
+        let total: usize = (0..96)
+            .scan(0, |background, refresh| {
+                let now = at(0, 0) + quarter * refresh;
+                let allowance = usage(0, *background).background_allowance(50, quarter, now);
+                *background += allowance as u32;
+                Some(allowance)
+            })
+            .sum();
+        assert_eq!(total, 50);
+    }
+
+    #[test]
+    fn unused_share_carries_over() {
+        assert_eq!(usage(0, 0).background_allowance(50, HOUR, at(11, 30)), 25);
+        assert_eq!(usage(0, 20).background_allowance(50, HOUR, at(11, 30)), 5);
+    }
+
+    #[test]
+    fn nearly_spent_share() {
+        assert_eq!(usage(0, 25).background_allowance(50, HOUR, at(11, 30)), 0);
+        assert_eq!(usage(0, 49).background_allowance(50, HOUR, at(23, 0)), 1);
+        assert_eq!(usage(0, 50).background_allowance(50, HOUR, at(23, 59)), 0);
+    }
+
+    #[test]
+    fn share_never_touches_the_interactive_reserve() {
+        assert_eq!(usage(93, 0).background_allowance(50, HOUR, at(23, 0)), 2);
+        assert_eq!(usage(95, 0).background_allowance(50, HOUR, at(23, 0)), 0);
+        assert_eq!(usage(99, 0).background_allowance(100, HOUR, at(23, 0)), 0);
+    }
+}
@@ -0,0 +1,570 @@
+//! Readability-style extraction of the main content of an article page.
+//!
+//! Paragraph-like elements are scored by their length and punctuation, and the scores
+//! flow up to their parent and grandparent. The best scoring element, weighted by how
+//! few of its words are links, is taken to be the article body, together with siblings
+//! that look like part of it. Elements whose class or id suggests clutter (comments,
+//! share buttons, related stories, ...) are left out along the way.
+
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::html::image_url;
+use crate::reader::ReaderArticle;
+use crate::reader::ReaderBlock;
+use ego_tree::NodeId;
+use scraper::ElementRef;
+use scraper::Html;
+use scraper::Node;
+use scraper::Selector;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Class and id fragments of elements that rarely hold article text.
+const UNLIKELY: &[&str] = &[
+    "ad-break",
+    "agegate",
+    "banner",
+    "breadcrumb",
+    "byline",
+    "combx",
+    "comment",
+    "community",
+    "cookie",
+    "disqus",
+    "footer",
+    "gdpr",
+    "menu",
+    "newsletter",
+    "pager",
+    "pagination",
+    "popup",
+    "promo",
+    "related",
+    "remark",
+    "replies",
+    "share",
+    "shoutbox",
+    "sidebar",
+    "skyscraper",
+    "social",
+    "sponsor",
+    "subscribe",
+];
+
+/// Class and id fragments that keep an otherwise unlikely element.
+const MAYBE: &[&str] = &["article", "body", "column", "content", "main", "shadow"];
+
+/// Class and id fragments of elements likely to hold article text.
+const POSITIVE: &[&str] = &[
+    "article", "blog", "body", "content", "entry", "main", "page", "post", "story", "text",
+];
+
+/// Class and id fragments of elements unlikely to hold article text.
+const NEGATIVE: &[&str] = &[
+    "caption", "comment", "contact", "footer", "footnote", "masthead", "meta", "nav", "outbrain",
+    "promo", "related", "scroll", "share", "sidebar", "sponsor", "shopping", "tags", "taboola",
+    "tool", "widget",
+];
+
+/// Elements never part of the article text.
+const SKIPPED_TAGS: &[&str] = &[
+    "aside", "button", "embed", "footer", "form", "header", "iframe", "input", "nav", "noscript",
+    "object", "script", "select", "style", "svg", "template", "textarea",
+];
+
+/// Elements that start a new block of text, everything else is laid out inline.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "img",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "picture",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Elements whose text is scored as a paragraph.
+const SCORED_TAGS: &[&str] = &["p", "pre", "td"];
+
+/// Shortest text scored as a paragraph, in characters.
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Deepest nesting of elements looked into, deeper markup is left out so hostile pages can't
+/// overflow the stack.
+const MAX_DEPTH: usize = 200;
+
+/// Share of the text in links above which a list or section is taken to be navigation.
+const MAX_LINK_DENSITY: f32 = 0.5;
+
+static LINKS: LazyLock<Selector> = LazyLock::new(|| Selector::parse("a").expect("valid selector"));
+static IMAGES: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("img").expect("valid selector"));
+static FIGCAPTIONS: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("figcaption").expect("valid selector"));
+
+/// Extract the main content from the html of an article page.
+///
+/// Parameters:
+/// - `html`: the page source
+/// - `url`: the page url, relative image urls are resolved against it
+///
+/// Returns:
+/// - `Ok(ReaderArticle)` with the title, byline and article body
+/// - `Err(NewsAPIError::NoReadableContent)` when no article text was found
+pub fn extract(html: &str, url: &str) -> Result<ReaderArticle, NewsAPIError> {
+    let document = Html::parse_document(html);
+    let base = reqwest::Url::parse(url).ok();
+
+    let title = meta_content(&document, "meta[property='og:title']")
+        .or_else(|| first_text(&document, "h1"))
+        .or_else(|| first_text(&document, "title"));
+    let byline = meta_content(&document, "meta[name='author']")
+        .filter(|author| !author.starts_with("http"))
+        .or_else(|| first_text(&document, "[rel='author']"))
+        .or_else(|| first_text(&document, "[itemprop='author']"))
+        .or_else(|| first_text(&document, ".byline"))
+        .filter(|byline| byline.chars().count() < 100);
+
+    let mut scores = HashMap::new();
+    score_paragraphs(document.root_element(), 0, &mut scores);
+
+    let top = scores
+        .iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(document.tree.get(*id)?)?;
+            Some((element, score * (1.0 - link_density(element))))
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let Some((top, top_score)) = top else {
+        return Err(NewsAPIError::NoReadableContent);
+    };
+
+    let mut extractor = Extractor {
+        base,
+        title: title.clone(),
+        blocks: Vec::new(),
+    };
+
+    // siblings of the best element are often part of the article, split up by the page layout
+    let siblings: Vec<ElementRef> = match top.parent().and_then(ElementRef::wrap) {
+        Some(parent) => parent.child_elements().collect(),
+        None => vec![top],
+    };
+    let threshold = (top_score * 0.2).max(10.0);
+
+    for sibling in siblings {
+        let score = scores.get(&sibling.id()).copied().unwrap_or(0.0);
+        let text = normalized_text(sibling);
+        let length = text.chars().count();
+
+        let include = sibling == top
+            || score * (1.0 - link_density(sibling)) >= threshold
+            || (sibling.value().name() == "p"
+                && link_density(sibling) < 0.25
+                && (length > 80 || (length > 0 && text.contains(". "))));
+
+        if include {
+            extractor.block(sibling, 0);
+        }
+    }
+
+    let blocks = extractor.blocks;
+    if !blocks
+        .iter()
+        .any(|block| matches!(block, ReaderBlock::Paragraph(_)))
+    {
+        return Err(NewsAPIError::NoReadableContent);
+    }
+
+    Ok(ReaderArticle {
+        title,
+        byline,
+        blocks,
+    })
+}
+
+/// The `content` attribute of the first element matching `selector`.
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+
+    document
+        .select(&selector)
+        .filter_map(|element| element.attr("content"))
+        .map(collapse_whitespace)
+        .find(|content| !content.is_empty())
+}
+
+/// The text of the first element matching `selector`.
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+
+    document
+        .select(&selector)
+        .map(normalized_text)
+        .find(|text| !text.is_empty())
+}
+
+/// Collapse runs of whitespace into single spaces and trim the ends.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text of an element with whitespace collapsed.
+fn normalized_text(element: ElementRef) -> String {
+    collapse_whitespace(&element.text().collect::<String>())
+}
+
+/// Lowercased class and id of an element, for matching against the fragment lists.
+fn class_and_id(element: ElementRef) -> String {
+    format!(
+        "{} {}",
+        element.attr("class").unwrap_or_default(),
+        element.attr("id").unwrap_or_default()
+    )
+    .to_lowercase()
+}
+
+fn matches_any(haystack: &str, fragments: &[&str]) -> bool {
+    fragments.iter().any(|fragment| haystack.contains(fragment))
+}
+
+/// Whether an element and everything in it is left out.
+fn is_clutter(element: ElementRef) -> bool {
+    let name = element.value().name();
+    if SKIPPED_TAGS.contains(&name) || element.attr("hidden").is_some() {
+        return true;
+    }
+
+    // the body and main content wrappers often carry page-wide classes like "sidebar-open"
+    if matches!(name, "body" | "article" | "main") {
+        return false;
+    }
+
+    let class_and_id = class_and_id(element);
+    matches_any(&class_and_id, UNLIKELY) && !matches_any(&class_and_id, MAYBE)
+}
+
+/// Score bonus or penalty from an element's class and id.
+fn class_weight(element: ElementRef) -> f32 {
+    let class_and_id = class_and_id(element);
+    let mut weight = 0.0;
+
+    if matches_any(&class_and_id, POSITIVE) {
+        weight += 25.0;
+    }
+    if matches_any(&class_and_id, NEGATIVE) {
+        weight -= 25.0;
+    }
+
+    weight
+}
+
+/// Starting score of an element receiving paragraph scores, from its tag and class.
+fn initial_score(element: ElementRef) -> f32 {
+    let tag_score = match element.value().name() {
+        "article" => 10.0,
+        "div" | "main" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+
+    tag_score + class_weight(element)
+}
+
+/// Whether a div holds only inline content, in which case it's scored like a paragraph.
+fn is_inline_div(element: ElementRef) -> bool {
+    element.value().name() == "div"
+        && element
+            .child_elements()
+            .all(|child| !BLOCK_TAGS.contains(&child.value().name()))
+}
+
+/// Score every paragraph under `element`, adding the scores to their parents and grandparents.
+///
+/// Parameters:
+/// - `depth`: nesting of `element`, nothing deeper than `MAX_DEPTH` is scored
+fn score_paragraphs(element: ElementRef, depth: usize, scores: &mut HashMap<NodeId, f32>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+
+    for child in element.child_elements() {
+        if is_clutter(child) {
+            continue;
+        }
+
+        if SCORED_TAGS.contains(&child.value().name()) || is_inline_div(child) {
+            let text = normalized_text(child);
+            let length = text.chars().count();
+
+            if length >= MIN_PARAGRAPH_LENGTH {
+                let commas = text.matches([',', '，', '、']).count() as f32;
+                let score = 1.0 + commas + (length as f32 / 100.0).min(3.0);
+
+                let ancestors = child.ancestors().filter_map(ElementRef::wrap).take(3);
+                for (level, ancestor) in ancestors.enumerate() {
+                    let share = match level {
+                        0 => 1.0,
+                        1 => 2.0,
+                        _ => level as f32 * 3.0,
+                    };
+
+                    *scores
+                        .entry(ancestor.id())
+                        .or_insert_with(|| initial_score(ancestor)) += score / share;
+                }
+            }
+        }
+
+        score_paragraphs(child, depth + 1, scores);
+    }
+}
+
+/// Share of an element's text that is inside links.
+fn link_density(element: ElementRef) -> f32 {
+    let length = normalized_text(element).chars().count();
+    if length == 0 {
+        return 0.0;
+    }
+
+    let link_length: usize = element
+        .select(&LINKS)
+        .map(|link| normalized_text(link).chars().count())
+        .sum();
+
+    link_length as f32 / length as f32
+}
+
+/// Turns the elements of the article body into blocks.
+struct Extractor {
+    /// page url, relative image urls are resolved against it
+    base: Option<reqwest::Url>,
+    /// the page title, a heading repeating it is left out
+    title: Option<String>,
+    blocks: Vec<ReaderBlock>,
+}
+
+impl Extractor {
+    /// Add the blocks of an element and everything in it.
+    ///
+    /// Parameters:
+    /// - `depth`: nesting below the article body, nothing deeper than `MAX_DEPTH` is added
+    fn block(&mut self, element: ElementRef, depth: usize) {
+        if depth >= MAX_DEPTH || is_clutter(element) {
+            return;
+        }
+
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = normalized_text(element);
+                if !text.is_empty() && Some(&text) != self.title.as_ref() {
+                    let level = name[1..].parse().unwrap_or(2);
+                    self.blocks.push(ReaderBlock::Heading(level, text));
+                }
+            }
+            "ul" | "ol" if link_density(element) > MAX_LINK_DENSITY => {}
+            "li" => {
+                let text = normalized_text(element);
+                if !text.is_empty() {
+                    self.blocks.push(ReaderBlock::ListItem(text));
+                }
+            }
+            "blockquote" => {
+                let text = normalized_text(element);
+                if !text.is_empty() {
+                    self.blocks.push(ReaderBlock::Quote(text));
+                }
+            }
+            "pre" => {
+                let text = element.text().collect::<String>();
+                if !text.trim().is_empty() {
+                    self.blocks.push(ReaderBlock::Preformatted(text));
+                }
+            }
+            "img" => {
+                if let Some(url) = image_url(element, self.base.as_ref()) {
+                    let caption = element
+                        .attr("alt")
+                        .map(collapse_whitespace)
+                        .filter(|alt| !alt.is_empty());
+                    self.blocks.push(ReaderBlock::Image { url, caption });
+                }
+            }
+            "figure" => self.figure(element),
+            "table" | "section" | "div"
+                if link_density(element) > MAX_LINK_DENSITY
+                    && normalized_text(element).chars().count() < 1000 => {}
+            _ => self.container(element, depth),
+        }
+    }
+
+    /// Add the blocks of an element made of text runs and nested blocks.
+    ///
+    /// Consecutive text and inline elements are joined into a paragraph, block elements end it.
+    fn container(&mut self, element: ElementRef, depth: usize) {
+        let mut paragraph = String::new();
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => paragraph.push_str(text),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+
+                    let name = child.value().name();
+                    if name == "br" {
+                        paragraph.push(' ');
+                    } else if BLOCK_TAGS.contains(&name) || child.select(&IMAGES).next().is_some() {
+                        self.paragraph(&mut paragraph);
+                        self.block(child, depth + 1);
+                    } else if !is_clutter(child) {
+                        paragraph.extend(child.text());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.paragraph(&mut paragraph);
+    }
+
+    /// Add the collected text as a paragraph, if there's any.
+    fn paragraph(&mut self, text: &mut String) {
+        let paragraph = collapse_whitespace(text);
+        if !paragraph.is_empty() {
+            self.blocks.push(ReaderBlock::Paragraph(paragraph));
+        }
+
+        text.clear();
+    }
+
+    /// Add the images of a figure, the first one captioned with the figcaption.
+    fn figure(&mut self, element: ElementRef) {
+        let mut caption = element
+            .select(&FIGCAPTIONS)
+            .map(normalized_text)
+            .find(|caption| !caption.is_empty());
+
+        for image in element.select(&IMAGES) {
+            if let Some(url) = image_url(image, self.base.as_ref()) {
+                let caption = caption
+                    .take()
+                    .or_else(|| image.attr("alt").map(collapse_whitespace))
+                    .filter(|caption| !caption.is_empty());
+                self.blocks.push(ReaderBlock::Image { url, caption });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEWS_ARTICLE: &str = include_str!("../../tests/fixtures/reader/news_article.html");
+    const NO_ARTICLE: &str = include_str!("../../tests/fixtures/reader/no_article.html");
+    const URL: &str = "https://example.com/news/bike-lanes";
+
+    #[test]
+    fn extracts_title_and_byline() {
+        let article = extract(NEWS_ARTICLE, URL).expect("article found");
+
+        assert_eq!(
+            article.title.as_deref(),
+            Some("Council approves new bike lanes downtown")
+        );
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+    }
+
+    #[test]
+    fn extracts_article_blocks_without_clutter() {
+        let article = extract(NEWS_ARTICLE, URL).expect("article found");
+
+        assert_eq!(
+            article.blocks,
+            vec![
+                ReaderBlock::Paragraph(
+                    "The city council voted on Tuesday to build protected bike lanes along three \
+                     of the busiest streets downtown, ending a debate that lasted more than two \
+                     years."
+                        .to_string()
+                ),
+                ReaderBlock::Image {
+                    url: "https://example.com/images/lanes.jpg".to_string(),
+                    caption: Some(
+                        "Main Street will be the first to get a protected lane.".to_string()
+                    ),
+                },
+                ReaderBlock::Paragraph(
+                    "Supporters said the lanes will make cycling safer, cut traffic and help \
+                     local shops, while opponents worried about the loss of parking spaces for \
+                     delivery vans."
+                        .to_string()
+                ),
+                ReaderBlock::Heading(2, "What happens next".to_string()),
+                ReaderBlock::Paragraph(
+                    "Construction is expected to start in the spring, and the first lane, on \
+                     Main Street, should open before the end of the summer, the council said."
+                        .to_string()
+                ),
+                ReaderBlock::ListItem("Main Street, from the station to the river".to_string()),
+                ReaderBlock::ListItem(
+                    "Market Street, along the whole length of the square".to_string()
+                ),
+                ReaderBlock::Quote(
+                    "This is the biggest change to our streets in a generation, and it will \
+                     make them safer for everyone."
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn page_without_article_has_no_readable_content() {
+        assert!(matches!(
+            extract(NO_ARTICLE, URL),
+            Err(NewsAPIError::NoReadableContent)
+        ));
+    }
+
+    #[test]
+    fn deeply_nested_markup_does_not_overflow() {
+        let depth = 5_000;
+        let html = format!(
+            "<html><body>{}<p>{}</p>{}</body></html>",
+            "<div>".repeat(depth),
+            "Some text deep down, long enough to be scored as a paragraph.",
+            "</div>".repeat(depth)
+        );
+
+        // too deep to be looked into, but it mustn't crash
+        assert!(extract(&html, URL).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+mod extract;
+
+use crate::newsapi::NewsAPIError;
+use encoding_rs::Encoding;
+use encoding_rs::UTF_8;
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+pub use extract::extract;
+
+/// User agent sent with article page requests, some sites refuse requests without one.
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; newsapi-demo reader mode)";
+
+/// How long an article page request may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Largest article page downloaded, anything bigger isn't an article worth reading.
+const MAX_PAGE_SIZE: usize = 5 * 1024 * 1024;
+
+/// A piece of the extracted article body, in reading order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReaderBlock {
+    /// Heading with its level, 1 to 6
+    Heading(u8, String),
+    /// Paragraph of running text
+    Paragraph(String),
+    /// Entry of a bulleted or numbered list
+    ListItem(String),
+    /// Quoted passage
+    Quote(String),
+    /// Preformatted text, whitespace is kept
+    Preformatted(String),
+    /// Image with its absolute url and caption
+    Image {
+        url: String,
+        caption: Option<String>,
+    },
+}
+
+/// Main content of an article page, with the navigation, ads and other clutter left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReaderArticle {
+    /// Headline found on the page
+    pub title: Option<String>,
+    /// Author line found on the page
+    pub byline: Option<String>,
+    /// The article body
+    pub blocks: Vec<ReaderBlock>,
+}
+
+impl ReaderArticle {
+    /// Urls of the images in the article body.
+    pub fn image_urls(&self) -> impl Iterator<Item = &str> {
+        self.blocks.iter().filter_map(|block| match block {
+            ReaderBlock::Image { url, .. } => Some(url.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Running text of the article, paragraphs, list items and quotes on lines of their own.
+    pub fn plain_text(&self) -> String {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                ReaderBlock::Paragraph(text)
+                | ReaderBlock::ListItem(text)
+                | ReaderBlock::Quote(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Download an article page and extract its main content.
+///
+/// The request is made with a client of its own, the NewsAPI client sends the API key with every request.
+///
+/// Parameters:
+/// - `url`: the article url
+///
+/// Returns:
+/// - `Ok(ReaderArticle)` with the extracted content
+/// - `Err(NewsAPIError::NoReadableContent)` when the page isn't HTML or has no recognisable article text
+/// - `Err(NewsAPIError::PageTooLarge)` when the page is over `MAX_PAGE_SIZE`,
+///   or another error when the request fails
+pub async fn fetch_reader_article(url: &str) -> Result<ReaderArticle, NewsAPIError> {
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_ascii_lowercase();
+    if !is_html(&content_type) {
+        return Err(NewsAPIError::NoReadableContent);
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PAGE_SIZE as u64)
+    {
+        return Err(NewsAPIError::PageTooLarge);
+    }
+
+    // the length header may be missing or wrong, so the body is counted as it arrives
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_PAGE_SIZE {
+            return Err(NewsAPIError::PageTooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let html = decode(&body, &content_type);
+
+    // parsing large pages takes a while, keep it off the async workers
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || extract(&html, &url))
+        .await
+        .map_err(|e| NewsAPIError::IO(e.into()))?
+}
+
+/// Whether a `Content-Type` header value, in lower case, is an HTML page.
+fn is_html(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    matches!(mime, "text/html" | "application/xhtml+xml")
+}
+
+/// Decode a page body with the charset of its `Content-Type` header, UTF-8 when it names none.
+fn decode(body: &[u8], content_type: &str) -> String {
+    let encoding = content_type
+        .split(';')
+        .filter_map(|param| param.trim().strip_prefix("charset="))
+        .find_map(|label| Encoding::for_label(label.trim_matches('"').as_bytes()))
+        .unwrap_or(UTF_8);
+
+    encoding.decode(body).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_html_pages_are_read() {
+        assert!(is_html("text/html"));
+        assert!(is_html("text/html; charset=utf-8"));
+        assert!(is_html("application/xhtml+xml"));
+        assert!(!is_html("application/pdf"));
+        assert!(!is_html("image/jpeg"));
+    }
+
+    #[test]
+    fn decodes_with_the_header_charset() {
+        assert_eq!(decode(b"caf\xe9", "text/html; charset=iso-8859-1"), "café");
+        assert_eq!(
+            decode(b"caf\xe9", "text/html; charset=\"windows-1252\""),
+            "café"
+        );
+        assert_eq!(decode("café".as_bytes(), "text/html"), "café");
+    }
+}
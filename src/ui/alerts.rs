@@ -0,0 +1,300 @@
+use crate::archive::watch::Alert;
+use crate::archive::watch::WatchTerm;
+use crate::newsapi::query::Query;
+use crate::newsapi::query::QueryError;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::main_page::error_element;
+use crate::ui::saved::badge;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use crate::ui::style::text_input_style;
+use chrono::DateTime;
+use chrono::Local;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Font;
+use iced::Gradient;
+use iced::Length;
+use iced::color;
+use iced::font::Weight;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::horizontal_rule;
+use iced::widget::rich_text;
+use iced::widget::scrollable;
+use iced::widget::span;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::text::Span;
+use iced::widget::text_input;
+use iced::widget::vertical_rule;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::f32::consts::FRAC_PI_4;
+
+/// Render text with every occurrence of the query's terms highlighted.
+///
+/// Parameters:
+/// - `content`: the text to render
+/// - `query`: the watch term, highlighting is skipped when it's None
+/// - `size`: text size
+fn highlighted<'a>(content: &'a str, query: Option<&Query>, size: u16) -> Element<'a, Message> {
+    let ranges = query.map(|q| q.match_ranges(content)).unwrap_or_default();
+
+    let mut spans: Vec<Span<'a, Message>> = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut position = 0;
+
+    for range in ranges {
+        if range.start > position {
+            spans.push(span(&content[position..range.start]));
+        }
+
+        spans.push(
+            span(&content[range.clone()])
+                .background(color!(0xffe066))
+                .font(Font {
+                    weight: Weight::Bold,
+                    ..Font::DEFAULT
+                }),
+        );
+        position = range.end;
+    }
+
+    if position < content.len() {
+        spans.push(span(&content[position..]));
+    }
+
+    rich_text(spans).size(size).into()
+}
+
+/// Build the list entry for a watch term: the term, a badge with unread alerts and a remove button.
+fn watch_term_entry(term: &WatchTerm, selected: bool) -> Element<'_, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    row![
+        button(
+            row![
+                text(&term.term)
+                    .size(18)
+                    .shaping(Advanced)
+                    .width(Length::Fill)
+            ]
+            .push_maybe((term.unread > 0).then(|| badge(term.unread)))
+            .align_y(Alignment::Center)
+        )
+        .on_press_maybe((!selected).then_some(M(WatchTermSelected(term.id))))
+        .style(button_style)
+        .width(Length::Fill),
+        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+            .width(32)
+            .style(close_button_style)
+            .on_press(M(WatchTermRemoved(term.id))),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Build a single alert: the article title with the matched term highlighted, its source and when it matched.
+///
+/// Parameters:
+/// - `index`: position of the alert in the list, sent with `MainPageMessage::AlertOpened`
+/// - `alert`: the alert
+/// - `query`: the parsed watch term, used for highlighting
+fn alert_entry<'a>(index: usize, alert: &'a Alert, query: Option<&Query>) -> Element<'a, Message> {
+    let matched: DateTime<Local> = DateTime::from(alert.matched_at);
+    let source = alert
+        .article
+        .source
+        .name
+        .as_deref()
+        .or(alert.article.source.id.as_deref())
+        .unwrap_or("Unknown source");
+
+    let description = alert
+        .article
+        .description
+        .as_deref()
+        .filter(|d| query.is_some_and(|q| !q.match_ranges(d).is_empty()));
+
+    button(
+        column![
+            row![highlighted(&alert.article.title, query, 20)]
+                .push_maybe((!alert.read).then(|| text("new").size(14).color(color!(0xd93025))))
+                .spacing(10),
+        ]
+        .push_maybe(description.map(|d| highlighted(d, query, 15)))
+        .push(
+            text(format!(
+                "{source} · matched {}",
+                matched.format("%B %d, %Y at %H:%M")
+            ))
+            .size(13)
+            .shaping(Advanced),
+        )
+        .spacing(3),
+    )
+    .on_press(Message::MainPage(MainPageMessage::AlertOpened(index)))
+    .style(button_style)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Render the alerts page when `alerts_page` is true.
+///
+/// Parameters:
+/// - `alerts_page`: whether the page should be shown. If false, returns None.
+/// - `terms`: the watch terms with their unread counts
+/// - `input`: content of the new watch term input
+/// - `error`: problem with the entered watch term, if any
+/// - `selected`: id of the watch term whose alerts are listed
+/// - `alerts`: alerts of the selected term, None while loading
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with the watch term list on the left and the alerts of the selected term on the right.
+/// - `None` when the page is hidden.
+pub fn alerts_page<'a>(
+    alerts_page: bool,
+    terms: &'a [WatchTerm],
+    input: &'a str,
+    error: Option<&QueryError>,
+    selected: Option<i64>,
+    alerts: Option<&'a Result<Vec<Alert>, String>>,
+) -> Option<Element<'a, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !alerts_page {
+        return None;
+    }
+
+    let term_list: Element<'a, Message> = if terms.is_empty() {
+        text("Watch terms are checked against every article you fetch.").into()
+    } else {
+        scrollable(
+            Column::with_children(
+                terms
+                    .iter()
+                    .map(|t| watch_term_entry(t, selected == Some(t.id))),
+            )
+            .spacing(5)
+            .padding(iced::Padding::ZERO.right(10)),
+        )
+        .height(Length::Fill)
+        .into()
+    };
+
+    let query = selected
+        .and_then(|id| terms.iter().find(|t| t.id == id))
+        .and_then(|t| Query::parse(&t.term).ok());
+
+    let entry = |(i, alert): (usize, &'a Alert)| alert_entry(i, alert, query.as_ref());
+
+    let alert_list: Element<'a, Message> = match (selected, alerts) {
+        (None, _) => text("Select a watch term to see its alerts.").into(),
+        (Some(_), None) => text("Loading...").into(),
+        (Some(_), Some(Err(e))) => error_element(e),
+        (Some(_), Some(Ok(alerts))) if alerts.is_empty() => {
+            text("No matches yet, new articles are checked as they are fetched.").into()
+        }
+        (Some(_), Some(Ok(alerts))) => column![
+            button(text("Show all in results"))
+                .style(button_style)
+                .on_press(M(ShowAlertArticles)),
+            scrollable(
+                Column::with_children(alerts.iter().enumerate().map(entry))
+                    .spacing(5)
+                    .padding(iced::Padding::ZERO.right(10)),
+            )
+            .height(Length::Fill),
+        ]
+        .spacing(5)
+        .into(),
+    };
+
+    let terms_column = column![
+        text("Watch terms").size(20),
+        row![
+            text_input("Company, product, \"phrase\"...", input)
+                .on_input(|s| M(WatchInputOnInput(s)))
+                .on_submit(M(AddWatchTerm))
+                .style(text_input_style)
+                .width(Length::Fill),
+            button(text("Watch"))
+                .style(button_style)
+                .on_press(M(AddWatchTerm)),
+        ]
+        .spacing(5),
+    ]
+    .push_maybe(error.map(|e| text(e.to_string()).size(14).color(color!(0xff0000))))
+    .push(horizontal_rule(6))
+    .push(term_list)
+    .spacing(5)
+    .width(280);
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        row![
+                            text("Alerts").size(24),
+                            container(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(48)
+                                    .style(close_button_style)
+                                    .on_press(M(ToggleAlertsPage)),
+                            )
+                            .align_right(Length::Fill),
+                        ]
+                        .padding(5)
+                        .spacing(5)
+                        .align_y(Alignment::Center),
+                        horizontal_rule(6),
+                        row![
+                            terms_column,
+                            vertical_rule(6),
+                            container(alert_list).width(Length::Fill)
+                        ]
+                        .spacing(10)
+                        .padding(5),
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(1200)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(ToggleAlertsPage))
+        .on_press(M(ToggleAlertsPage))
+        .into(),
+    )
+}
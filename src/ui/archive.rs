@@ -0,0 +1,180 @@
+use crate::archive::ArchiveFilter;
+use crate::archive::ArchiveIndex;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::main_page::error_element;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Gradient;
+use iced::Length;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::horizontal_rule;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::f32::consts::FRAC_PI_4;
+
+/// Which grouping of the archive is listed in the browse menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchiveMode {
+    #[default]
+    Date,
+    Source,
+    Query,
+}
+
+/// Build a single clickable archive entry showing a label and an article count.
+///
+/// Parameters:
+/// - `label`: display text for the entry
+/// - `count`: number of archived articles behind the entry
+/// - `filter`: filter sent with `MainPageMessage::ArchiveBrowse` when the entry is pressed
+fn archive_entry(label: String, count: usize, filter: ArchiveFilter) -> Element<'static, Message> {
+    button(
+        row![
+            text(label).shaping(Advanced).size(20).width(Length::Fill),
+            text(format!("{count} articles")).size(16)
+        ]
+        .align_y(Alignment::Center),
+    )
+    .on_press(Message::MainPage(MainPageMessage::ArchiveBrowse(filter)))
+    .style(button_style)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Render the archive browse menu when `archive_page` is true.
+///
+/// Parameters:
+/// - `archive_page`: whether the menu should be shown. If false, returns None.
+/// - `index`: the archive overview or an error; when None (still loading) returns None.
+/// - `mode`: which grouping to list.
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with mode buttons and the entries for the selected grouping, or an error element.
+/// - `None` when the page is hidden or the index hasn't loaded yet.
+pub fn archive_page<'a>(
+    archive_page: bool,
+    index: Option<&'a Result<ArchiveIndex, String>>,
+    mode: ArchiveMode,
+) -> Option<Element<'a, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !archive_page {
+        return None;
+    }
+
+    let data = match index? {
+        Ok(data) => data,
+        Err(error) => return Some(error_element(error)),
+    };
+
+    let mode_button = |label: &'static str, target: ArchiveMode| {
+        button(text(label).size(20))
+            .on_press_maybe((mode != target).then_some(M(ArchiveModeSelected(target))))
+            .style(button_style)
+            .padding(10)
+    };
+
+    let entries: Vec<Element<'a, Message>> = match mode {
+        ArchiveMode::Date => data
+            .days
+            .iter()
+            .map(|(day, count)| {
+                archive_entry(day.clone(), *count, ArchiveFilter::Date(day.clone()))
+            })
+            .collect(),
+        ArchiveMode::Source => data
+            .sources
+            .iter()
+            .map(|(key, name, count)| {
+                archive_entry(name.clone(), *count, ArchiveFilter::Source(key.clone()))
+            })
+            .collect(),
+        ArchiveMode::Query => data
+            .queries
+            .iter()
+            .map(|(query, count)| {
+                let label = match query.as_str() {
+                    "" => "Top headlines".to_string(),
+                    q => q.to_string(),
+                };
+
+                archive_entry(label, *count, ArchiveFilter::Query(query.clone()))
+            })
+            .collect(),
+    };
+
+    let list: Element<'a, Message> = if entries.is_empty() {
+        text("Nothing archived yet, articles are stored as you search.").into()
+    } else {
+        scrollable(Column::with_children(entries).spacing(5).padding([0, 10]))
+            .spacing(5)
+            .into()
+    };
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        row![
+                            mode_button("By date", ArchiveMode::Date),
+                            mode_button("By source", ArchiveMode::Source),
+                            mode_button("By query", ArchiveMode::Query),
+                            container(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(48)
+                                    .style(close_button_style)
+                                    .on_press(M(ToggleArchivePage)),
+                            )
+                            .align_right(Length::Fill),
+                        ]
+                        .padding(5)
+                        .spacing(5),
+                        horizontal_rule(6),
+                        list,
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(900)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(ToggleArchivePage))
+        .on_press(M(ToggleArchivePage))
+        .into(),
+    )
+}
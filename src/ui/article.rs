@@ -19,35 +19,194 @@ use iced::widget::button;
 use iced::widget::horizontal_rule;
 use iced::widget::image::Handle;
 use iced::widget::mouse_area;
+use iced::widget::pick_list;
 use iced::widget::scrollable;
 use iced::widget::svg;
 use iced::widget::text::Shaping::Advanced;
 use sha2::Digest;
 use sha2::Sha256;
+use std::collections::HashMap;
 use std::env::temp_dir;
 use std::f32::consts::FRAC_PI_4;
+use std::fmt;
 use std::fs::create_dir;
 use std::path::PathBuf;
 
+use crate::analysis::summary::summarize;
 use crate::newsapi::NewsAPIError;
 use crate::newsapi::article::Article;
+use crate::newsapi::html::ContentBlock;
+use crate::newsapi::html::content_text;
 use crate::ui::Message;
+use crate::ui::compare::compare_checkbox;
+use crate::ui::html::html_content;
 use crate::ui::main_page::MainPageMessage;
+use crate::ui::reader::ReaderView;
+use crate::ui::reader::reader_content;
+use crate::ui::stories::Coverage;
+use crate::ui::stories::StoryEntry;
+use crate::ui::stories::coverage_strip;
 use crate::ui::style::CLOSE_ICON;
 use crate::ui::style::button_style;
 use crate::ui::style::card_style;
 use crate::ui::style::close_button_style;
+use crate::ui::tone::tone_indicator;
 use iced::widget::Button;
 use iced::widget::text;
 use iced::{Alignment, Length, Theme};
 use iced::{Element, widget::container};
 
+/// Setting key the number of summary sentences is persisted under.
+pub const SUMMARY_LENGTH_KEY: &str = "summary_sentences";
+
+/// Number of sentences in the summary at the top of an article.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryLength(pub usize);
+
+impl SummaryLength {
+    pub const ALL: [SummaryLength; 5] = [
+        SummaryLength(1),
+        SummaryLength(2),
+        SummaryLength(3),
+        SummaryLength(4),
+        SummaryLength(5),
+    ];
+
+    pub const DEFAULT: SummaryLength = SummaryLength(3);
+}
+
+impl fmt::Display for SummaryLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            1 => f.write_str("1 sentence"),
+            n => write!(f, "{n} sentences"),
+        }
+    }
+}
+
+/// Content loaded for the open article on top of what NewsAPI returned.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadedContent<'a> {
+    /// reader mode content, only shown for the article it belongs to
+    pub reader: Option<&'a ReaderView>,
+    /// images in the article content by url
+    pub content_images: &'a HashMap<String, Handle>,
+    /// blocks of the content NewsAPI returned, parsed when the article was opened
+    pub content_blocks: &'a [ContentBlock],
+    /// sentences of the summary, picked when the article or its reader mode content loaded
+    pub summary: &'a [String],
+    /// number of sentences in the summary
+    pub summary_length: SummaryLength,
+}
+
+/// Plain text of the content NewsAPI returned, without the "[+1234 chars]" marker at its end and
+/// the sentence it cut off.
+fn truncated_content_text(content: &str) -> String {
+    let text = content_text(content);
+
+    match text.rfind("[+") {
+        Some(marker) if text[marker..].trim_end().ends_with("chars]") => {
+            let kept = &text[..marker];
+            let end = kept.rfind(['.', '!', '?']).map_or(0, |end| end + 1);
+            kept[..end].to_string()
+        }
+        _ => text,
+    }
+}
+
+/// Sentences that best summarize an article, picked offline with TextRank.
+///
+/// Parameters:
+/// - `article`: the article
+/// - `reader`: reader mode content, summarized instead of the description and content once loaded for this article
+/// - `length`: number of sentences to pick
+///
+/// Returns:
+/// - the picked sentences, empty when the text is too short to summarize
+pub fn article_summary(
+    article: &Article,
+    reader: Option<&ReaderView>,
+    length: SummaryLength,
+) -> Vec<String> {
+    let plain_text = match reader
+        .filter(|reader| article.url.as_ref() == Some(&reader.url))
+        .and_then(|reader| reader.result.as_ref())
+    {
+        Some(Ok(reader)) => reader.plain_text(),
+        _ => [
+            article.description.clone().unwrap_or_default(),
+            article
+                .content
+                .as_deref()
+                .map(truncated_content_text)
+                .unwrap_or_default(),
+        ]
+        .join("\n"),
+    };
+
+    summarize(&plain_text, length.0)
+}
+
+/// Box with the sentences that best summarize an article.
+///
+/// Parameters:
+/// - `sentences`: the summary, from `article_summary`
+/// - `length`: number of sentences picked
+/// - `adjustable`: show a picker for the number of sentences
+///
+/// Returns:
+/// - `Some(Element)` with the summary, None when there are no sentences
+fn summary_box<'a>(
+    sentences: &'a [String],
+    length: SummaryLength,
+    adjustable: bool,
+) -> Option<Element<'a, Message>> {
+    if sentences.is_empty() {
+        return None;
+    }
+
+    Some(
+        container(
+            Column::with_capacity(sentences.len() + 1)
+                .push(
+                    Row::with_capacity(2)
+                        .push(text("Summary").size(22).width(Length::Fill))
+                        .push_maybe(adjustable.then(|| {
+                            pick_list(SummaryLength::ALL, Some(length), |l| {
+                                Message::MainPage(MainPageMessage::SummaryLengthSelected(l))
+                            })
+                        }))
+                        .align_y(Alignment::Center),
+                )
+                .extend(sentences.iter().map(|sentence| {
+                    text(format!("• {sentence}"))
+                        .size(18)
+                        .shaping(Advanced)
+                        .into()
+                }))
+                .spacing(6),
+        )
+        .padding(15)
+        .width(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.93, 0.95, 1.0))),
+            border: Border::default()
+                .color(theme.palette().primary)
+                .rounded(8)
+                .width(1),
+            ..Default::default()
+        })
+        .into(),
+    )
+}
+
 /// Render a full-page view for the currently active article, if any.
 ///
 /// Parameters:
 /// - `active_article`: index of the active article to show. If None, no page is rendered.
 /// - `search_result`: the fetched search result; used to get the article data. If None, no page is rendered.
 /// - `images_loaded`: slice of optional image handles; the handle at the same index as `active_article` is used for the article.
+/// - `loaded`: reader mode content and content images loaded for the active article
 ///
 /// Returns:
 /// - `Some(Element<'a, Message>)` when `active_article` and `search_result` are present (renders the article or an error element).
@@ -59,18 +218,22 @@ pub fn article_page<'a>(
     active_article: Option<&'a usize>,
     search_result: Option<&'a Result<NewsAPIArticlesSuccess, String>>,
     images_loaded: &'a [Option<Handle>],
+    loaded: LoadedContent<'a>,
 ) -> Option<Element<'a, Message>> {
     use MainPageMessage::*;
     use Message::MainPage as M;
 
     if let Some(index) = active_article {
-        match search_result {
-            Some(inner) => Some::<Element<'a, Message>>(
+        search_result.map(|inner| {
+            Into::<Element<'a, Message>>::into(
                 mouse_area(
                     container(match inner {
-                        Ok(data) => {
-                            article_view(&data.articles[*index], images_loaded[*index].as_ref())
-                        }
+                        Ok(data) => article_view(
+                            &data.articles[*index],
+                            images_loaded[*index].as_ref(),
+                            Some(*index),
+                            loaded,
+                        ),
                         // this should be impossible to reach under any conditions
                         // the index is only set if you click on an article card, which depends on the same search_result
                         Err(error) => error_element(error),
@@ -88,11 +251,9 @@ pub fn article_page<'a>(
                 )
                 .interaction(iced::mouse::Interaction::Idle)
                 .on_right_press(M(ActiveArticle(None)))
-                .on_press(M(ActiveArticle(None)))
-                .into(),
-            ),
-            None => None,
-        }
+                .on_press(M(ActiveArticle(None))),
+            )
+        })
     } else {
         None
     }
@@ -104,6 +265,9 @@ pub fn article_page<'a>(
 /// - `index`: index of this article (used in the on-press message).
 /// - `article`: article data (title shown).
 /// - `image`: optional image handle to display; when None a fallback is used.
+/// - `coverage`: set when the article leads a story covered by several articles, adds a strip to expand the story.
+/// - `compare_selection`: indices of the articles selected for comparison, the card has a checkbox to add itself.
+/// - `tone`: tone of the article, shown under the title.
 ///
 /// Returns:
 /// - `Element<'a, Message>` — a Button-styled card that, when pressed, sends
@@ -116,17 +280,26 @@ pub fn article_to_card<'a>(
     index: usize,
     article: &'a Article,
     image: Option<&Handle>,
+    coverage: Option<Coverage>,
+    compare_selection: &[usize],
+    tone: f32,
 ) -> Element<'a, Message> {
-    let content: Column<'_, Message> = Column::with_capacity(2)
+    let content: Column<'_, Message> = Column::with_capacity(4)
         .push(
-            text(&article.title)
-                .shaping(Advanced)
-                .size(18)
-                .width(Length::Fill)
-                .style(|_theme| text::Style {
-                    color: Some(Color::from_rgb(0.1, 0.1, 0.1)),
-                }),
+            Row::with_capacity(2)
+                .push(
+                    text(&article.title)
+                        .shaping(Advanced)
+                        .size(18)
+                        .width(Length::Fill)
+                        .style(|_theme| text::Style {
+                            color: Some(Color::from_rgb(0.1, 0.1, 0.1)),
+                        }),
+                )
+                .push(compare_checkbox(index, compare_selection)),
         )
+        .push(tone_indicator(tone))
+        .push_maybe(coverage.map(|coverage| coverage_strip(index, coverage)))
         .push(
             container(match &image {
                 Some(img) => {
@@ -149,11 +322,16 @@ pub fn article_to_card<'a>(
     .into()
 }
 
+/// Id of the scrollable holding the article cards, used to restore its position.
+pub const RESULTS_SCROLL_ID: &str = "results_scroll";
+
 /// Build a scrollable collection of article cards (arranged in rows) using `article_to_card`.
 ///
 /// Parameters:
 /// - `search_result`: Option<&Result<NewsAPIArticlesSuccess, String>> — if Some(Ok(data)) builds cards from `data.articles`; if Some(Err(e)) returns an error element; if None returns None.
+/// - `stories`: &[StoryEntry] — the articles to show in order, one card per story unless it's expanded.
 /// - `article_chunks`: usize — number of cards per row (must be > 0).
+/// - `compare_selection`: &[usize] — indices of the articles selected for comparison.
 /// - `images_loaded`: &'a [Option<Handle>] — image handles; the handle at each article's index is used if present.
 ///
 /// Returns:
@@ -165,22 +343,31 @@ pub fn article_to_card<'a>(
 /// - If `search_result` is Some(Ok(data)), callers must ensure `images_loaded.len() >= data.articles.len()` and indices used are valid.
 pub fn article_cards<'a>(
     search_result: Option<&'a Result<NewsAPIArticlesSuccess, String>>,
+    stories: &[StoryEntry],
     article_chunks: usize,
     images_loaded: &'a [Option<Handle>],
+    compare_selection: &[usize],
 ) -> Option<Element<'a, Message>> {
     match search_result {
         Some(Ok(data)) => Some::<Element<'a, Message>>(
             scrollable(
                 Column::with_children(
-                    data.articles
+                    stories
                         .iter()
-                        .enumerate()
-                        .collect::<Vec<(usize, &Article)>>()
+                        .filter_map(|entry| Some((entry, data.articles.get(entry.index)?)))
+                        .collect::<Vec<(&StoryEntry, &Article)>>()
                         .chunks(article_chunks)
                         .map(|chunk| {
                             Into::<Element<'_, Message>>::into(
-                                Row::with_children(chunk.iter().map(|(i, a)| {
-                                    article_to_card(*i, a, images_loaded[*i].as_ref())
+                                Row::with_children(chunk.iter().map(|(entry, a)| {
+                                    article_to_card(
+                                        entry.index,
+                                        a,
+                                        images_loaded[entry.index].as_ref(),
+                                        entry.coverage,
+                                        compare_selection,
+                                        entry.tone,
+                                    )
                                 }))
                                 .spacing(10)
                                 .align_y(Alignment::Center),
@@ -190,6 +377,10 @@ pub fn article_cards<'a>(
                 .spacing(5)
                 .padding(5),
             )
+            .id(scrollable::Id::new(RESULTS_SCROLL_ID))
+            .on_scroll(|viewport| {
+                Message::MainPage(MainPageMessage::ResultsScrolled(viewport.absolute_offset()))
+            })
             .spacing(5)
             .height(Length::Fill)
             .width(Length::Fill)
@@ -205,28 +396,75 @@ pub fn article_cards<'a>(
 /// Parameters:
 /// - `article`: &Article — article data to render (title, author/source, published timestamp, description, content, url).
 /// - `image`: Option<&Handle> — optional image handle to display above the article; when None a fallback is shown.
+/// - `index`: Option<usize> — index of the article in the results; None when it's shown in a window of its own, which leaves out the close, pop out and reader mode buttons.
+/// - `loaded`: LoadedContent — reader mode content, shown in place of the truncated content when it belongs to this article, and the images the content's HTML is rendered with.
 ///
 /// Returns:
 /// - `Element<'a, Message>` — a scrollable, styled article view containing title, image, metadata, body text and a "Read full article" button when a URL is present. Interaction callbacks are attached to close or interact with the view.
-pub fn article_view<'a>(article: &'a Article, image: Option<&Handle>) -> Element<'a, Message> {
-    mouse_area(
+pub fn article_view<'a>(
+    article: &'a Article,
+    image: Option<&Handle>,
+    index: Option<usize>,
+    loaded: LoadedContent<'a>,
+) -> Element<'a, Message> {
+    // reader mode content of another article is ignored
+    let reader = loaded
+        .reader
+        .filter(|reader| article.url.as_ref() == Some(&reader.url));
+
+    let view = mouse_area(
         container(
             Column::<Message, Theme>::with_capacity(3)
-                .push(
+                .push_maybe(index.map(|index| {
                     container(
-                        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
-                            .width(Length::Fixed(48.0))
-                            .style(close_button_style)
-                            .on_press(Message::MainPage(MainPageMessage::ActiveArticle(None))),
+                        Row::with_capacity(3)
+                            .push_maybe(article.url.as_ref().map(|_| {
+                                button(
+                                    text(match reader {
+                                        Some(_) => "Original text",
+                                        None => "Reader mode",
+                                    })
+                                    .size(18),
+                                )
+                                .padding(10)
+                                .style(button_style)
+                                .on_press(Message::MainPage(match reader {
+                                    Some(_) => MainPageMessage::CloseReader,
+                                    None => MainPageMessage::OpenReader(index),
+                                }))
+                            }))
+                            .push(
+                                button(text("Pop out").size(18))
+                                    .padding(10)
+                                    .style(button_style)
+                                    .on_press(Message::MainPage(MainPageMessage::PopOutArticle(
+                                        index,
+                                    ))),
+                            )
+                            .push(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(Length::Fixed(48.0))
+                                    .style(close_button_style)
+                                    .on_press(Message::MainPage(MainPageMessage::ActiveArticle(
+                                        None,
+                                    ))),
+                            )
+                            .spacing(10)
+                            .align_y(Alignment::Center),
                     )
                     .align_right(Length::Fill)
-                    .padding(10),
-                )
-                .push(horizontal_rule(6))
+                    .padding(10)
+                }))
+                .push_maybe(index.map(|_| horizontal_rule(6)))
                 .push(
                     scrollable(
-                        Column::<Message, Theme>::with_capacity(9)
+                        Column::<Message, Theme>::with_capacity(10)
                             .push(text(&article.title).size(44).shaping(Advanced))
+                            .push_maybe(summary_box(
+                                loaded.summary,
+                                loaded.summary_length,
+                                index.is_some(),
+                            ))
                             .push(
                                 container(match &image {
                                     Some(img) => Into::<Element<'a, Message>>::into(
@@ -260,14 +498,17 @@ pub fn article_view<'a>(article: &'a Article, image: Option<&Handle>) -> Element
                                     text(description).size(32).shaping(Advanced)
                                 }),
                             )
-                            .push_maybe(
-                                article
-                                    .content
-                                    .as_ref()
-                                    .map(|content| text(content).size(20).shaping(Advanced)),
-                            )
-                            .push_maybe(match (&article.description, &article.content) {
-                                (Some(_), _) | (_, Some(_)) => Some(horizontal_rule(6)),
+                            .push_maybe(match reader {
+                                // the full text replaces the truncated content
+                                Some(reader) => Some(reader_content(reader)),
+                                None => article.content.as_ref().map(|_| {
+                                    html_content(loaded.content_blocks, loaded.content_images, 20)
+                                }),
+                            })
+                            .push_maybe(match (&article.description, &article.content, reader) {
+                                (Some(_), _, _) | (_, Some(_), _) | (_, _, Some(_)) => {
+                                    Some(horizontal_rule(6))
+                                }
                                 _ => None,
                             })
                             .push_maybe(article.url.as_ref().map(|url| {
@@ -302,9 +543,14 @@ pub fn article_view<'a>(article: &'a Article, image: Option<&Handle>) -> Element
         }),
     )
     .on_press(Message::NoOp)
-    .on_right_press(Message::MainPage(MainPageMessage::ActiveArticle(None)))
-    .interaction(mouse::Interaction::Idle)
-    .into()
+    .interaction(mouse::Interaction::Idle);
+
+    match index {
+        Some(_) => view
+            .on_right_press(Message::MainPage(MainPageMessage::ActiveArticle(None)))
+            .into(),
+        None => view.into(),
+    }
 }
 
 fn tmpdir() -> PathBuf {
@@ -330,6 +576,7 @@ fn url_to_path(url: &str) -> PathBuf {
 ///
 /// Parameters:
 /// - `url`: the image URL to fetch.
+/// - `offline`: when true only the local cache is read, a missing image returns `NewsAPIError::Offline`.
 ///
 /// Returns:
 /// - `Ok(Bytes)` with the image data (validated).
@@ -338,7 +585,7 @@ fn url_to_path(url: &str) -> PathBuf {
 /// Notes:
 /// - Cache directory creation and file-write errors are handled; write failures are
 ///   logged from the background task and do not prevent returning the downloaded bytes.
-pub async fn get_image_from_url(url: &str) -> Result<Bytes, NewsAPIError> {
+pub async fn get_image_from_url(url: &str, offline: bool) -> Result<Bytes, NewsAPIError> {
     match create_dir(tmpdir()) {
         Ok(()) => (),
         Err(e) => match e.kind() {
@@ -351,6 +598,8 @@ pub async fn get_image_from_url(url: &str) -> Result<Bytes, NewsAPIError> {
 
     let bytes = if path.exists() {
         tokio::fs::read(path).await?.into()
+    } else if offline {
+        return Err(NewsAPIError::Offline);
     } else {
         let bytes = reqwest::get(url).await?.bytes().await?;
 
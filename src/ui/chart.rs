@@ -0,0 +1,372 @@
+use iced::Color;
+use iced::Pixels;
+use iced::Point;
+use iced::Rectangle;
+use iced::Renderer;
+use iced::Size;
+use iced::Theme;
+use iced::alignment;
+use iced::color;
+use iced::mouse;
+use iced::widget::canvas;
+use iced::widget::canvas::Frame;
+use iced::widget::canvas::Geometry;
+use iced::widget::canvas::Path;
+use iced::widget::canvas::Stroke;
+use iced::widget::canvas::Text;
+use std::fmt;
+
+/// Colors series are drawn in, in order.
+pub const SERIES_COLORS: [Color; 8] = [
+    color!(0x1a73e8),
+    color!(0xe8710a),
+    color!(0x188038),
+    color!(0xd93025),
+    color!(0x9334e6),
+    color!(0x12b5cb),
+    color!(0xe52592),
+    color!(0x80868b),
+];
+
+/// Room left of the plot for the value labels.
+const MARGIN_LEFT: f32 = 44.0;
+/// Room under the plot for the bucket labels.
+const MARGIN_BOTTOM: f32 = 24.0;
+const MARGIN_TOP: f32 = 10.0;
+const MARGIN_RIGHT: f32 = 10.0;
+
+/// Number of steps the value axis is divided in.
+const GRID_STEPS: usize = 4;
+
+/// Width a bucket label needs, labels are skipped so they don't overlap.
+const LABEL_WIDTH: f32 = 70.0;
+
+const LABEL_SIZE: f32 = 12.0;
+
+/// How a chart draws its series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartKind {
+    #[default]
+    Line,
+    Bar,
+}
+
+impl ChartKind {
+    pub const ALL: [ChartKind; 2] = [ChartKind::Line, ChartKind::Bar];
+}
+
+impl fmt::Display for ChartKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChartKind::Line => "Line chart",
+            ChartKind::Bar => "Bar chart",
+        })
+    }
+}
+
+/// One line, or one set of bars, of a chart.
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    pub color: Color,
+    /// a value for every bucket of the chart
+    pub values: Vec<f32>,
+}
+
+/// Chart of one or more series over a row of buckets, drawn on a canvas.
+///
+/// Hovering the chart shows the values of the bucket under the cursor.
+#[derive(Debug, Clone)]
+pub struct Chart<Message> {
+    /// label of every bucket, in order
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+    pub kind: ChartKind,
+    /// message sent when a bucket is clicked, for every bucket. Empty when the chart can't be clicked.
+    pub on_press: Vec<Message>,
+    /// bucket drawn highlighted
+    pub selected: Option<usize>,
+}
+
+impl<Message> Chart<Message> {
+    /// Area the series are drawn in, inside the axis labels.
+    fn plot_area(bounds: Rectangle) -> Rectangle {
+        Rectangle {
+            x: MARGIN_LEFT,
+            y: MARGIN_TOP,
+            width: (bounds.width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0),
+            height: (bounds.height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0),
+        }
+    }
+
+    /// Bucket under the cursor, None when the cursor isn't over the plot.
+    fn bucket_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let plot = Self::plot_area(bounds);
+        let position = cursor.position_in(bounds)?;
+        if self.labels.is_empty() || !plot.contains(position) {
+            return None;
+        }
+
+        let slot = plot.width / self.labels.len() as f32;
+        Some((((position.x - plot.x) / slot) as usize).min(self.labels.len() - 1))
+    }
+}
+
+/// Top of the value axis and the distance between its grid lines, rounded to 1, 2 or 5 times a power of ten.
+fn axis_scale(max: f32) -> (f32, f32) {
+    let raw = (max / GRID_STEPS as f32).max(1.0);
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(raw);
+
+    (step * GRID_STEPS as f32, step)
+}
+
+/// Label of a value, without decimals for whole numbers.
+fn format_value(value: f32) -> String {
+    if value.fract() == 0.0 {
+        format!("{value}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+impl<Message: Clone> canvas::Program<Message> for Chart<Message> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        if let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
+            && let Some(bucket) = self.bucket_at(bounds, cursor)
+            && let Some(message) = self.on_press.get(bucket)
+        {
+            return (canvas::event::Status::Captured, Some(message.clone()));
+        }
+
+        (canvas::event::Status::Ignored, None)
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match self.bucket_at(bounds, cursor) {
+            Some(bucket) if bucket < self.on_press.len() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let buckets = self.labels.len();
+        if buckets == 0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let plot = Self::plot_area(bounds);
+        let bottom = plot.y + plot.height;
+
+        let max = self
+            .series
+            .iter()
+            .flat_map(|s| s.values.iter().copied())
+            .fold(0.0, f32::max);
+        let (top, step) = axis_scale(max);
+
+        let slot = plot.width / buckets as f32;
+        let x_of = |bucket: usize| plot.x + slot * (bucket as f32 + 0.5);
+        let y_of = |value: f32| bottom - value / top * plot.height;
+
+        let text_color = theme.palette().text;
+        let label = |content: String, position: Point, horizontal, vertical| Text {
+            content,
+            position,
+            color: text_color,
+            size: Pixels(LABEL_SIZE),
+            horizontal_alignment: horizontal,
+            vertical_alignment: vertical,
+            ..Text::default()
+        };
+
+        if let Some(selected) = self.selected.filter(|s| *s < buckets) {
+            frame.fill_rectangle(
+                Point::new(plot.x + slot * selected as f32, plot.y),
+                Size::new(slot, plot.height),
+                Color {
+                    a: 0.12,
+                    ..theme.palette().primary
+                },
+            );
+        }
+
+        // value axis
+        for i in 0..=GRID_STEPS {
+            let value = step * i as f32;
+            let y = y_of(value);
+            frame.stroke(
+                &Path::line(Point::new(plot.x, y), Point::new(plot.x + plot.width, y)),
+                Stroke::default()
+                    .with_color(Color {
+                        a: if i == 0 { 0.6 } else { 0.15 },
+                        ..text_color
+                    })
+                    .with_width(1.0),
+            );
+            frame.fill_text(label(
+                format_value(value),
+                Point::new(plot.x - 6.0, y),
+                alignment::Horizontal::Right,
+                alignment::Vertical::Center,
+            ));
+        }
+
+        // bucket labels, as many as fit
+        let every = ((LABEL_WIDTH / slot).ceil() as usize).max(1);
+        for i in (0..buckets).step_by(every) {
+            frame.fill_text(label(
+                self.labels[i].clone(),
+                Point::new(x_of(i), bottom + 6.0),
+                alignment::Horizontal::Center,
+                alignment::Vertical::Top,
+            ));
+        }
+
+        match self.kind {
+            ChartKind::Line => {
+                for series in &self.series {
+                    let points: Vec<Point> = series
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| Point::new(x_of(i), y_of(*value)))
+                        .collect();
+
+                    if let [point] = points[..] {
+                        frame.fill(&Path::circle(point, 3.0), series.color);
+                        continue;
+                    }
+
+                    let path = Path::new(|builder| {
+                        for (i, point) in points.iter().enumerate() {
+                            if i == 0 {
+                                builder.move_to(*point);
+                            } else {
+                                builder.line_to(*point);
+                            }
+                        }
+                    });
+                    frame.stroke(
+                        &path,
+                        Stroke::default().with_color(series.color).with_width(2.0),
+                    );
+                }
+            }
+            ChartKind::Bar => {
+                // bars of a bucket stand side by side, leaving a gap between buckets
+                let width = slot * 0.8 / self.series.len().max(1) as f32;
+                for (s, series) in self.series.iter().enumerate() {
+                    for (i, value) in series.values.iter().enumerate() {
+                        if *value <= 0.0 {
+                            continue;
+                        }
+
+                        let y = y_of(*value);
+                        frame.fill_rectangle(
+                            Point::new(plot.x + slot * (i as f32 + 0.1) + width * s as f32, y),
+                            Size::new(width.max(1.0), bottom - y),
+                            series.color,
+                        );
+                    }
+                }
+            }
+        }
+
+        // values of the hovered bucket
+        if let Some(bucket) = self.bucket_at(bounds, cursor)
+            && let Some(position) = cursor.position_in(bounds)
+        {
+            let x = x_of(bucket);
+            frame.stroke(
+                &Path::line(Point::new(x, plot.y), Point::new(x, bottom)),
+                Stroke::default()
+                    .with_color(Color {
+                        a: 0.4,
+                        ..text_color
+                    })
+                    .with_width(1.0),
+            );
+
+            let lines: Vec<(String, Color)> =
+                std::iter::once((self.labels[bucket].clone(), text_color))
+                    .chain(self.series.iter().map(|series| {
+                        let value = series.values.get(bucket).copied().unwrap_or_default();
+                        (
+                            format!("{}: {}", series.name, format_value(value)),
+                            series.color,
+                        )
+                    }))
+                    .collect();
+
+            let line_height = LABEL_SIZE * 1.4;
+            let size = Size::new(
+                lines
+                    .iter()
+                    .map(|(line, _)| line.chars().count())
+                    .max()
+                    .unwrap_or_default() as f32
+                    * LABEL_SIZE
+                    * 0.6
+                    + 16.0,
+                lines.len() as f32 * line_height + 10.0,
+            );
+            // next to the cursor, on the side with room
+            let left = if position.x + 12.0 + size.width > bounds.width {
+                position.x - 12.0 - size.width
+            } else {
+                position.x + 12.0
+            };
+            let corner = Point::new(
+                left.max(0.0),
+                position.y.min(bounds.height - size.height).max(0.0),
+            );
+
+            frame.fill_rectangle(corner, size, Color::from_rgba(1.0, 1.0, 1.0, 0.95));
+            frame.stroke(
+                &Path::rectangle(corner, size),
+                Stroke::default()
+                    .with_color(theme.palette().primary)
+                    .with_width(1.0),
+            );
+            for (i, (line, color)) in lines.into_iter().enumerate() {
+                frame.fill_text(Text {
+                    color,
+                    ..label(
+                        line,
+                        Point::new(corner.x + 8.0, corner.y + 5.0 + i as f32 * line_height),
+                        alignment::Horizontal::Left,
+                        alignment::Vertical::Top,
+                    )
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,312 @@
+use crate::analysis::compare::Highlights;
+use crate::analysis::compare::PhraseKind;
+use crate::analysis::compare::compare_articles;
+use crate::newsapi::article::Article;
+use crate::newsapi::html::content_text;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Gradient;
+use iced::Length;
+use iced::color;
+use iced::widget::Column;
+use iced::widget::Row;
+use iced::widget::button;
+use iced::widget::checkbox;
+use iced::widget::horizontal_rule;
+use iced::widget::rich_text;
+use iced::widget::scrollable;
+use iced::widget::span;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::text::Span;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::f32::consts::FRAC_PI_4;
+use std::ops::Range;
+
+/// Most articles that can be compared at once.
+pub const MAX_COMPARED: usize = 4;
+
+/// Background of wording shared with another article.
+const SHARED_COLOR: Color = color!(0xffe066);
+
+/// Background of words no other article uses.
+const UNIQUE_COLOR: Color = color!(0xb3d9ff);
+
+/// Checkbox selecting an article for comparison.
+///
+/// Parameters:
+/// - `index`: index of the article in the shown results
+/// - `selection`: indices of the articles selected for comparison
+///
+/// Returns:
+/// - a checkbox sending `ToggleCompare(index)`, disabled when enough other articles are selected
+pub fn compare_checkbox(index: usize, selection: &[usize]) -> Element<'static, Message> {
+    let selected = selection.contains(&index);
+
+    checkbox("", selected)
+        .on_toggle_maybe(
+            (selected || selection.len() < MAX_COMPARED)
+                .then_some(move |_| Message::MainPage(MainPageMessage::ToggleCompare(index))),
+        )
+        .size(18)
+        .into()
+}
+
+/// Bar above the results while articles are selected for comparison.
+///
+/// Parameters:
+/// - `selected`: number of selected articles
+///
+/// Returns:
+/// - `Some(Element)` with the count and buttons to compare or clear the selection, None when nothing is selected
+pub fn compare_bar(selected: usize) -> Option<Element<'static, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if selected == 0 {
+        return None;
+    }
+
+    let hint = if selected < 2 {
+        "select another article to compare".to_string()
+    } else {
+        format!("up to {MAX_COMPARED} articles can be compared")
+    };
+
+    Some(
+        container(
+            row![
+                text(format!("{selected} selected · {hint}"))
+                    .size(16)
+                    .width(Length::Fill),
+                button(text("Compare"))
+                    .style(button_style)
+                    .on_press_maybe((selected >= 2).then_some(M(OpenComparison))),
+                button(text("Clear"))
+                    .style(button_style)
+                    .on_press(M(ClearCompareSelection)),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .padding([5, 15])
+        .into(),
+    )
+}
+
+/// Render text with its shared and unique wording highlighted.
+fn highlighted(
+    content: &str,
+    highlights: &[(Range<usize>, PhraseKind)],
+    size: u16,
+) -> Element<'static, Message> {
+    let mut spans: Vec<Span<'static, Message>> = Vec::with_capacity(highlights.len() * 2 + 1);
+    let mut position = 0;
+
+    for (range, kind) in highlights {
+        if range.start > position {
+            spans.push(span(content[position..range.start].to_string()));
+        }
+
+        spans.push(
+            span(content[range.clone()].to_string()).background(match kind {
+                PhraseKind::Shared => SHARED_COLOR,
+                PhraseKind::Unique => UNIQUE_COLOR,
+            }),
+        );
+        position = range.end;
+    }
+
+    if position < content.len() {
+        spans.push(span(content[position..].to_string()));
+    }
+
+    rich_text(spans).size(size).into()
+}
+
+/// Small colored square with a label, explaining a highlight color.
+fn legend(color: Color, label: &str) -> Element<'_, Message> {
+    row![
+        container(text(""))
+            .width(14)
+            .height(14)
+            .style(move |_theme| container::Style {
+                background: Some(Background::Color(color)),
+                border: Border::default().rounded(3),
+                ..Default::default()
+            }),
+        text(label).size(14),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Build the column of one compared article.
+///
+/// Parameters:
+/// - `article`: the article
+/// - `texts`: its title, description and content as compared
+/// - `highlights`: highlights of each of `texts`
+fn article_column(
+    article: &Article,
+    texts: &[String],
+    highlights: &[Highlights],
+) -> Element<'static, Message> {
+    let published = article
+        .published_at
+        .as_ref()
+        .and_then(|t| t.parse::<DateTime<Utc>>().ok())
+        .map(|dt| {
+            DateTime::<Local>::from(dt)
+                .format("%b %d, %Y at %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| "Unknown time".to_string());
+
+    let sizes = [22, 17, 15];
+
+    Column::with_capacity(6)
+        .push(
+            text(article.source.name.clone().unwrap_or_default())
+                .size(18)
+                .shaping(Advanced),
+        )
+        .push(text(published).size(13).color(color!(0x555555)))
+        .push(horizontal_rule(6))
+        .extend(
+            texts
+                .iter()
+                .zip(highlights)
+                .zip(sizes)
+                .filter(|((text, _), _)| !text.is_empty())
+                .map(|((text, highlights), size)| highlighted(text, highlights, size)),
+        )
+        .push_maybe(article.url.clone().map(|url| {
+            button(text("Read full article"))
+                .style(button_style)
+                .width(Length::Fill)
+                .on_press(Message::OpenLink(url))
+        }))
+        .spacing(8)
+        .width(Length::FillPortion(1))
+        .into()
+}
+
+/// Render the comparison of the selected articles, side by side.
+///
+/// Parameters:
+/// - `compare_page`: whether the page should be shown. If false, returns None.
+/// - `articles`: the articles to compare, in the order they were selected
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with a column per article, showing its source, publishing time,
+///   title, description and content with shared and unique wording highlighted.
+/// - `None` when the page is hidden.
+pub fn compare_page<'a>(compare_page: bool, articles: &[&Article]) -> Option<Element<'a, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !compare_page {
+        return None;
+    }
+
+    let texts: Vec<Vec<String>> = articles
+        .iter()
+        .map(|article| {
+            vec![
+                article.title.clone(),
+                article.description.clone().unwrap_or_default(),
+                article
+                    .content
+                    .as_deref()
+                    .map(content_text)
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let highlights = compare_articles(
+        &texts
+            .iter()
+            .map(|texts| texts.iter().map(String::as_str).collect())
+            .collect::<Vec<_>>(),
+    );
+
+    let columns = Row::with_children(
+        articles
+            .iter()
+            .zip(&texts)
+            .zip(&highlights)
+            .map(|((article, texts), highlights)| article_column(article, texts, highlights)),
+    )
+    .spacing(20)
+    .padding(iced::Padding::ZERO.right(10));
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        row![
+                            text("Compare coverage").size(24),
+                            legend(SHARED_COLOR, "shared wording"),
+                            legend(UNIQUE_COLOR, "only in this article"),
+                            container(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(48)
+                                    .style(close_button_style)
+                                    .on_press(M(CloseComparison)),
+                            )
+                            .align_right(Length::Fill),
+                        ]
+                        .padding(5)
+                        .spacing(20)
+                        .align_y(Alignment::Center),
+                        horizontal_rule(6),
+                        scrollable(columns).height(Length::Fill),
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(1600)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(CloseComparison))
+        .on_press(M(CloseComparison))
+        .into(),
+    )
+}
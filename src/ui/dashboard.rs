@@ -0,0 +1,470 @@
+use crate::newsapi::article::Article;
+use crate::newsapi::source::Source;
+use crate::ui::Message;
+use crate::ui::chart::Chart;
+use crate::ui::chart::ChartKind;
+use crate::ui::chart::SERIES_COLORS;
+use crate::ui::chart::Series;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Timelike;
+use chrono::Utc;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Gradient;
+use iced::Length;
+use iced::color;
+use iced::widget::Row;
+use iced::widget::button;
+use iced::widget::canvas;
+use iced::widget::horizontal_rule;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_4;
+use std::fmt;
+
+/// Most bars a chart of sources, languages or countries shows, the smallest are left out.
+const MAX_BARS: usize = 15;
+
+/// Narrows the shown results to the articles behind one bar of a dashboard chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridFilter {
+    /// articles from the source with this name
+    Source(String),
+    /// articles published in this local hour of the day
+    Hour(u32),
+    /// articles from sources of this category
+    Category(String),
+    /// articles from sources in this language
+    Language(String),
+    /// articles from sources in this country
+    Country(String),
+    /// articles with or without an image
+    Image(bool),
+    /// articles with or without an author
+    Author(bool),
+}
+
+impl GridFilter {
+    /// Whether an article is shown while the filter is active.
+    ///
+    /// Parameters:
+    /// - `article`: the article
+    /// - `sources`: source metadata by source id, articles of unknown sources don't match source properties
+    pub fn matches(&self, article: &Article, sources: &HashMap<&str, &Source>) -> bool {
+        let source = || article.source.id.as_deref().and_then(|id| sources.get(id));
+
+        match self {
+            GridFilter::Source(name) => source_name(article) == name,
+            GridFilter::Hour(hour) => published_hour(article) == Some(*hour),
+            GridFilter::Category(category) => source().is_some_and(|s| s.category == *category),
+            GridFilter::Language(language) => source().is_some_and(|s| s.language == *language),
+            GridFilter::Country(country) => source().is_some_and(|s| s.country == *country),
+            GridFilter::Image(image) => article.url_to_image.is_some() == *image,
+            GridFilter::Author(author) => has_author(article) == *author,
+        }
+    }
+}
+
+impl fmt::Display for GridFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridFilter::Source(name) => write!(f, "from {name}"),
+            GridFilter::Hour(hour) => {
+                write!(f, "published between {hour:02}:00 and {:02}:00", hour + 1)
+            }
+            GridFilter::Category(category) => write!(f, "from {category} sources"),
+            GridFilter::Language(language) => {
+                write!(f, "from sources in {}", language.to_uppercase())
+            }
+            GridFilter::Country(country) => {
+                write!(f, "from sources in {}", country.to_uppercase())
+            }
+            GridFilter::Image(true) => f.write_str("with an image"),
+            GridFilter::Image(false) => f.write_str("without an image"),
+            GridFilter::Author(true) => f.write_str("with an author"),
+            GridFilter::Author(false) => f.write_str("without an author"),
+        }
+    }
+}
+
+/// Name an article's source is counted under.
+fn source_name(article: &Article) -> &str {
+    article
+        .source
+        .name
+        .as_deref()
+        .or(article.source.id.as_deref())
+        .unwrap_or("Unknown source")
+}
+
+/// Local hour of the day an article was published, None without a publishing time.
+fn published_hour(article: &Article) -> Option<u32> {
+    let published: DateTime<Utc> = article.published_at.as_ref()?.parse().ok()?;
+    Some(DateTime::<Local>::from(published).hour())
+}
+
+fn has_author(article: &Article) -> bool {
+    article
+        .author
+        .as_ref()
+        .is_some_and(|a| !a.trim().is_empty())
+}
+
+/// Count how often every value occurs, most frequent first, leaving out all but the `max` most frequent.
+fn ranked<'a>(values: impl Iterator<Item = &'a str>, max: usize) -> Vec<(&'a str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    ranked.truncate(max);
+    ranked
+}
+
+/// Single series bar chart, filtering the grid with the filter of the clicked bar.
+///
+/// Parameters:
+/// - `name`: what the bars count
+/// - `bars`: label, value and filter of every bar
+/// - `active`: the active grid filter, its bar is highlighted
+fn bar_chart(
+    name: &str,
+    bars: Vec<(String, usize, GridFilter)>,
+    active: Option<&GridFilter>,
+) -> Chart<Message> {
+    let selected = bars.iter().position(|(_, _, f)| Some(f) == active);
+    let mut labels = Vec::with_capacity(bars.len());
+    let mut values = Vec::with_capacity(bars.len());
+    let mut on_press = Vec::with_capacity(bars.len());
+
+    for (label, value, filter) in bars {
+        labels.push(label);
+        values.push(value as f32);
+        on_press.push(Message::MainPage(MainPageMessage::GridFilterSelected(
+            filter,
+        )));
+    }
+
+    Chart {
+        labels,
+        series: vec![Series {
+            name: name.to_string(),
+            color: SERIES_COLORS[0],
+            values,
+        }],
+        kind: ChartKind::Bar,
+        on_press,
+        selected,
+    }
+}
+
+/// A chart with its title and a line about what it shows, the chart is left out when there's nothing to chart.
+fn chart_block<'a>(
+    title: &'a str,
+    subtitle: String,
+    chart: Option<Chart<Message>>,
+) -> Element<'a, Message> {
+    column![
+        text(title).size(18),
+        text(subtitle)
+            .size(13)
+            .color(color!(0x555555))
+            .shaping(Advanced),
+    ]
+    .push_maybe(chart.map(|chart| canvas(chart).width(Length::Fill).height(240)))
+    .spacing(5)
+    .width(Length::FillPortion(1))
+    .into()
+}
+
+/// Percentage of `part` in `total`, rounded down.
+fn percent(part: usize, total: usize) -> usize {
+    (part * 100).checked_div(total).unwrap_or_default()
+}
+
+/// Render the source analytics dashboard when `dashboard_page` is true.
+///
+/// Parameters:
+/// - `dashboard_page`: whether the page should be shown. If false, returns None.
+/// - `articles`: the shown results
+/// - `sources`: the source list, None when it couldn't be loaded
+/// - `enabled_sources`: whether every source is enabled, all sources are described when none is
+/// - `active`: the active grid filter
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with charts of the results per source and hour of day, the
+///   enabled sources per category, language and country, and how many results have an image or author.
+///   Clicking a bar filters the results.
+/// - `None` when the page is hidden.
+pub fn dashboard_page<'a>(
+    dashboard_page: bool,
+    articles: &[Article],
+    sources: Option<&[Source]>,
+    enabled_sources: &HashMap<String, bool>,
+    active: Option<&GridFilter>,
+) -> Option<Element<'a, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !dashboard_page {
+        return None;
+    }
+
+    let total = articles.len();
+
+    let per_source = ranked(articles.iter().map(source_name), MAX_BARS);
+    let source_chart = (!per_source.is_empty()).then(|| {
+        bar_chart(
+            "Articles",
+            per_source
+                .iter()
+                .map(|(name, count)| {
+                    (
+                        name.to_string(),
+                        *count,
+                        GridFilter::Source(name.to_string()),
+                    )
+                })
+                .collect(),
+            active,
+        )
+    });
+
+    let mut hours = [0; 24];
+    for hour in articles.iter().filter_map(published_hour) {
+        hours[hour as usize] += 1;
+    }
+    let hour_chart = (total > 0).then(|| {
+        bar_chart(
+            "Articles",
+            (0..24)
+                .map(|hour| {
+                    (
+                        format!("{hour:02}:00"),
+                        hours[hour as usize],
+                        GridFilter::Hour(hour),
+                    )
+                })
+                .collect(),
+            active,
+        )
+    });
+
+    let with_image = articles.iter().filter(|a| a.url_to_image.is_some()).count();
+    let with_author = articles.iter().filter(|a| has_author(a)).count();
+    let completeness_chart = (total > 0).then(|| {
+        bar_chart(
+            "Articles",
+            vec![
+                ("With image".into(), with_image, GridFilter::Image(true)),
+                (
+                    "No image".into(),
+                    total - with_image,
+                    GridFilter::Image(false),
+                ),
+                ("With author".into(), with_author, GridFilter::Author(true)),
+                (
+                    "No author".into(),
+                    total - with_author,
+                    GridFilter::Author(false),
+                ),
+            ],
+            active,
+        )
+    });
+
+    // the described sources, all of them when none is enabled
+    let described: Vec<&Source> = sources
+        .map(|sources| {
+            let enabled: Vec<&Source> = sources
+                .iter()
+                .filter(|s| enabled_sources.get(&s.id).copied().unwrap_or_default())
+                .collect();
+            if enabled.is_empty() {
+                sources.iter().collect()
+            } else {
+                enabled
+            }
+        })
+        .unwrap_or_default();
+    let all_sources = described.len() == sources.map(<[Source]>::len).unwrap_or_default();
+
+    let source_property = |property: fn(&Source) -> &str, filter: fn(String) -> GridFilter| {
+        let ranked = ranked(described.iter().map(|s| property(s)), MAX_BARS);
+        (!ranked.is_empty()).then(|| {
+            bar_chart(
+                "Sources",
+                ranked
+                    .into_iter()
+                    .map(|(value, count)| (value.to_uppercase(), count, filter(value.to_string())))
+                    .collect(),
+                active,
+            )
+        })
+    };
+
+    let sources_line = match sources {
+        None => "The source list isn't loaded".to_string(),
+        Some(_) if all_sources => format!("All {} sources", described.len()),
+        Some(_) => format!("The {} enabled sources", described.len()),
+    };
+
+    let charts = column![
+        row![
+            chart_block(
+                "Articles per source",
+                format!("{total} shown articles from {} sources", per_source.len()),
+                source_chart,
+            ),
+            chart_block(
+                "Publishing time",
+                "Shown articles by local hour of day".to_string(),
+                hour_chart,
+            ),
+        ]
+        .spacing(30),
+        row![
+            chart_block(
+                "Categories",
+                sources_line.clone(),
+                source_property(|s| &s.category, GridFilter::Category),
+            ),
+            chart_block(
+                "Languages",
+                sources_line.clone(),
+                source_property(|s| &s.language, GridFilter::Language),
+            ),
+        ]
+        .spacing(30),
+        row![
+            chart_block(
+                "Countries",
+                sources_line,
+                source_property(|s| &s.country, GridFilter::Country),
+            ),
+            chart_block(
+                "Images and authors",
+                format!(
+                    "{}% of shown articles have an image, {}% an author",
+                    percent(with_image, total),
+                    percent(with_author, total)
+                ),
+                completeness_chart,
+            ),
+        ]
+        .spacing(30),
+    ]
+    .spacing(30)
+    .padding(iced::Padding::ZERO.right(10));
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        Row::with_capacity(3)
+                            .push(text("Source analytics").size(24))
+                            .push(
+                                text("Click a bar to filter the results")
+                                    .size(14)
+                                    .color(color!(0x555555))
+                            )
+                            .push(
+                                container(
+                                    button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                        .width(48)
+                                        .style(close_button_style)
+                                        .on_press(M(ToggleDashboardPage)),
+                                )
+                                .align_right(Length::Fill),
+                            )
+                            .padding(5)
+                            .spacing(20)
+                            .align_y(Alignment::Center),
+                        horizontal_rule(6),
+                        scrollable(charts).height(Length::Fill),
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(1400)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(ToggleDashboardPage))
+        .on_press(M(ToggleDashboardPage))
+        .into(),
+    )
+}
+
+/// Bar above the results while a dashboard filter is active.
+///
+/// Parameters:
+/// - `filter`: the active filter, None hides the bar
+/// - `shown`: number of articles the filter lets through
+///
+/// Returns:
+/// - `Some(Element)` telling what's shown with a button to show all results again
+pub fn grid_filter_notice(
+    filter: Option<&GridFilter>,
+    shown: usize,
+) -> Option<Element<'static, Message>> {
+    let filter = filter?;
+    let plural = if shown == 1 { "" } else { "s" };
+
+    Some(
+        container(
+            Row::with_capacity(2)
+                .push(
+                    text(format!("Showing {shown} article{plural} {filter}"))
+                        .size(16)
+                        .shaping(Advanced)
+                        .width(Length::Fill),
+                )
+                .push(
+                    button(text("Show all"))
+                        .style(button_style)
+                        .on_press(Message::MainPage(MainPageMessage::ClearGridFilter)),
+                )
+                .spacing(10)
+                .align_y(Alignment::Center),
+        )
+        .padding([5, 15])
+        .into(),
+    )
+}
@@ -0,0 +1,245 @@
+use crate::analysis::facets::Facet;
+use crate::analysis::facets::FacetKind;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use crate::ui::style::text_input_style;
+use iced::Alignment;
+use iced::Element;
+use iced::Length;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::column;
+use iced::widget::container;
+use iced::widget::horizontal_rule;
+use iced::widget::row;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::text_input;
+
+/// Setting the gazetteer entries are stored under, one per line.
+pub const GAZETTEER_KEY: &str = "gazetteer";
+
+/// A facet the results are narrowed down by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetFilter {
+    pub kind: FacetKind,
+    pub key: String,
+    /// hide the articles mentioning the facet instead of showing only them
+    pub exclude: bool,
+}
+
+impl FacetFilter {
+    /// Whether the article at `index` in the results passes the filter.
+    ///
+    /// A facet missing from `facets` isn't mentioned by any article.
+    pub fn matches(&self, index: usize, facets: &[Facet]) -> bool {
+        let mentioned = facets
+            .iter()
+            .find(|f| f.kind == self.kind && f.key == self.key)
+            .is_some_and(|f| f.articles.binary_search(&index).is_ok());
+
+        mentioned != self.exclude
+    }
+}
+
+/// Build a facet entry, showing how many articles mention it and whether it's filtered on.
+fn facet_entry<'a>(facet: &'a Facet, filter: Option<&FacetFilter>) -> Element<'a, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let marker = match filter {
+        Some(FacetFilter { exclude: false, .. }) => "✓ ",
+        Some(FacetFilter { exclude: true, .. }) => "✕ ",
+        None => "",
+    };
+
+    button(
+        row![
+            text(format!("{marker}{}", facet.label))
+                .shaping(Advanced)
+                .width(Length::Fill),
+            text(facet.articles.len()).size(13),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center),
+    )
+    .on_press(M(FacetPressed(facet.kind, facet.key.clone())))
+    .style(button_style)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Sidebar listing the people, organizations, places and key phrases of the results.
+///
+/// Parameters:
+/// - `facets`: the facets of the results, grouped by kind
+/// - `filters`: the facets the results are narrowed down by
+/// - `shown`: number of articles the filters leave
+/// - `gazetteer`: the user's gazetteer entries
+/// - `gazetteer_input`: content of the gazetteer entry input
+pub fn facet_sidebar<'a>(
+    facets: &'a [Facet],
+    filters: &[FacetFilter],
+    shown: usize,
+    gazetteer: &'a [String],
+    gazetteer_input: &'a str,
+) -> Element<'a, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let mut list = Column::new()
+        .spacing(5)
+        .padding(iced::Padding::ZERO.right(10));
+    if facets.is_empty() {
+        list = list.push(text("No names or phrases found in the results."));
+    }
+    for kind in FacetKind::ALL {
+        let mut of_kind = facets.iter().filter(|f| f.kind == kind).peekable();
+        if of_kind.peek().is_none() {
+            continue;
+        }
+
+        list = list.push(text(kind.to_string()).size(16));
+        for facet in of_kind {
+            let filter = filters
+                .iter()
+                .find(|f| f.kind == facet.kind && f.key == facet.key);
+            list = list.push(facet_entry(facet, filter));
+        }
+    }
+
+    list = list
+        .push(horizontal_rule(6))
+        .push(text("Gazetteer").size(16))
+        .push(text("Names to always recognize, like \"place: Kyiv\" or \"org: OpenAI\".").size(13))
+        .push(
+            row![
+                text_input("person: Jane Doe", gazetteer_input)
+                    .on_input(|s| M(GazetteerInputOnInput(s)))
+                    .on_submit(M(GazetteerEntryAdded))
+                    .style(text_input_style)
+                    .width(Length::Fill),
+                button(text("Add"))
+                    .style(button_style)
+                    .on_press(M(GazetteerEntryAdded)),
+            ]
+            .spacing(5),
+        );
+    for (i, entry) in gazetteer.iter().enumerate() {
+        list = list.push(
+            row![
+                text(entry).shaping(Advanced).width(Length::Fill),
+                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                    .width(32)
+                    .style(close_button_style)
+                    .on_press(M(GazetteerEntryRemoved(i))),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let plural = if shown == 1 { "" } else { "s" };
+    container(
+        column![
+            text("Facets").size(20),
+            text("Click to show only the articles mentioning a facet, Shift+click to hide them.")
+                .size(13),
+        ]
+        .push_maybe((!filters.is_empty()).then(|| {
+            row![
+                text(format!("Showing {shown} article{plural}"))
+                    .size(13)
+                    .width(Length::Fill),
+                button(text("Clear"))
+                    .style(button_style)
+                    .on_press(M(ClearFacetFilters)),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center)
+        }))
+        .push(horizontal_rule(6))
+        .push(scrollable(list).height(Length::Fill))
+        .spacing(5),
+    )
+    .padding(iced::Padding::ZERO.right(15).bottom(5))
+    .width(280)
+    .height(Length::Fill)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facet(kind: FacetKind, key: &str, articles: &[usize]) -> Facet {
+        Facet {
+            kind,
+            key: key.to_string(),
+            label: key.to_string(),
+            articles: articles.to_vec(),
+        }
+    }
+
+    fn filter(kind: FacetKind, key: &str, exclude: bool) -> FacetFilter {
+        FacetFilter {
+            kind,
+            key: key.to_string(),
+            exclude,
+        }
+    }
+
+    fn passing(filters: &[FacetFilter], facets: &[Facet]) -> Vec<usize> {
+        (0..5)
+            .filter(|&i| filters.iter().all(|f| f.matches(i, facets)))
+            .collect()
+    }
+
+    #[test]
+    fn include_and_exclude_filters() {
+        let facets = [
+            facet(FacetKind::Person, "jerome powell", &[0, 1, 3]),
+            facet(FacetKind::Place, "kyiv", &[2, 3]),
+            // same key, another kind
+            facet(FacetKind::Keyphrase, "kyiv", &[4]),
+        ];
+
+        assert_eq!(
+            passing(
+                &[filter(FacetKind::Person, "jerome powell", false)],
+                &facets
+            ),
+            [0, 1, 3]
+        );
+        assert_eq!(
+            passing(&[filter(FacetKind::Place, "kyiv", true)], &facets),
+            [0, 1, 4]
+        );
+        assert_eq!(
+            passing(
+                &[
+                    filter(FacetKind::Person, "jerome powell", false),
+                    filter(FacetKind::Place, "kyiv", true),
+                ],
+                &facets
+            ),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn facets_no_longer_found_match_no_article() {
+        let facets = [facet(FacetKind::Person, "jerome powell", &[0])];
+
+        assert!(passing(&[filter(FacetKind::Place, "kyiv", false)], &facets).is_empty());
+        assert_eq!(
+            passing(&[filter(FacetKind::Place, "kyiv", true)], &facets),
+            [0, 1, 2, 3, 4]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::archive::history::HistoryEntry;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use chrono::DateTime;
+use chrono::Local;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Gradient;
+use iced::Length;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::horizontal_rule;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::f32::consts::FRAC_PI_4;
+
+/// Build a history entry: the query, its sources and when it was run, with re-run and save buttons.
+fn history_entry(entry: &HistoryEntry) -> Element<'_, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let searched_at: DateTime<Local> = DateTime::from(entry.searched_at);
+    let label = match entry.query.as_str() {
+        "" => "Top headlines",
+        q => q,
+    };
+    let sources = match entry.sources.as_str() {
+        "" => "All sources".to_string(),
+        s => s.replace(',', ", "),
+    };
+
+    row![
+        column![
+            text(label).size(20).shaping(Advanced),
+            text(format!(
+                "{sources} · {}",
+                searched_at.format("%B %d, %Y at %H:%M")
+            ))
+            .size(13)
+            .shaping(Advanced),
+        ]
+        .width(Length::Fill),
+        button(text("Run"))
+            .style(button_style)
+            .on_press(M(HistoryRerun(entry.id))),
+        button(text("Save"))
+            .style(button_style)
+            .on_press(M(HistorySave(entry.id))),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Render the search history panel when `history_page` is true.
+///
+/// Parameters:
+/// - `history_page`: whether the panel should be shown. If false, returns None.
+/// - `history`: the search history, most recent first
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with a clear button and one entry per submitted search.
+/// - `None` when the panel is hidden.
+pub fn history_page(history_page: bool, history: &[HistoryEntry]) -> Option<Element<'_, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !history_page {
+        return None;
+    }
+
+    let list: Element<'_, Message> = if history.is_empty() {
+        text("Searches you submit are listed here.").into()
+    } else {
+        scrollable(
+            Column::with_children(history.iter().map(history_entry))
+                .spacing(8)
+                .padding([0, 10]),
+        )
+        .spacing(5)
+        .into()
+    };
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        row![
+                            text("History").size(24),
+                            button(text("Clear history"))
+                                .style(button_style)
+                                .padding(10)
+                                .on_press_maybe((!history.is_empty()).then_some(M(ClearHistory))),
+                            container(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(48)
+                                    .style(close_button_style)
+                                    .on_press(M(ToggleHistoryPage)),
+                            )
+                            .align_right(Length::Fill),
+                        ]
+                        .padding(5)
+                        .spacing(15)
+                        .align_y(Alignment::Center),
+                        horizontal_rule(6),
+                        list,
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(900)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(ToggleHistoryPage))
+        .on_press(M(ToggleHistoryPage))
+        .into(),
+    )
+}
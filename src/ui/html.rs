@@ -0,0 +1,115 @@
+use crate::newsapi::html::ContentBlock;
+use crate::newsapi::html::TextKind;
+use crate::newsapi::html::TextRun;
+use crate::ui::APP_FONT;
+use crate::ui::Message;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Font;
+use iced::Length::Shrink;
+use iced::Theme;
+use iced::font::Style;
+use iced::font::Weight;
+use iced::widget::Column;
+use iced::widget::Image;
+use iced::widget::image::Handle;
+use iced::widget::rich_text;
+use iced::widget::span;
+use iced::widget::text::Span;
+use iced::widget::{container, row, text};
+use std::collections::HashMap;
+
+/// Turn a run of text into a span, links open in the browser when clicked.
+///
+/// Plain runs inherit the font of their block, bold and italic runs only change its weight or style.
+fn run_span(run: &TextRun, block_font: Font) -> Span<'_, Message> {
+    let mut span = span(&run.text);
+
+    if run.bold || run.italic {
+        span = span.font(Font {
+            weight: if run.bold {
+                Weight::Bold
+            } else {
+                block_font.weight
+            },
+            style: if run.italic {
+                Style::Italic
+            } else {
+                block_font.style
+            },
+            ..block_font
+        });
+    }
+
+    match &run.link {
+        Some(link) => span
+            .color(Theme::Light.palette().primary)
+            .underline(true)
+            .link(Message::OpenLink(link.clone())),
+        None => span,
+    }
+}
+
+/// Render article content that may contain HTML as formatted text.
+///
+/// Parameters:
+/// - `blocks`: the article content, parsed with `content_blocks` when the article was opened
+/// - `images`: loaded images of the content by url, images that aren't loaded yet are left out
+/// - `size`: text size of paragraphs, headings are larger
+///
+/// Returns:
+/// - `Element<'a, Message>` with the paragraphs, headings, quotes, list items and images of the content
+pub fn html_content<'a>(
+    blocks: &'a [ContentBlock],
+    images: &HashMap<String, Handle>,
+    size: u16,
+) -> Element<'a, Message> {
+    Column::with_capacity(blocks.len())
+        .extend(blocks.iter().filter_map(|block| {
+            match block {
+                ContentBlock::Image(url) => Some(
+                    container(Image::new(images.get(url)?.clone()).height(Shrink))
+                        .max_height(800)
+                        .into(),
+                ),
+                ContentBlock::Text(kind, runs) => {
+                    let font = match kind {
+                        TextKind::Heading => Font {
+                            weight: Weight::Bold,
+                            ..APP_FONT
+                        },
+                        _ => APP_FONT,
+                    };
+                    let spans: Vec<_> = runs.iter().map(|run| run_span(run, font)).collect();
+
+                    Some(match kind {
+                        TextKind::Paragraph => rich_text(spans).size(size).into(),
+                        TextKind::Heading => rich_text(spans).size(size + 6).font(font).into(),
+                        TextKind::Quote => container(rich_text(spans).size(size))
+                            .padding([5, 15])
+                            .style(|theme: &Theme| container::Style {
+                                background: Some(Background::Color(Color::from_rgba(
+                                    0.0, 0.0, 0.0, 0.04,
+                                ))),
+                                border: Border::default()
+                                    .color(theme.palette().primary)
+                                    .width(1)
+                                    .rounded(5),
+                                ..Default::default()
+                            })
+                            .into(),
+                        TextKind::ListItem { depth, marker } => {
+                            row![text(marker).size(size), rich_text(spans).size(size)]
+                                .spacing(8)
+                                .padding(iced::Padding::ZERO.left(20.0 * (depth - 1) as f32))
+                                .into()
+                        }
+                    })
+                }
+            }
+        }))
+        .spacing(10)
+        .into()
+}
@@ -1,40 +1,145 @@
-use crate::fetch_top;
+use crate::analysis::cluster::story_clusters;
+use crate::analysis::facets::Facet;
+use crate::analysis::facets::FacetKind;
+use crate::analysis::facets::Gazetteer;
+use crate::analysis::facets::extract_facets;
+use crate::analysis::sentiment::article_sentiment;
+use crate::archive::Archive;
+use crate::archive::ArchiveFilter;
+use crate::archive::ArchiveIndex;
+use crate::archive::history::HistoryEntry;
+use crate::archive::saved::SavedSearch;
+use crate::archive::trends::TermCount;
+use crate::archive::trends::TrendInterval;
+use crate::archive::watch::Alert;
+use crate::archive::watch::WatchTerm;
+use crate::newsapi::EverythingParams;
 use crate::newsapi::NewsAPIArticlesSuccess;
 use crate::newsapi::NewsAPISourcesSuccess;
+use crate::newsapi::SortBy;
 use crate::newsapi::article::Article;
-use crate::newsapi::search_articles;
+use crate::newsapi::dedup::DuplicateMode;
+use crate::newsapi::dedup::find_duplicates;
+use crate::newsapi::fetch_articles;
+use crate::newsapi::fetch_sources;
+use crate::newsapi::html::ContentBlock;
+use crate::newsapi::html::content_blocks;
+use crate::newsapi::query::Query;
+use crate::newsapi::query::QueryError;
+use crate::newsapi::quota::DAILY_REQUEST_LIMIT;
+use crate::newsapi::quota::INTERACTIVE_RESERVE;
+use crate::newsapi::quota::LIVE_SEARCH_RESERVE;
+use crate::newsapi::quota::QuotaUsage;
+use crate::newsapi::quota::RequestKind;
+use crate::newsapi::quota::key_id;
+use crate::newsapi::source::Source;
+use crate::reader::ReaderArticle;
+use crate::reader::fetch_reader_article;
+use crate::ui::ArticleWindow;
 use crate::ui::SEARCH_BAR_ID;
 use crate::ui::TOKEN_INPUT_ID;
+use crate::ui::alerts::alerts_page;
+use crate::ui::archive::ArchiveMode;
+use crate::ui::archive::archive_page;
+use crate::ui::article::LoadedContent;
+use crate::ui::article::RESULTS_SCROLL_ID;
+use crate::ui::article::SUMMARY_LENGTH_KEY;
+use crate::ui::article::SummaryLength;
 use crate::ui::article::article_cards;
 use crate::ui::article::article_page;
+use crate::ui::article::article_summary;
 use crate::ui::article::get_image_from_url;
+use crate::ui::chart::ChartKind;
+use crate::ui::compare::MAX_COMPARED;
+use crate::ui::compare::compare_bar;
+use crate::ui::compare::compare_page;
+use crate::ui::dashboard::GridFilter;
+use crate::ui::dashboard::dashboard_page;
+use crate::ui::dashboard::grid_filter_notice;
+use crate::ui::facets::FacetFilter;
+use crate::ui::facets::GAZETTEER_KEY;
+use crate::ui::facets::facet_sidebar;
+use crate::ui::history::history_page;
+use crate::ui::navigation::Navigation;
+use crate::ui::navigation::ResultSnapshot;
+use crate::ui::navigation::navigation_buttons;
+use crate::ui::query_builder::BuilderGroup;
+use crate::ui::query_builder::QueryBuilderMessage;
+use crate::ui::query_builder::query_builder_panel;
+use crate::ui::reader::ReaderView;
+use crate::ui::reading::DEFAULT_SPLIT_RATIO;
+use crate::ui::reading::ReadingPane;
+use crate::ui::reading::SPLIT_LAYOUT_MIN_WIDTH;
+use crate::ui::reading::SPLIT_RATIO_KEY;
+use crate::ui::reading::clamp_ratio;
+use crate::ui::reading::reading_panes;
+use crate::ui::reading::split_view;
+use crate::ui::saved::REFRESH_BUDGET_KEY;
+use crate::ui::saved::REFRESH_INTERVAL_KEY;
+use crate::ui::saved::RefreshBudget;
+use crate::ui::saved::RefreshInterval;
+use crate::ui::saved::badge;
+use crate::ui::saved::saved_sidebar;
+use crate::ui::source::Language;
 use crate::ui::source::source_page;
+use crate::ui::stories::duplicates_notice;
+use crate::ui::stories::story_entries;
 use crate::ui::style::LIST_ICON;
 use crate::ui::style::SEARCH_ICON;
 use crate::ui::style::button_style;
 use crate::ui::style::text_input_style;
+use crate::ui::suggestions::Suggestion;
+use crate::ui::suggestions::suggestions;
+use crate::ui::suggestions::suggestions_dropdown;
+use crate::ui::tabs::TAB_STRIP_HEIGHT;
+use crate::ui::tabs::Tab;
+use crate::ui::tabs::tab_strip;
+use crate::ui::tabs::tab_title;
 use crate::ui::token_page::TokenPage;
+use crate::ui::tone::TONE_RANGE_KEY;
+use crate::ui::tone::TONE_SORT_KEY;
+use crate::ui::tone::ToneRange;
+use crate::ui::tone::ToneSort;
+use crate::ui::tone::tone_bar;
+use crate::ui::trends::TREND_INPUT_ID;
+use crate::ui::trends::TrendRange;
+use crate::ui::trends::TrendsView;
+use crate::ui::trends::trends_page;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use iced::Alignment;
 use iced::Background;
 use iced::Border;
 use iced::Theme;
 use iced::color;
+use iced::keyboard::Modifiers;
 use iced::widget::Stack;
 use iced::widget::container;
 use iced::widget::image::Handle;
 use iced::widget::mouse_area;
+use iced::widget::pane_grid;
+use iced::widget::scrollable;
+use iced::widget::scrollable::AbsoluteOffset;
 use iced::widget::svg;
 use iced::widget::text_input::focus;
+use iced::widget::text_input::move_cursor_to_end;
 use iced::widget::tooltip;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
 
 use crate::newsapi::NewsAPIError;
 use crate::ui::Action;
 use crate::ui::Message;
 use crate::ui::Page;
 use iced::Element;
+use iced::Font;
 use iced::Length;
+use iced::Subscription;
 use iced::Task;
 use iced::widget::Column;
+use iced::widget::Row;
 use iced::widget::button;
 use iced::widget::text_input;
 use iced::widget::{column, row, text};
@@ -64,6 +169,158 @@ pub struct MainPage {
     source_page: bool,
     /// stores the content of the source filter box in the source page
     source_filter: String,
+    /// local archive every fetched response is written to
+    archive: Archive,
+    /// show the archive browse page
+    archive_page: bool,
+    /// overview of the archive, loaded whenever the archive page is opened
+    archive_index: Option<Result<ArchiveIndex, String>>,
+    /// grouping shown on the archive page
+    archive_mode: ArchiveMode,
+    /// "work offline" toggle, when set no network requests are made and results come from the archive
+    offline: bool,
+    /// when the shown results were served from the archive instead of NewsAPI, the time they were fetched
+    cached_at: Option<DateTime<Utc>>,
+    /// search the local full-text index instead of NewsAPI
+    local_search: bool,
+    /// problem found when validating the submitted query, cleared when the query is edited
+    query_error: Option<QueryError>,
+    /// show the query builder panel
+    query_builder_open: bool,
+    /// state of the query builder, edits regenerate `search_query`
+    query_builder: BuilderGroup,
+    /// sort order and language used for everything searches
+    everything_params: EverythingParams,
+    /// saved searches shown in the sidebar
+    saved_searches: Vec<SavedSearch>,
+    /// show the saved search sidebar
+    saved_sidebar: bool,
+    /// content of the saved search name input
+    saved_name: String,
+    /// saved search whose results are currently shown, cleared by any other search
+    viewing_saved: Option<i64>,
+    /// how often saved searches are refreshed in the background
+    refresh_interval: RefreshInterval,
+    /// watch terms with their unread alert counts
+    watch_terms: Vec<WatchTerm>,
+    /// show the alerts page
+    alerts_page: bool,
+    /// content of the new watch term input
+    watch_input: String,
+    /// problem with the entered watch term, cleared when it's edited
+    watch_error: Option<QueryError>,
+    /// watch term whose alerts are listed on the alerts page
+    alerts_term: Option<i64>,
+    /// alerts of `alerts_term`
+    alerts: Option<Result<Vec<Alert>, String>>,
+    /// identifies the API key in the request counts, see `quota::key_id`
+    key_id: String,
+    /// requests made with the API key today
+    quota: QuotaUsage,
+    /// share of the daily quota background refreshes may use
+    refresh_budget: RefreshBudget,
+    /// index of the saved search the next background refresh starts from
+    refresh_cursor: usize,
+    /// the submitted search would use up the daily quota and waits for confirmation
+    quota_warning: bool,
+    /// the last archive operation that failed, shown until it's dismissed
+    archive_error: Option<String>,
+    /// incremented whenever new results are requested, responses and images from older requests are dropped
+    generation: u64,
+    /// highest generation handed out to any tab, generations are unique across tabs
+    last_generation: u64,
+    /// aborts the image downloads of the shown results
+    image_tasks: Option<iced::task::Handle>,
+    /// aborts the search currently in flight
+    search_handle: Option<iced::task::Handle>,
+    /// search while typing, after a short pause
+    live_search: bool,
+    /// incremented on every edit of the search bar, a debounced live search only runs if no edit came after it
+    input_serial: u64,
+    /// submitted searches, most recent first
+    history: Vec<HistoryEntry>,
+    /// show the search history panel
+    history_page: bool,
+    /// show the keyword trend charts
+    trends_page: bool,
+    /// show the source analytics dashboard
+    dashboard_page: bool,
+    /// narrows the shown results to the articles behind a clicked dashboard bar
+    grid_filter: Option<GridFilter>,
+    /// show the facet sidebar
+    facet_sidebar: bool,
+    /// names and key phrases mentioned in the shown results
+    facets: Vec<Facet>,
+    /// facets narrowing down the shown results
+    facet_filters: Vec<FacetFilter>,
+    /// gazetteer entries as entered, like "place: Kyiv"
+    gazetteer_entries: Vec<String>,
+    /// `gazetteer_entries` parsed
+    gazetteer: Gazetteer,
+    /// content of the gazetteer entry input
+    gazetteer_input: String,
+    /// keyboard modifiers held, Shift+clicking a facet hides its articles instead
+    modifiers: Modifiers,
+    /// terms and options of the trend charts
+    trends: TrendsView,
+    /// position in `history` recalled into the search bar with the arrow keys, None while editing
+    history_cursor: Option<usize>,
+    /// what was typed before recalling history, restored when stepping back past the newest entry
+    history_draft: String,
+    /// suggestions shown under the search bar, empty when the dropdown is closed
+    suggestions: Vec<Suggestion>,
+    /// earlier and later result lists to move between with back and forward
+    navigation: Navigation,
+    /// query and sources of the request for results in flight
+    requested_search: (String, HashMap<String, bool>),
+    /// title of the results in flight when they aren't searched for with the query
+    requested_label: Option<String>,
+    /// query and sources the shown results were searched with
+    shown_search: (String, HashMap<String, bool>),
+    /// title of the shown results when they weren't searched for with the query
+    shown_label: Option<String>,
+    /// scroll position of the result list
+    results_scroll: AbsoluteOffset,
+    /// open tabs in order, the entry of the active tab is empty apart from its id
+    tabs: Vec<Tab>,
+    /// index of the active tab in `tabs`
+    active_tab: usize,
+    /// highest tab id handed out
+    last_tab_id: u64,
+    /// panes of the split reading layout used on wide windows
+    reading_panes: pane_grid::State<ReadingPane>,
+    /// share of the width the article list takes in the split reading layout
+    split_ratio: f32,
+    /// incremented on every resize of the split, the ratio is saved once resizing stops
+    split_serial: u64,
+    /// reader mode content of the open article, only shown for the article it belongs to
+    reader: Option<ReaderView>,
+    /// images in the content of opened articles by url
+    content_images: HashMap<String, Handle>,
+    /// number of sentences in the summary of the open article
+    summary_length: SummaryLength,
+    /// summary of the open article, picked when it's opened rather than on every redraw
+    summary: Vec<String>,
+    /// content of the open article, parsed when it's opened rather than on every redraw
+    content_blocks: Vec<ContentBlock>,
+    /// for every shown article the earlier article it duplicates
+    duplicate_of: Vec<Option<usize>>,
+    /// how duplicates are shown
+    duplicate_mode: DuplicateMode,
+    /// tone of every shown article, from -1 for very negative to 1 for very positive
+    tones: Vec<f32>,
+    /// tones of the articles shown, the others are hidden
+    tone_range: ToneRange,
+    /// order of the stories by tone
+    tone_sort: ToneSort,
+    /// the shown results grouped into stories covered by one or more articles
+    clusters: Vec<Vec<usize>>,
+    /// first article of every story whose other versions are shown
+    expanded_stories: HashSet<usize>,
+    /// articles selected for comparison, in the order they were selected
+    compare_selection: Vec<usize>,
+    /// show the selected articles side by side
+    compare_page: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -71,19 +328,155 @@ pub enum MainPageMessage {
     SearchBarOnInput(String),
     SourceFilterOnInput(String),
     SearchSubmit,
-    SearchComplete(Result<NewsAPIArticlesSuccess, String>),
+    // the generation the results were requested in,
+    // the timestamp is set when cached results were served in place of a NewsAPI response
+    SearchComplete(
+        u64,
+        Result<NewsAPIArticlesSuccess, String>,
+        Option<DateTime<Utc>>,
+    ),
     SourcesFetched(Result<NewsAPISourcesSuccess, String>),
     SourceToggled(String, bool),
     // Handle is a reference to bytes, doesn't own the data
-    ImageLoaded(Option<(usize, Handle)>),
+    // the generation is the one of the results the image belongs to
+    ImageLoaded(u64, Option<(usize, Handle)>),
     ActiveArticle(Option<usize>),
     ToggleSourcePage,
     DisableAllSources,
+    ToggleArchivePage,
+    ArchiveIndexLoaded(Result<ArchiveIndex, String>),
+    ArchiveModeSelected(ArchiveMode),
+    ArchiveBrowse(ArchiveFilter),
+    ToggleOffline,
+    ToggleLocalSearch,
+    ToggleQueryBuilder,
+    QueryBuilder(QueryBuilderMessage),
+    SortBySelected(SortBy),
+    LanguageSelected(Language),
+    ToggleSavedSidebar,
+    SavedNameOnInput(String),
+    SaveCurrentSearch,
+    SavedSearchSaved(Result<SavedSearch, String>),
+    SavedSearchSelected(i64),
+    SavedSearchDeleted(i64),
+    RefreshIntervalSelected(RefreshInterval),
+    RefreshSavedSearches,
+    SavedSearchRefreshed(i64, Result<usize, String>),
+    ToggleAlertsPage,
+    WatchTermsLoaded(Result<Vec<WatchTerm>, String>),
+    WatchInputOnInput(String),
+    AddWatchTerm,
+    WatchTermRemoved(i64),
+    WatchTermSelected(i64),
+    AlertsLoaded(i64, Result<Vec<Alert>, String>),
+    // index into the loaded alerts
+    AlertOpened(usize),
+    ShowAlertArticles,
+    // sent after the live search delay with the input serial it was started for
+    LiveSearch(u64),
+    LiveSearchToggled(bool),
+    SuggestionSelected(Suggestion),
+    ToggleHistoryPage,
+    HistoryRerun(i64),
+    ToggleTrendsPage,
+    ToggleDashboardPage,
+    GridFilterSelected(GridFilter),
+    ClearGridFilter,
+    ToggleFacetSidebar,
+    // a facet was clicked, with Shift held it hides its articles
+    FacetPressed(FacetKind, String),
+    ClearFacetFilters,
+    GazetteerInputOnInput(String),
+    GazetteerEntryAdded,
+    // index into the gazetteer entries
+    GazetteerEntryRemoved(usize),
+    ModifiersChanged(Modifiers),
+    TrendInputOnInput(String),
+    TrendTermAdded,
+    TrendTermRemoved(String),
+    TrendRangeSelected(TrendRange),
+    TrendIntervalSelected(TrendInterval),
+    ChartKindSelected(ChartKind),
+    TrendBySourceToggled(bool),
+    // the generation the counts were loaded for, the term and its counts
+    TrendCountsLoaded(u64, String, Result<Vec<TermCount>, String>),
+    HistorySave(i64),
+    ClearHistory,
+    // arrow key pressed anywhere, true for up
+    HistoryKeyPressed(bool),
+    // arrow key pressed in the search bar, true for up
+    HistoryRecall(bool),
+    ResultsScrolled(AbsoluteOffset),
+    NewTab,
+    SelectTab(u64),
+    CloseTab(u64),
+    CloseActiveTab,
+    // Ctrl+Tab, true for the next tab
+    CycleTab(bool),
+    // true to move the tab right
+    MoveTab(u64, bool),
+    // index into the shown results
+    PopOutArticle(usize),
+    SplitResized(pane_grid::ResizeEvent),
+    // sent after the split stopped moving with the serial of the last resize
+    SaveSplitRatio(u64),
+    // index into the shown results
+    OpenReader(usize),
+    CloseReader,
+    // the article url and the content extracted from it earlier, if the archive has any
+    ReaderArchived(String, Result<Option<ReaderArticle>, String>),
+    // the article url and its extracted content
+    ReaderLoaded(String, Result<ReaderArticle, String>),
+    // the article url, the image url and the image
+    ReaderImageLoaded(String, String, Option<Handle>),
+    // an image url in article content and the image
+    ContentImageLoaded(String, Option<Handle>),
+    SummaryLengthSelected(SummaryLength),
+    // index of the first article of the story
+    ToggleStory(usize),
+    // index into the shown results
+    ToggleCompare(usize),
+    OpenComparison,
+    CloseComparison,
+    ClearCompareSelection,
+    DuplicateModeSelected(DuplicateMode),
+    ToneMinChanged(f32),
+    ToneMaxChanged(f32),
+    // a tone slider was released
+    SaveToneRange,
+    ResetToneRange,
+    ToneSortSelected(ToneSort),
+    RefreshBudgetSelected(RefreshBudget),
+    // answers to the quota warning
+    ConfirmSearch,
+    SearchArchiveInstead,
+    DismissQuotaWarning,
+    QuotaLoaded(QuotaUsage),
+    // the search as recorded in the history
+    SearchRecorded(HistoryEntry),
+    // description of an archive operation that failed
+    ArchiveFailed(String),
+    DismissArchiveError,
     BackToApiKeyPage,
 }
 
 pub const SOURCE_FILTER_ID: &str = "source_filter_input";
 
+/// Setting key the search-as-you-type option is persisted under.
+const LIVE_SEARCH_KEY: &str = "live_search";
+
+/// Setting key the way duplicates are shown is persisted under.
+const DUPLICATES_KEY: &str = "duplicates";
+
+/// Pause in typing after which a live search runs.
+const LIVE_SEARCH_DELAY: Duration = Duration::from_millis(600);
+
+/// Pause after resizing the split reading layout before the ratio is saved.
+const SPLIT_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Shortest query a live search runs for, in characters.
+const LIVE_SEARCH_MIN_LENGTH: usize = 3;
+
 /// Render a compact error view showing a message and a Back to API key page button.
 ///
 /// Parameters:
@@ -106,32 +499,292 @@ pub fn error_element(error: &str) -> Element<'_, Message> {
     .into()
 }
 
+/// Error shown under the search bar when the query doesn't parse, with a caret under the offending column.
+///
+/// Parameters:
+/// - query: the submitted query
+/// - error: the parse error, its column is used to place the caret
+///
+/// Returns:
+/// - an Element with the error message, and the query with a marker under the problem
+fn query_error_element<'a>(query: &'a str, error: &QueryError) -> Element<'a, Message> {
+    let caret = format!("{}^", " ".repeat(error.column.saturating_sub(1)));
+
+    container(column![
+        text(error.to_string()).color(color!(0xff0000)).size(18),
+        text(query).font(Font::MONOSPACE).size(16),
+        text(caret)
+            .font(Font::MONOSPACE)
+            .size(16)
+            .color(color!(0xff0000)),
+    ])
+    .padding([0, 15])
+    .into()
+}
+
+/// Banner shown above the results when they were served from the archive.
+///
+/// Parameters:
+/// - cached_at: when the shown results were originally fetched
+///
+/// Returns:
+/// - an Element with a short notice like "offline — showing results from 14:02", the date is included for older results
+fn offline_banner(cached_at: DateTime<Utc>) -> Element<'static, Message> {
+    let local: DateTime<Local> = DateTime::from(cached_at);
+    let time = if local.date_naive() == Local::now().date_naive() {
+        local.format("%H:%M").to_string()
+    } else {
+        local.format("%B %d, %Y at %H:%M").to_string()
+    };
+
+    container(text(format!("offline — showing results from {time}")).size(18))
+        .padding([5, 15])
+        .width(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(Background::Color(color!(0xfff3cd))),
+            border: Border::default().color(theme.palette().primary).width(1),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Warning shown before a search that would use up the daily request quota.
+///
+/// Parameters:
+/// - quota: today's request usage
+///
+/// Returns:
+/// - an Element explaining the situation, with buttons to search anyway, search the local archive instead or cancel
+fn quota_warning_banner(quota: &QuotaUsage) -> Element<'static, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let resets: DateTime<Local> = DateTime::from(quota.resets_at());
+    let warning = match quota.remaining() {
+        0 => {
+            "Today's NewsAPI requests are used up, this search will most likely be answered from the archive."
+        }
+        _ => "This search will use your last NewsAPI request for today.",
+    };
+
+    container(
+        row![
+            text(format!(
+                "{warning} The quota resets at {}.",
+                resets.format("%H:%M")
+            ))
+            .size(18)
+            .width(Length::Fill),
+            button(text("Search anyway"))
+                .style(button_style)
+                .on_press(M(ConfirmSearch)),
+            button(text("Search archive"))
+                .style(button_style)
+                .on_press(M(SearchArchiveInstead)),
+            button(text("Cancel"))
+                .style(button_style)
+                .on_press(M(DismissQuotaWarning)),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center),
+    )
+    .padding([5, 15])
+    .width(Length::Fill)
+    .style(|theme: &Theme| container::Style {
+        background: Some(Background::Color(color!(0xfff3cd))),
+        border: Border::default().color(theme.palette().primary).width(1),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Banner shown above the results after an archive operation failed.
+///
+/// Parameters:
+/// - error: description of the failure
+///
+/// Returns:
+/// - an Element with the error and a button to dismiss it
+fn archive_error_banner(error: &str) -> Element<'_, Message> {
+    container(
+        row![
+            text(error)
+                .color(color!(0xff0000))
+                .size(18)
+                .width(Length::Fill),
+            button(text("Dismiss"))
+                .style(button_style)
+                .on_press(Message::MainPage(MainPageMessage::DismissArchiveError)),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center),
+    )
+    .padding([5, 15])
+    .width(Length::Fill)
+    .style(|theme: &Theme| container::Style {
+        background: Some(Background::Color(color!(0xfff3cd))),
+        border: Border::default().color(theme.palette().primary).width(1),
+        ..Default::default()
+    })
+    .into()
+}
+
 /// Top bar containing the search input and buttons.
 ///
 /// Parameters:
 /// - search_query: current text in the search input
 /// - n_sources: number shown on the sources button
+/// - offline: state of the "work offline" toggle
+/// - local_search: whether searches go to the local full-text index
+/// - unseen: new articles across all saved searches, shown as a badge on the saved searches button
+/// - unread_alerts: unread alerts across all watch terms, shown as a badge on the alerts button
+/// - quota: today's request usage, the remaining requests are shown next to the offline toggle
 ///
 /// Returns:
-/// - an Element containing the search field, search submit button, and a sources toggle/tooltip button
-fn top_bar(search_query: &str, n_sources: usize) -> Element<'_, Message> {
+/// - an Element containing a search mode toggle, the search field, search submit button, a query builder button, a saved searches button, an alerts button, an archive button, a history button, trends, analytics and facets buttons, the remaining request count, an offline toggle, and a sources toggle/tooltip button
+fn top_bar<'a>(
+    search_query: &'a str,
+    n_sources: usize,
+    offline: bool,
+    local_search: bool,
+    unseen: usize,
+    unread_alerts: usize,
+    quota: &QuotaUsage,
+) -> Element<'a, Message> {
     use MainPageMessage::*;
     use Message::MainPage as M;
 
     row![
-        text_input("Search for articles", search_query)
-            .on_input(|s| M(SearchBarOnInput(s)))
-            .on_submit(M(SearchSubmit))
-            .id(SEARCH_BAR_ID) // id for focus task
-            .style(text_input_style)
-            .width(Length::Fill)
-            .size(24),
+        tooltip(
+            button(text(if local_search { "Local" } else { "NewsAPI" }).size(20))
+                .on_press(M(ToggleLocalSearch))
+                .padding(10)
+                .height(Length::Fill)
+                .style(button_style),
+            container(text("Search NewsAPI or the local archive"))
+                .padding(5)
+                .style(|theme: &Theme| {
+                    container::Style {
+                        background: Some(Background::Color(color!(0xeeeeff))),
+                        border: Border::default().color(theme.palette().primary).rounded(5),
+                        ..Default::default()
+                    }
+                }),
+            tooltip::Position::Bottom,
+        ),
+        text_input(
+            if local_search {
+                "Search the local archive"
+            } else {
+                "Search for articles"
+            },
+            search_query
+        )
+        .on_input(|s| M(SearchBarOnInput(s)))
+        .on_submit(M(SearchSubmit))
+        .id(SEARCH_BAR_ID) // id for focus task
+        .style(text_input_style)
+        .width(Length::Fill)
+        .size(24),
         button(svg(iced::advanced::svg::Handle::from_memory(SEARCH_ICON)))
             .on_press(M(SearchSubmit))
             .padding(10)
             .width(48)
             .height(Length::Fill)
             .style(button_style),
+        button(text("Builder").size(20))
+            .on_press(M(ToggleQueryBuilder))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        button(
+            row![text("Saved").size(20)]
+                .push_maybe((unseen > 0).then(|| badge(unseen)))
+                .spacing(5)
+                .align_y(Alignment::Center)
+        )
+        .on_press(M(ToggleSavedSidebar))
+        .padding(10)
+        .height(Length::Fill)
+        .style(button_style),
+        button(
+            row![text("Alerts").size(20)]
+                .push_maybe((unread_alerts > 0).then(|| badge(unread_alerts)))
+                .spacing(5)
+                .align_y(Alignment::Center)
+        )
+        .on_press(M(ToggleAlertsPage))
+        .padding(10)
+        .height(Length::Fill)
+        .style(button_style),
+        button(text("Archive").size(20))
+            .on_press(M(ToggleArchivePage))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        button(text("History").size(20))
+            .on_press(M(ToggleHistoryPage))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        button(text("Trends").size(20))
+            .on_press(M(ToggleTrendsPage))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        button(text("Analytics").size(20))
+            .on_press(M(ToggleDashboardPage))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        button(text("Facets").size(20))
+            .on_press(M(ToggleFacetSidebar))
+            .padding(10)
+            .height(Length::Fill)
+            .style(button_style),
+        tooltip(
+            container(
+                text(format!("{}/{DAILY_REQUEST_LIMIT}", quota.remaining()))
+                    .size(20)
+                    .color_maybe(
+                        (quota.remaining() <= INTERACTIVE_RESERVE).then_some(color!(0xd93025))
+                    )
+            )
+            .center_y(Length::Fill)
+            .padding([0, 5]),
+            container(text(format!(
+                "NewsAPI requests left today, resets at {}",
+                DateTime::<Local>::from(quota.resets_at()).format("%H:%M")
+            )))
+            .padding(5)
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(Background::Color(color!(0xeeeeff))),
+                    border: Border::default().color(theme.palette().primary).rounded(5),
+                    ..Default::default()
+                }
+            }),
+            tooltip::Position::Bottom,
+        ),
+        tooltip(
+            button(text(if offline { "Offline" } else { "Online" }).size(20))
+                .on_press(M(ToggleOffline))
+                .padding(10)
+                .height(Length::Fill)
+                .style(button_style),
+            container(text(
+                "Work offline, results and images come from the local archive"
+            ))
+            .padding(5)
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(Background::Color(color!(0xeeeeff))),
+                    border: Border::default().color(theme.palette().primary).rounded(5),
+                    ..Default::default()
+                }
+            }),
+            tooltip::Position::Bottom,
+        ),
         tooltip(
             mouse_area(
                 button(row![
@@ -171,7 +824,7 @@ impl MainPage {
     ///
     /// Returns:
     /// - Ok(MainPage) with a reqwest client that has the API key header and a default user agent, plus default UI state
-    /// - Err(NewsAPIError) if the header value is invalid or the HTTP client cannot be built
+    /// - Err(NewsAPIError) if the header value is invalid, the HTTP client cannot be built or the archive cannot be opened
     pub fn new(token: &str) -> Result<Self, NewsAPIError> {
         let mut headers = HeaderMap::new();
         headers.insert("X-Api-Key", HeaderValue::from_str(token)?);
@@ -181,6 +834,50 @@ impl MainPage {
             .default_headers(headers)
             .build()?;
 
+        let archive = Archive::open()?;
+        let saved_searches = archive.saved_searches()?;
+        let watch_terms = archive.watch_terms()?;
+        let history = archive.search_history()?;
+        let key_id = key_id(token);
+        let quota = archive.quota_usage(&key_id)?;
+        let refresh_budget = archive
+            .setting(REFRESH_BUDGET_KEY)?
+            .and_then(|v| v.parse().ok())
+            .map(RefreshBudget)
+            .unwrap_or(RefreshBudget::DEFAULT);
+        let live_search = archive.setting(LIVE_SEARCH_KEY)?.as_deref() == Some("true");
+        let summary_length = archive
+            .setting(SUMMARY_LENGTH_KEY)?
+            .and_then(|v| v.parse().ok())
+            .map(SummaryLength)
+            .unwrap_or(SummaryLength::DEFAULT);
+        let gazetteer_entries: Vec<String> = archive
+            .setting(GAZETTEER_KEY)?
+            .map(|v| v.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let tone_range = archive
+            .setting(TONE_RANGE_KEY)?
+            .and_then(|v| ToneRange::from_param(&v))
+            .unwrap_or(ToneRange::FULL);
+        let tone_sort = archive
+            .setting(TONE_SORT_KEY)?
+            .map(|v| ToneSort::from_param(&v))
+            .unwrap_or_default();
+        let duplicate_mode = archive
+            .setting(DUPLICATES_KEY)?
+            .map(|v| DuplicateMode::from_param(&v))
+            .unwrap_or_default();
+        let split_ratio = archive
+            .setting(SPLIT_RATIO_KEY)?
+            .and_then(|v| v.parse().ok())
+            .map(clamp_ratio)
+            .unwrap_or(DEFAULT_SPLIT_RATIO);
+        let refresh_interval = archive
+            .setting(REFRESH_INTERVAL_KEY)?
+            .and_then(|v| v.parse().ok())
+            .map(RefreshInterval)
+            .unwrap_or(RefreshInterval::DEFAULT);
+
         Ok(Self {
             client,
             search_query: String::new(),
@@ -191,184 +888,2387 @@ impl MainPage {
             source_page: false,
             enabled_sources: HashMap::new(),
             source_filter: String::new(),
+            archive,
+            archive_page: false,
+            archive_index: None,
+            archive_mode: ArchiveMode::default(),
+            offline: false,
+            cached_at: None,
+            local_search: false,
+            query_error: None,
+            query_builder_open: false,
+            query_builder: BuilderGroup::default(),
+            everything_params: EverythingParams::default(),
+            saved_searches,
+            saved_sidebar: false,
+            saved_name: String::new(),
+            viewing_saved: None,
+            refresh_interval,
+            watch_terms,
+            alerts_page: false,
+            watch_input: String::new(),
+            watch_error: None,
+            alerts_term: None,
+            alerts: None,
+            key_id,
+            quota,
+            refresh_budget,
+            refresh_cursor: 0,
+            quota_warning: false,
+            archive_error: None,
+            generation: 0,
+            last_generation: 0,
+            image_tasks: None,
+            search_handle: None,
+            live_search,
+            input_serial: 0,
+            history,
+            history_page: false,
+            trends_page: false,
+            dashboard_page: false,
+            grid_filter: None,
+            facet_sidebar: false,
+            facets: Vec::new(),
+            facet_filters: Vec::new(),
+            gazetteer: Gazetteer::parse(gazetteer_entries.iter().map(String::as_str)),
+            gazetteer_entries,
+            gazetteer_input: String::new(),
+            modifiers: Modifiers::default(),
+            trends: TrendsView::default(),
+            history_cursor: None,
+            history_draft: String::new(),
+            navigation: Navigation::default(),
+            requested_search: (String::new(), HashMap::new()),
+            requested_label: None,
+            shown_search: (String::new(), HashMap::new()),
+            shown_label: None,
+            results_scroll: AbsoluteOffset::default(),
+            tabs: vec![Tab {
+                id: 0,
+                ..Default::default()
+            }],
+            active_tab: 0,
+            last_tab_id: 0,
+            reading_panes: reading_panes(split_ratio),
+            split_ratio,
+            split_serial: 0,
+            reader: None,
+            content_images: HashMap::new(),
+            summary_length,
+            summary: Vec::new(),
+            content_blocks: Vec::new(),
+            duplicate_of: Vec::new(),
+            duplicate_mode,
+            tones: Vec::new(),
+            tone_range,
+            tone_sort,
+            clusters: Vec::new(),
+            expanded_stories: HashSet::new(),
+            compare_selection: Vec::new(),
+            compare_page: false,
+            suggestions: Vec::new(),
         })
     }
-}
 
-impl Page for MainPage {
-    fn view(&self, size: (f32, f32)) -> Element<'_, Message> {
-        let w = size.0;
-        let mut article_chunks = (w / 400.0).floor();
-        if article_chunks < 1.0 {
-            article_chunks = 1.0;
+    /// Replace the query builder contents with the parsed search box query.
+    ///
+    /// An empty search box clears the builder, a query that doesn't parse leaves it untouched and shows the error.
+    fn import_query_into_builder(&mut self) {
+        if self.search_query.trim().is_empty() {
+            self.query_builder = BuilderGroup::default();
+            return;
         }
 
-        let mut source_chunks = (w / 300.0).floor();
-        if source_chunks < 1.0 {
-            source_chunks = 1.0;
+        match Query::parse(&self.search_query) {
+            Ok(query) => self.query_builder = BuilderGroup::from_query(&query),
+            Err(e) => self.query_error = Some(e),
         }
+    }
 
-        let article_chunks = article_chunks as usize;
-        let source_chunks = source_chunks as usize;
+    /// Create a Task that runs an archive operation off the UI thread and sends the message `done`
+    /// makes of its result.
+    ///
+    /// A failure is shown to the user instead, after `failure` describing what couldn't be done.
+    fn archive_task<T: Send + 'static>(
+        &self,
+        failure: &'static str,
+        operation: impl FnOnce(&Archive) -> Result<T, NewsAPIError> + Send + 'static,
+        done: impl Fn(T) -> Message + Send + 'static,
+    ) -> Task<Message> {
+        let archive = self.archive.clone();
 
-        Stack::with_capacity(3) // allocate max
-            // bottom layer
-            // has top bar and article card list
-            .push(
-                Column::with_capacity(2) // allocate max
-                    .push(top_bar(
-                        &self.search_query,
-                        self.enabled_sources.values().filter(|v| **v).count(),
-                    ))
-                    .push_maybe(article_cards(
-                        self.search_result.as_ref(),
-                        article_chunks,
-                        &self.images_loaded,
-                    )),
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || operation(&archive))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())
+            },
+            move |result| match result {
+                Ok(value) => done(value),
+                Err(e) => {
+                    Message::MainPage(MainPageMessage::ArchiveFailed(format!("{failure}: {e}")))
+                }
+            },
+        )
+    }
+
+    /// Create a Task that saves a setting in the archive, `failure` is shown when that fails.
+    fn save_setting(&self, failure: &'static str, key: &'static str, value: &str) -> Task<Message> {
+        let value = value.to_string();
+
+        self.archive_task(
+            failure,
+            move |archive| archive.set_setting(key, &value),
+            |()| Message::NoOp,
+        )
+    }
+
+    /// Create a Task that reloads today's request usage from the archive and sends a QuotaLoaded message.
+    ///
+    /// Run after requests were made, they are counted in the archive by the tasks making them.
+    fn quota_task(&self) -> Task<Message> {
+        let key_id = self.key_id.clone();
+
+        self.archive_task(
+            "Failed to load request quota",
+            move |archive| archive.quota_usage(&key_id),
+            |quota| Message::MainPage(MainPageMessage::QuotaLoaded(quota)),
+        )
+    }
+
+    /// Run the current search, asking for confirmation first when it would use up the daily quota.
+    ///
+    /// Local and offline searches don't make requests and always run right away.
+    fn start_search(&mut self) -> Action {
+        if !self.local_search && !self.offline && self.quota.as_of(Utc::now()).remaining() <= 1 {
+            self.quota_warning = true;
+            return Action::None;
+        }
+
+        self.quota_warning = false;
+        Action::Task(self.search_task())
+    }
+
+    /// Start a new request generation, superseding every earlier request for results.
+    ///
+    /// Image downloads for the shown results are aborted, responses to older requests
+    /// are dropped when they arrive. The search query and sources are kept for the
+    /// navigation history.
+    fn next_generation(&mut self) -> u64 {
+        self.last_generation += 1;
+        self.generation = self.last_generation;
+        self.cancel_search();
+        self.requested_search = (self.search_query.clone(), self.enabled_sources.clone());
+        self.requested_label = None;
+
+        if let Some(handle) = self.image_tasks.take() {
+            handle.abort();
+        }
+
+        self.generation
+    }
+
+    /// Take the shown results out of the page, with what was found in them and how they're
+    /// filtered, to be kept in the navigation history or a background tab.
+    fn take_snapshot(&mut self) -> ResultSnapshot {
+        let (query, enabled_sources) = std::mem::take(&mut self.shown_search);
+
+        ResultSnapshot {
+            query,
+            label: self.shown_label.take(),
+            enabled_sources,
+            search_result: self.search_result.take(),
+            images_loaded: std::mem::take(&mut self.images_loaded),
+            cached_at: self.cached_at.take(),
+            scroll: self.results_scroll,
+            duplicate_of: std::mem::take(&mut self.duplicate_of),
+            facets: std::mem::take(&mut self.facets),
+            tones: std::mem::take(&mut self.tones),
+            grid_filter: self.grid_filter.take(),
+            facet_filters: std::mem::take(&mut self.facet_filters),
+            expanded_stories: std::mem::take(&mut self.expanded_stories),
+            compare_selection: std::mem::take(&mut self.compare_selection),
+        }
+    }
+
+    /// Put results taken with `take_snapshot` back on the page as they were.
+    ///
+    /// The stories are grouped again, the duplicate and tone settings may have changed since.
+    fn put_snapshot(&mut self, snapshot: ResultSnapshot) {
+        self.shown_search = (snapshot.query, snapshot.enabled_sources);
+        self.shown_label = snapshot.label;
+        self.search_result = snapshot.search_result;
+        self.images_loaded = snapshot.images_loaded;
+        self.cached_at = snapshot.cached_at;
+        self.results_scroll = snapshot.scroll;
+        self.duplicate_of = snapshot.duplicate_of;
+        self.facets = snapshot.facets;
+        self.tones = snapshot.tones;
+        self.grid_filter = snapshot.grid_filter;
+        self.facet_filters = snapshot.facet_filters;
+        self.expanded_stories = snapshot.expanded_stories;
+        self.compare_selection = snapshot.compare_selection;
+        self.group_stories();
+    }
+
+    /// Select sources from a map of source ids to enabled state.
+    ///
+    /// The source list may have been fetched since the map was made, so only its entries are kept.
+    fn set_enabled_sources(&mut self, sources: &HashMap<String, bool>) {
+        for (source, enabled) in self.enabled_sources.iter_mut() {
+            *enabled = sources.get(source) == Some(&true);
+        }
+    }
+
+    /// Show results from the navigation history in place of the current ones, without a new request.
+    ///
+    /// Returns:
+    /// - a Task restoring the scroll position and fetching the images that hadn't loaded yet
+    fn restore_snapshot(&mut self, snapshot: ResultSnapshot) -> Task<Message> {
+        let generation = self.next_generation();
+
+        self.search_query = snapshot.query.clone();
+        self.set_enabled_sources(&snapshot.enabled_sources);
+        self.put_snapshot(snapshot);
+        self.active_article = None;
+        self.compare_page = false;
+        self.viewing_saved = None;
+        self.query_error = None;
+        self.quota_warning = false;
+        self.suggestions.clear();
+
+        let mut tasks = vec![scrollable::scroll_to(
+            scrollable::Id::new(RESULTS_SCROLL_ID),
+            self.results_scroll,
+        )];
+
+        if let Some(Ok(data)) = &self.search_result {
+            let (images, handle) = Task::batch(
+                data.articles
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| matches!(self.images_loaded.get(*i), Some(None)))
+                    .map(|input| image_task(input, self.offline, generation)),
             )
-            // detailed article page
-            .push_maybe(article_page(
-                self.active_article.as_ref(),
-                self.search_result.as_ref(),
-                &self.images_loaded,
-            ))
-            // detailed source page
-            .push_maybe(source_page(
-                self.source_page,
-                self.source_data.as_ref(),
-                &self.enabled_sources,
-                source_chunks,
-                &self.source_filter,
-            ))
-            .into()
+            .abortable();
+
+            tasks.push(images);
+            self.image_tasks = Some(handle);
+        }
+
+        Task::batch(tasks)
     }
 
-    fn update(&mut self, message: Message) -> Action {
-        use MainPageMessage::*;
-        use Message::MainPage as M;
+    /// Move the active tab's state out of the page.
+    fn park_tab(&mut self, id: u64) -> Tab {
+        Tab {
+            id,
+            search_query: std::mem::take(&mut self.search_query),
+            enabled_sources: self.enabled_sources.clone(),
+            results: self.take_snapshot(),
+            active_article: self.active_article.take(),
+            viewing_saved: self.viewing_saved.take(),
+            compare_page: std::mem::take(&mut self.compare_page),
+            navigation: std::mem::take(&mut self.navigation),
+            generation: self.generation,
+            search_handle: self.search_handle.take(),
+            image_tasks: self.image_tasks.take(),
+            requested_search: std::mem::take(&mut self.requested_search),
+            requested_label: self.requested_label.take(),
+        }
+    }
 
-        // handle escape key
-        if let Message::Escape = message {
-            if self.active_article.is_some() {
-                self.active_article = None;
-            }
+    /// Move a tab's state into the page, after the active tab was parked.
+    fn unpark_tab(&mut self, tab: Tab) {
+        self.search_query = tab.search_query;
+        self.set_enabled_sources(&tab.enabled_sources);
+        self.put_snapshot(tab.results);
+        self.active_article = tab.active_article;
+        self.update_content_blocks();
+        self.update_summary();
+        self.viewing_saved = tab.viewing_saved;
+        self.compare_page = tab.compare_page;
+        self.navigation = tab.navigation;
+        self.generation = tab.generation;
+        self.search_handle = tab.search_handle;
+        self.image_tasks = tab.image_tasks;
+        self.requested_search = tab.requested_search;
+        self.requested_label = tab.requested_label;
+    }
 
-            if self.source_page {
-                self.source_page = false;
-            }
+    /// Take the tab at `index` out of `tabs`, leaving its id in place.
+    fn take_tab(&mut self, index: usize) -> Tab {
+        let tab = std::mem::take(&mut self.tabs[index]);
+        self.tabs[index].id = tab.id;
+        tab
+    }
+
+    /// Run `f` with a background tab's state in the page, as if it was the active tab.
+    fn with_tab<T>(&mut self, index: usize, f: impl FnOnce(&mut Self) -> T) -> T {
+        let active = self.park_tab(self.tabs[self.active_tab].id);
+        let tab = self.take_tab(index);
+        self.unpark_tab(tab);
+
+        let result = f(self);
 
-            return Action::Task(focus(SEARCH_BAR_ID));
+        self.tabs[index] = self.park_tab(self.tabs[index].id);
+        self.unpark_tab(active);
+        result
+    }
+
+    /// Make the tab at `index` the active one, the current one is parked unless it was closed.
+    ///
+    /// Returns:
+    /// - a Task restoring the tab's scroll position and focusing the search bar
+    fn show_tab(&mut self, index: usize, park_current: bool) -> Task<Message> {
+        if park_current {
+            self.tabs[self.active_tab] = self.park_tab(self.tabs[self.active_tab].id);
         }
 
-        if let Message::MainPage(message) = message {
-            match message {
-                SearchBarOnInput(s) => self.search_query = s,
-                SourceFilterOnInput(s) => self.source_filter = s,
-                SearchSubmit => {
-                    // need to pass these into the async block
-                    // client is a shallow clone
-                    let client = self.client.clone();
-                    let query = self.search_query.clone();
+        let tab = self.take_tab(index);
+        self.unpark_tab(tab);
+        self.active_tab = index;
 
-                    let sources: Option<String> = Some(
-                        self.enabled_sources
-                            .iter()
-                            .filter(|(_, v)| **v)
-                            .map(|(k, _)| k.as_ref())
-                            .collect::<Vec<&str>>()
-                            .join(","),
-                    );
+        // whatever was being typed or warned about belonged to the other tab
+        self.query_error = None;
+        self.quota_warning = false;
+        self.suggestions.clear();
+        self.history_cursor = None;
+        self.input_serial += 1;
 
-                    return Action::Task(Task::perform(
-                        async move {
-                            match query.as_str() {
-                                "" => fetch_top(&client, sources).await,
-                                query => search_articles(&client, query, sources).await,
-                            }
-                            .map_err(|e| {
-                                eprintln!("{e:#?}");
-                                e.to_string()
-                            })
-                        },
-                        |v| M(SearchComplete(v)),
-                    ));
-                }
-                SearchComplete(v) => {
-                    self.active_article = None;
-                    let mut tasks: Task<Message> = Task::none();
+        Task::batch([
+            scrollable::scroll_to(scrollable::Id::new(RESULTS_SCROLL_ID), self.results_scroll),
+            focus(SEARCH_BAR_ID),
+        ])
+    }
 
-                    self.images_loaded = Vec::new();
+    /// Close the tab at `index`, its requests are aborted. The last tab can't be closed.
+    fn close_tab(&mut self, index: usize) -> Task<Message> {
+        if self.tabs.len() < 2 || index >= self.tabs.len() {
+            return Task::none();
+        }
 
-                    if let Ok(data) = &v {
-                        self.images_loaded.resize(data.articles.len(), None);
+        if index != self.active_tab {
+            let tab = self.tabs.remove(index);
+            for handle in [tab.search_handle, tab.image_tasks].into_iter().flatten() {
+                handle.abort();
+            }
 
-                        tasks = Task::batch(data.articles.iter().enumerate().map(image_task));
-                    }
+            if index < self.active_tab {
+                self.active_tab -= 1;
+            }
+            return Task::none();
+        }
 
-                    self.search_result = Some(v);
-                    return Action::Task(tasks);
-                }
-                SourcesFetched(v) => {
-                    if let Ok(data) = &v {
-                        self.enabled_sources.clear();
+        self.next_generation();
+        self.tabs.remove(index);
+        self.show_tab(index.min(self.tabs.len() - 1), false)
+    }
 
-                        for s in &data.sources {
-                            self.enabled_sources.insert(s.id.clone(), false);
-                        }
-                    }
+    /// Show the results of a request, the previous results are kept in the navigation history.
+    ///
+    /// Returns:
+    /// - a Task loading the images of the results
+    fn show_results(
+        &mut self,
+        generation: u64,
+        result: Result<NewsAPIArticlesSuccess, String>,
+        cached_at: Option<DateTime<Utc>>,
+    ) -> Task<Message> {
+        // keep the shown results to come back to
+        if self.search_result.is_some() {
+            let snapshot = self.take_snapshot();
+            self.navigation.visit(snapshot);
+        }
 
-                    self.source_data = Some(v);
-                }
-                // Toggle specific source
-                SourceToggled(id, state) => {
-                    self.enabled_sources.insert(id, state);
-                    // refocus input box
-                    return Action::Task(focus(SOURCE_FILTER_ID));
-                }
-                // Reset source filter
-                DisableAllSources => {
-                    for i in self.enabled_sources.values_mut() {
-                        *i = false;
-                    }
-                }
-                // Toggle the source filter page
-                ToggleSourcePage => {
-                    self.source_page = !self.source_page;
-                    if self.source_page {
-                        return Action::Task(focus(SOURCE_FILTER_ID));
-                    }
+        self.shown_search = std::mem::take(&mut self.requested_search);
+        self.shown_label = self.requested_label.take();
+        self.results_scroll = AbsoluteOffset::default();
+        self.active_article = None;
+        self.cached_at = cached_at;
+        self.images_loaded = Vec::new();
 
-                    self.source_filter = String::new();
-                    return Action::Task(focus(SEARCH_BAR_ID));
-                }
-                ImageLoaded(data) => {
-                    if let Some((i, handle)) = data
-                        && i < self.images_loaded.len()
-                    {
-                        self.images_loaded[i] = Some(handle);
-                    }
-                }
-                ActiveArticle(index) => {
-                    self.active_article = index;
-                }
-                BackToApiKeyPage => {
-                    return Action::SwitchPage((Box::new(TokenPage::new()), focus(TOKEN_INPUT_ID)));
-                }
+        let mut tasks = Task::none();
+
+        if let Ok(data) = &result {
+            self.images_loaded.resize(data.articles.len(), None);
+
+            // the new articles of a saved search count as seen once its results are on screen
+            if let Some(id) = self.viewing_saved
+                && let Some(saved) = self.saved_searches.iter_mut().find(|s| s.id == id)
+            {
+                saved.unseen = 0;
             }
+
+            let (images, handle) = Task::batch(
+                data.articles
+                    .iter()
+                    .enumerate()
+                    .map(|input| image_task(input, self.offline, generation)),
+            )
+            .abortable();
+
+            tasks = images;
+            self.image_tasks = Some(handle);
         }
 
-        Action::None
+        self.search_result = Some(result);
+        self.results_changed();
+        tasks
     }
+
+    /// Find duplicates, facets and tones of newly shown results and group them into stories,
+    /// collapsing every story and forgetting the dashboard and facet filters and the articles
+    /// selected for comparison.
+    fn results_changed(&mut self) {
+        (self.duplicate_of, self.facets, self.tones) = match &self.search_result {
+            Some(Ok(data)) => (
+                find_duplicates(&data.articles),
+                extract_facets(&data.articles, &self.gazetteer),
+                data.articles.iter().map(article_sentiment).collect(),
+            ),
+            _ => (Vec::new(), Vec::new(), Vec::new()),
+        };
+        self.grid_filter = None;
+        self.facet_filters.clear();
+        self.group_stories();
+        self.expanded_stories.clear();
+        self.compare_selection.clear();
+        self.compare_page = false;
+    }
+
+    /// Group the shown results into stories, with duplicates grouped with their original or left
+    /// out depending on `duplicate_mode`, articles the dashboard and facet filters don't match
+    /// and articles outside the tone range left out, and the stories ordered by `tone_sort`.
+    fn group_stories(&mut self) {
+        let Some(Ok(data)) = &self.search_result else {
+            self.clusters = Vec::new();
+            return;
+        };
+
+        self.clusters = story_clusters(&data.articles, &self.duplicate_of);
+        if self.duplicate_mode == DuplicateMode::Hide {
+            for cluster in &mut self.clusters {
+                cluster.retain(|&i| self.duplicate_of[i].is_none());
+            }
+        }
+
+        if let Some(filter) = &self.grid_filter {
+            let sources: HashMap<&str, &Source> = match &self.source_data {
+                Some(Ok(data)) => data.sources.iter().map(|s| (s.id.as_str(), s)).collect(),
+                _ => HashMap::new(),
+            };
+            for cluster in &mut self.clusters {
+                cluster.retain(|&i| filter.matches(&data.articles[i], &sources));
+            }
+        }
+
+        for filter in &self.facet_filters {
+            for cluster in &mut self.clusters {
+                cluster.retain(|&i| filter.matches(i, &self.facets));
+            }
+        }
+
+        for cluster in &mut self.clusters {
+            cluster.retain(|&i| self.tone_range.contains(self.tones[i]));
+        }
+
+        self.clusters.retain(|cluster| !cluster.is_empty());
+
+        // by the average tone of the story, a stable sort keeps the result order among equals
+        let tone = |cluster: &Vec<usize>| {
+            cluster.iter().map(|&i| self.tones[i]).sum::<f32>() / cluster.len() as f32
+        };
+        match self.tone_sort {
+            ToneSort::Results => {}
+            ToneSort::MostPositive => self.clusters.sort_by(|a, b| tone(b).total_cmp(&tone(a))),
+            ToneSort::MostNegative => self.clusters.sort_by(|a, b| tone(a).total_cmp(&tone(b))),
+        }
+    }
+
+    /// Find the facets of the shown results with the edited gazetteer, forgetting the facet filters.
+    ///
+    /// Returns:
+    /// - a Task saving the gazetteer
+    fn gazetteer_changed(&mut self) -> Task<Message> {
+        self.gazetteer = Gazetteer::parse(self.gazetteer_entries.iter().map(String::as_str));
+        if let Some(Ok(data)) = &self.search_result {
+            self.facets = extract_facets(&data.articles, &self.gazetteer);
+        }
+        self.facet_filters.clear();
+        self.group_stories();
+        self.expanded_stories.clear();
+
+        self.save_setting(
+            "Failed to save gazetteer",
+            GAZETTEER_KEY,
+            &self.gazetteer_entries.join("\n"),
+        )
+    }
+
+    /// Reader mode content and content images loaded for the open article.
+    fn loaded_content(&self) -> LoadedContent<'_> {
+        LoadedContent {
+            reader: self.reader.as_ref(),
+            content_images: &self.content_images,
+            content_blocks: &self.content_blocks,
+            summary: &self.summary,
+            summary_length: self.summary_length,
+        }
+    }
+
+    /// Open the article at `index` in the results, or close the open article for None.
+    ///
+    /// Returns:
+    /// - a Task loading the images in the article content that aren't loaded yet
+    fn open_article(&mut self, index: Option<usize>) -> Task<Message> {
+        self.active_article = index;
+        self.update_content_blocks();
+        self.update_summary();
+
+        Task::batch(self.content_blocks.iter().filter_map(|block| match block {
+            ContentBlock::Image(url) if !self.content_images.contains_key(url) => {
+                Some(content_image_task(url.clone(), self.offline))
+            }
+            _ => None,
+        }))
+    }
+
+    /// Parse the content of the open article, after another article was opened.
+    fn update_content_blocks(&mut self) {
+        self.content_blocks = match (&self.search_result, self.active_article) {
+            (Some(Ok(data)), Some(index)) => data
+                .articles
+                .get(index)
+                .and_then(|article| {
+                    Some(content_blocks(
+                        article.content.as_deref()?,
+                        article.url.as_deref(),
+                    ))
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Pick the summary of the open article again, after it or its reader mode content changed.
+    fn update_summary(&mut self) {
+        self.summary = match (&self.search_result, self.active_article) {
+            (Some(Ok(data)), Some(index)) => match data.articles.get(index) {
+                Some(article) => {
+                    article_summary(article, self.reader.as_ref(), self.summary_length)
+                }
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+    }
+
+    /// The articles selected for comparison, in the order they were selected.
+    fn compared_articles(&self) -> Vec<&Article> {
+        match &self.search_result {
+            Some(Ok(data)) => self
+                .compare_selection
+                .iter()
+                .filter_map(|&i| data.articles.get(i))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Abort the search in flight, if any.
+    fn cancel_search(&mut self) {
+        if let Some(handle) = self.search_handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Create a Task adding the current search to the history, moving it to the top if it was
+    /// run before, and sending a SearchRecorded message.
+    fn remember_search(&mut self) -> Task<Message> {
+        let query = self.search_query.trim().to_string();
+        let sources = self.enabled_sources_param();
+        self.history_cursor = None;
+
+        self.archive_task(
+            "Failed to record search history",
+            move |archive| archive.record_search(&query, &sources),
+            |entry| Message::MainPage(MainPageMessage::SearchRecorded(entry)),
+        )
+    }
+
+    /// Create a Task that counts the archived articles mentioning a trend term and sends a
+    /// TrendCountsLoaded message.
+    fn trend_counts_task(&self, term: String) -> Task<Message> {
+        let archive = self.archive.clone();
+        let since = self.trends.since;
+        let interval = self.trends.interval;
+        let generation = self.trends.generation;
+        let counted = term.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || archive.term_counts(&counted, since, interval))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())
+            },
+            move |v| {
+                Message::MainPage(MainPageMessage::TrendCountsLoaded(
+                    generation,
+                    term.clone(),
+                    v,
+                ))
+            },
+        )
+    }
+
+    /// Recount every trend term, after the range changed or new articles may have been archived.
+    fn reload_trends(&mut self) -> Task<Message> {
+        self.trends.generation += 1;
+        self.trends.since = Utc::now() - self.trends.range.duration();
+        self.trends.counts.clear();
+
+        Task::batch(
+            self.trends
+                .terms
+                .iter()
+                .map(|term| self.trend_counts_task(term.clone())),
+        )
+    }
+
+    /// Create a Task that saves a search with the current parameters and sends a SavedSearchSaved message.
+    fn save_search_task(&self, name: String, query: String, sources: String) -> Task<Message> {
+        let archive = self.archive.clone();
+        let params = self.everything_params.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    archive.save_search(&name, &query, &sources, &params)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())
+            },
+            |v| Message::MainPage(MainPageMessage::SavedSearchSaved(v)),
+        )
+    }
+
+    /// Restore the source selection from a comma-separated list of source ids.
+    fn select_sources(&mut self, sources: &str) {
+        for (source, enabled) in self.enabled_sources.iter_mut() {
+            *enabled = sources.split(',').any(|s| s == source);
+        }
+    }
+
+    /// Recompute the suggestions for the search bar contents.
+    fn update_suggestions(&mut self) {
+        let sources = match &self.source_data {
+            Some(Ok(data)) => data.sources.as_slice(),
+            _ => &[],
+        };
+
+        self.suggestions = suggestions(
+            &self.search_query,
+            &self.history,
+            &self.saved_searches,
+            sources,
+        );
+    }
+
+    /// Comma-separated ids of the enabled sources, sorted so the same selection always produces the same string.
+    fn enabled_sources_param(&self) -> String {
+        let mut sources: Vec<&str> = self
+            .enabled_sources
+            .iter()
+            .filter(|(_, v)| **v)
+            .map(|(k, _)| k.as_ref())
+            .collect();
+        sources.sort_unstable();
+
+        sources.join(",")
+    }
+
+    /// Create a Task that runs the current search and sends a SearchComplete message.
+    ///
+    /// An empty query fetches the top headlines, anything else goes to the everything endpoint.
+    /// Successful responses are written to the archive before being handed to the UI.
+    /// When working offline, or when NewsAPI is unreachable or the key is exhausted,
+    /// the most recent cached results for the request are used instead.
+    /// In local search mode the query runs against the archive's full-text index and nothing is fetched.
+    /// While a saved search is being viewed, the fetched articles are recorded as seen for it.
+    /// Starts a new generation, so the results of any earlier search are dropped.
+    pub fn search_task(&mut self) -> Task<Message> {
+        if self.local_search {
+            return self.local_search_task();
+        }
+
+        // need to pass these into the async block
+        // client and archive are shallow clones
+        let client = self.client.clone();
+        let archive = self.archive.clone();
+        let query = self.search_query.clone();
+        let sources = self.enabled_sources_param();
+        let params = self.everything_params.clone();
+        let offline = self.offline;
+        let viewing_saved = self.viewing_saved;
+        let key_id = self.key_id.clone();
+        let generation = self.next_generation();
+
+        let (task, handle) = Task::future(async move {
+            let result = if offline {
+                Err(NewsAPIError::Offline)
+            } else {
+                counted(
+                    &archive,
+                    &key_id,
+                    RequestKind::Interactive,
+                    fetch_articles(&client, &query, &sources, &params),
+                )
+                .await
+            };
+
+            match result {
+                Ok(data) => tokio::task::spawn_blocking(move || {
+                    let stored =
+                        archive.store(&query, &sources, &data).and_then(
+                            |ids| match viewing_saved {
+                                Some(id) => {
+                                    archive.record_saved_results(id, &ids, true).map(|_| ())
+                                }
+                                None => Ok(()),
+                            },
+                        );
+
+                    // the results are shown all the same, with word that they weren't kept
+                    let failure = stored
+                        .err()
+                        .map(|e| format!("Failed to archive articles: {e}"));
+                    (Ok(data), None, failure)
+                })
+                .await
+                .unwrap_or_else(|e| (Err(e.to_string()), None, None)),
+                Err(e) if e.is_unavailable() => {
+                    if !offline {
+                        eprintln!("NewsAPI unavailable, falling back to the archive: {e:#?}");
+                    }
+
+                    let cached =
+                        tokio::task::spawn_blocking(move || archive.cached(&query, &sources)).await;
+
+                    match cached {
+                        Ok(Ok(Some((data, cached_at)))) => (Ok(data), Some(cached_at), None),
+                        Ok(Ok(None)) if offline => (
+                            Err("No archived results for this search".to_string()),
+                            None,
+                            None,
+                        ),
+                        Ok(Ok(None)) => (Err(e.to_string()), None, None),
+                        Ok(Err(archive_error)) => (
+                            Err(e.to_string()),
+                            None,
+                            Some(format!("Failed to search the archive: {archive_error}")),
+                        ),
+                        Err(join_error) => (Err(join_error.to_string()), None, None),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{e:#?}");
+                    (Err(e.to_string()), None, None)
+                }
+            }
+        })
+        .then(move |(v, cached_at, failure)| {
+            let complete = Task::done(Message::MainPage(MainPageMessage::SearchComplete(
+                generation, v, cached_at,
+            )));
+
+            match failure {
+                Some(e) => complete.chain(Task::done(Message::MainPage(
+                    MainPageMessage::ArchiveFailed(e),
+                ))),
+                None => complete,
+            }
+        })
+        .abortable();
+
+        self.search_handle = Some(handle);
+        task
+    }
+
+    /// Create a Task that refreshes a saved search in the background and sends a SavedSearchRefreshed message.
+    ///
+    /// The response is archived and its articles recorded for the saved search, the message carries the new unseen count.
+    fn refresh_task(&self, saved: &SavedSearch) -> Task<Message> {
+        let client = self.client.clone();
+        let archive = self.archive.clone();
+        let saved = saved.clone();
+        let id = saved.id;
+        let key_id = self.key_id.clone();
+
+        Task::perform(
+            async move {
+                let data = counted(
+                    &archive,
+                    &key_id,
+                    RequestKind::Background,
+                    fetch_articles(&client, &saved.query, &saved.sources, &saved.params),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+                tokio::task::spawn_blocking(move || {
+                    let ids = archive.store(&saved.query, &saved.sources, &data)?;
+                    archive.record_saved_results(saved.id, &ids, false)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())
+            },
+            move |v| Message::MainPage(MainPageMessage::SavedSearchRefreshed(id, v)),
+        )
+    }
+
+    /// Create a Task that fetches the list of sources and sends a SourcesFetched message.
+    pub fn sources_task(&self) -> Task<Message> {
+        // client and archive are shallow clones
+        let client = self.client.clone();
+        let archive = self.archive.clone();
+        let key_id = self.key_id.clone();
+
+        Task::perform(
+            async move {
+                counted(
+                    &archive,
+                    &key_id,
+                    RequestKind::Interactive,
+                    fetch_sources(&client),
+                )
+                .await
+                .map_err(|e| {
+                    eprintln!("{e:#?}");
+                    e.to_string()
+                })
+            },
+            |v| Message::MainPage(MainPageMessage::SourcesFetched(v)),
+        )
+    }
+
+    /// Create a Task that reloads the watch terms and sends a WatchTermsLoaded message.
+    ///
+    /// Run after anything was fetched, since storing articles may have produced new alerts.
+    fn watch_terms_task(&self) -> Task<Message> {
+        let archive = self.archive.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || archive.watch_terms())
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())
+            },
+            |v| Message::MainPage(MainPageMessage::WatchTermsLoaded(v)),
+        )
+    }
+
+    /// Create a Task that loads the alerts of a watch term, marks them read and sends an AlertsLoaded message.
+    ///
+    /// The alerts keep the read state they had before, so new ones can still be told apart.
+    fn alerts_task(&self, term_id: i64) -> Task<Message> {
+        let archive = self.archive.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let alerts = archive.alerts(term_id)?;
+                    archive.mark_alerts_read(term_id)?;
+                    Ok::<_, NewsAPIError>(alerts)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())
+            },
+            move |v| Message::MainPage(MainPageMessage::AlertsLoaded(term_id, v)),
+        )
+    }
+
+    /// Create a Task that searches the local full-text index and sends a SearchComplete message.
+    fn local_search_task(&mut self) -> Task<Message> {
+        let archive = self.archive.clone();
+        let query = self.search_query.clone();
+        let sources = self.enabled_sources_param();
+        let generation = self.next_generation();
+
+        let (task, handle) = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || archive.search(&query, &sources))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| {
+                        eprintln!("{e:#?}");
+                        e.to_string()
+                    })
+            },
+            move |v| Message::MainPage(MainPageMessage::SearchComplete(generation, v, None)),
+        )
+        .abortable();
+
+        self.search_handle = Some(handle);
+        task
+    }
+}
+
+impl Page for MainPage {
+    fn view(&self, size: (f32, f32)) -> Element<'_, Message> {
+        let w = size.0;
+        // the saved search and facet sidebars take space from the card grid
+        let grid_w = w
+            - if self.saved_sidebar { 280.0 } else { 0.0 }
+            - if self.facet_sidebar { 280.0 } else { 0.0 };
+        // wide enough to read an article next to the results
+        let split_layout = grid_w >= SPLIT_LAYOUT_MIN_WIDTH;
+        let stories = match &self.search_result {
+            Some(Ok(data)) => story_entries(
+                &data.articles,
+                &self.clusters,
+                &self.expanded_stories,
+                &self.tones,
+            ),
+            _ => Vec::new(),
+        };
+        let mut article_chunks = (grid_w / 400.0).floor();
+        if article_chunks < 1.0 {
+            article_chunks = 1.0;
+        }
+
+        let mut source_chunks = (w / 300.0).floor();
+        if source_chunks < 1.0 {
+            source_chunks = 1.0;
+        }
+
+        let article_chunks = article_chunks as usize;
+        let source_chunks = source_chunks as usize;
+
+        Stack::with_capacity(7) // allocate max
+            // bottom layer
+            // has top bar and article card list
+            .push(
+                Column::with_capacity(5) // allocate max
+                    .push(tab_strip(
+                        self.tabs
+                            .iter()
+                            .enumerate()
+                            .map(|(i, tab)| {
+                                let title = if i == self.active_tab {
+                                    tab_title(
+                                        self.shown_label.as_deref().unwrap_or(&self.shown_search.0),
+                                        self.search_result.is_some(),
+                                    )
+                                } else {
+                                    tab_title(
+                                        tab.results.label.as_deref().unwrap_or(&tab.results.query),
+                                        tab.results.search_result.is_some(),
+                                    )
+                                };
+
+                                (tab.id, title)
+                            })
+                            .collect(),
+                        self.active_tab,
+                    ))
+                    .push(row![
+                        container(navigation_buttons(&self.navigation))
+                            .height(Length::Fixed(72.0))
+                            .padding(iced::Padding::new(15.0).right(0.0)),
+                        top_bar(
+                            &self.search_query,
+                            self.enabled_sources.values().filter(|v| **v).count(),
+                            self.offline,
+                            self.local_search,
+                            self.saved_searches.iter().map(|s| s.unseen).sum(),
+                            self.watch_terms.iter().map(|t| t.unread).sum(),
+                            &self.quota,
+                        )
+                    ])
+                    .push_maybe(
+                        self.query_builder_open
+                            .then(|| query_builder_panel(&self.query_builder)),
+                    )
+                    .push_maybe(
+                        self.query_error
+                            .as_ref()
+                            .map(|e| query_error_element(&self.search_query, e)),
+                    )
+                    .push_maybe(
+                        self.quota_warning
+                            .then(|| quota_warning_banner(&self.quota)),
+                    )
+                    .push_maybe(self.archive_error.as_deref().map(archive_error_banner))
+                    .push_maybe(self.cached_at.map(offline_banner))
+                    .push_maybe(compare_bar(self.compare_selection.len()))
+                    .push_maybe(duplicates_notice(
+                        self.duplicate_of.iter().flatten().count(),
+                        self.duplicate_mode,
+                    ))
+                    .push_maybe(matches!(self.search_result, Some(Ok(_))).then(|| {
+                        tone_bar(
+                            self.tone_range,
+                            self.tone_sort,
+                            self.tones
+                                .iter()
+                                .filter(|t| !self.tone_range.contains(**t))
+                                .count(),
+                        )
+                    }))
+                    .push_maybe(grid_filter_notice(
+                        self.grid_filter.as_ref(),
+                        self.clusters.iter().map(Vec::len).sum(),
+                    ))
+                    .push(
+                        Row::with_capacity(3)
+                            .push_maybe(self.saved_sidebar.then(|| {
+                                saved_sidebar(
+                                    &self.saved_searches,
+                                    &self.saved_name,
+                                    self.refresh_interval,
+                                    self.refresh_budget,
+                                    &self.quota,
+                                    self.viewing_saved,
+                                )
+                            }))
+                            .push_maybe(if split_layout {
+                                split_view(
+                                    &self.reading_panes,
+                                    self.search_result.as_ref(),
+                                    stories,
+                                    self.active_article,
+                                    &self.images_loaded,
+                                    &self.compare_selection,
+                                    self.loaded_content(),
+                                )
+                            } else {
+                                article_cards(
+                                    self.search_result.as_ref(),
+                                    &stories,
+                                    article_chunks,
+                                    &self.images_loaded,
+                                    &self.compare_selection,
+                                )
+                            })
+                            .push_maybe(self.facet_sidebar.then(|| {
+                                facet_sidebar(
+                                    &self.facets,
+                                    &self.facet_filters,
+                                    self.clusters.iter().map(Vec::len).sum(),
+                                    &self.gazetteer_entries,
+                                    &self.gazetteer_input,
+                                )
+                            })),
+                    ),
+            )
+            // suggestions dropdown, placed under the search bar
+            .push_maybe((!self.suggestions.is_empty()).then(|| {
+                container(suggestions_dropdown(&self.suggestions))
+                    .padding(iced::Padding::new(15.0).top(67.0 + TAB_STRIP_HEIGHT))
+                    .width(Length::Fill)
+            }))
+            // detailed article page, the split layout shows the article next to the list instead
+            .push_maybe(if split_layout {
+                None
+            } else {
+                article_page(
+                    self.active_article.as_ref(),
+                    self.search_result.as_ref(),
+                    &self.images_loaded,
+                    self.loaded_content(),
+                )
+            })
+            // selected articles side by side
+            .push_maybe(compare_page(self.compare_page, &self.compared_articles()))
+            // alerts page
+            .push_maybe(alerts_page(
+                self.alerts_page,
+                &self.watch_terms,
+                &self.watch_input,
+                self.watch_error.as_ref(),
+                self.alerts_term,
+                self.alerts.as_ref(),
+            ))
+            // archive browse page
+            // search history panel
+            .push_maybe(history_page(self.history_page, &self.history))
+            // keyword trend charts
+            .push_maybe(trends_page(self.trends_page, &self.trends))
+            // source analytics dashboard
+            .push_maybe(dashboard_page(
+                self.dashboard_page,
+                match &self.search_result {
+                    Some(Ok(data)) => &data.articles,
+                    _ => &[],
+                },
+                match &self.source_data {
+                    Some(Ok(data)) => Some(&data.sources),
+                    _ => None,
+                },
+                &self.enabled_sources,
+                self.grid_filter.as_ref(),
+            ))
+            // archive browse page
+            .push_maybe(archive_page(
+                self.archive_page,
+                self.archive_index.as_ref(),
+                self.archive_mode,
+            ))
+            // detailed source page
+            .push_maybe(source_page(
+                self.source_page,
+                self.source_data.as_ref(),
+                &self.enabled_sources,
+                source_chunks,
+                &self.source_filter,
+                &self.everything_params,
+                self.live_search,
+            ))
+            .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        use iced::keyboard::Key;
+        use iced::keyboard::key::Named;
+
+        let refresh = if self.refresh_interval.0 == 0 || self.saved_searches.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_secs(self.refresh_interval.0 * 60))
+                .map(|_| Message::MainPage(MainPageMessage::RefreshSavedSearches))
+        };
+
+        // text inputs don't handle the up and down arrows, so they come through here
+        let history_keys = iced::keyboard::on_key_press(|key, _mods| match key {
+            Key::Named(Named::ArrowUp) => {
+                Some(Message::MainPage(MainPageMessage::HistoryKeyPressed(true)))
+            }
+            Key::Named(Named::ArrowDown) => {
+                Some(Message::MainPage(MainPageMessage::HistoryKeyPressed(false)))
+            }
+            _ => None,
+        });
+
+        // held modifiers, for Shift+clicking facets
+        let modifiers = iced::event::listen_with(|event, _status, _window| match event {
+            iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => Some(
+                Message::MainPage(MainPageMessage::ModifiersChanged(modifiers)),
+            ),
+            _ => None,
+        });
+
+        Subscription::batch([refresh, history_keys, modifiers])
+    }
+
+    fn update(&mut self, message: Message) -> Action {
+        use MainPageMessage::*;
+        use Message::MainPage as M;
+
+        // mouse back button, Alt+Left or the back button
+        if let Message::Back = message {
+            // like leaving a page in a browser, an open article is closed first
+            if self.active_article.is_some() {
+                return Action::Task(self.open_article(None));
+            }
+
+            if !self.navigation.can_go_back() {
+                return Action::PopPage;
+            }
+
+            let current = self.take_snapshot();
+            return match self.navigation.back(current) {
+                Some(previous) => Action::Task(self.restore_snapshot(previous)),
+                None => Action::None,
+            };
+        }
+
+        if let Message::Forward = message {
+            if !self.navigation.can_go_forward() {
+                return Action::None;
+            }
+
+            let current = self.take_snapshot();
+            return match self.navigation.forward(current) {
+                Some(next) => Action::Task(self.restore_snapshot(next)),
+                None => Action::None,
+            };
+        }
+
+        // handle escape key
+        if let Message::Escape = message {
+            let close = match self.active_article {
+                Some(_) => self.open_article(None),
+                None => Task::none(),
+            };
+
+            if self.source_page {
+                self.source_page = false;
+            }
+
+            if self.archive_page {
+                self.archive_page = false;
+            }
+
+            if self.alerts_page {
+                self.alerts_page = false;
+            }
+
+            if self.history_page {
+                self.history_page = false;
+            }
+
+            if self.trends_page {
+                self.trends_page = false;
+            }
+
+            if self.dashboard_page {
+                self.dashboard_page = false;
+            }
+
+            if self.compare_page {
+                self.compare_page = false;
+            }
+
+            self.suggestions.clear();
+
+            return Action::Task(Task::batch([close, focus(SEARCH_BAR_ID)]));
+        }
+
+        if let Message::MainPage(message) = message {
+            match message {
+                SearchBarOnInput(s) => {
+                    self.search_query = s;
+                    self.query_error = None;
+                    self.history_cursor = None;
+                    self.update_suggestions();
+
+                    if !self.live_search {
+                        return Action::None;
+                    }
+
+                    // a search in flight keeps going until the one for the new query starts
+                    self.input_serial += 1;
+                    let serial = self.input_serial;
+
+                    return Action::Task(Task::perform(
+                        tokio::time::sleep(LIVE_SEARCH_DELAY),
+                        move |_| M(LiveSearch(serial)),
+                    ));
+                }
+                // Typing paused, search unless the query is unfinished or the quota is running low
+                LiveSearch(serial) => {
+                    let query = self.search_query.trim();
+                    if serial != self.input_serial
+                        || query.chars().count() < LIVE_SEARCH_MIN_LENGTH
+                        || Query::parse(query).is_err()
+                    {
+                        return Action::None;
+                    }
+
+                    if !self.local_search
+                        && !self.offline
+                        && self.quota.as_of(Utc::now()).remaining() <= LIVE_SEARCH_RESERVE
+                    {
+                        return Action::None;
+                    }
+
+                    // typing settled on this query, it goes in the history like a submitted one
+                    let recorded = self.remember_search();
+                    self.viewing_saved = None;
+                    return Action::Task(Task::batch([recorded, self.search_task()]));
+                }
+                LiveSearchToggled(state) => {
+                    self.live_search = state;
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save live search setting",
+                        LIVE_SEARCH_KEY,
+                        &state.to_string(),
+                    ));
+                }
+                SuggestionSelected(suggestion) => {
+                    self.suggestions.clear();
+
+                    match suggestion {
+                        Suggestion::History(query) => {
+                            self.search_query = query;
+                            return self.update(M(SearchSubmit));
+                        }
+                        Suggestion::Saved(id, _) => return self.update(M(SavedSearchSelected(id))),
+                        // filter by the source instead of searching for its name
+                        Suggestion::Source(id, _) => {
+                            self.enabled_sources.insert(id, true);
+                            self.search_query = String::new();
+                            return self.update(M(SearchSubmit));
+                        }
+                    }
+                }
+                SourceFilterOnInput(s) => self.source_filter = s,
+                SearchSubmit => {
+                    self.viewing_saved = None;
+                    self.suggestions.clear();
+                    // a pending live search would only repeat this one
+                    self.input_serial += 1;
+
+                    // catch syntax mistakes before they cost a request
+                    if !self.search_query.trim().is_empty()
+                        && let Err(e) = Query::parse(&self.search_query)
+                    {
+                        self.query_error = Some(e);
+                        return Action::None;
+                    }
+
+                    let recorded = self.remember_search();
+                    return match self.start_search() {
+                        Action::Task(task) => Action::Task(Task::batch([recorded, task])),
+                        _ => Action::Task(recorded),
+                    };
+                }
+                ConfirmSearch => {
+                    self.quota_warning = false;
+                    return Action::Task(self.search_task());
+                }
+                // Answer this one search from the archive, the search mode stays as it is
+                SearchArchiveInstead => {
+                    self.quota_warning = false;
+                    return Action::Task(self.local_search_task());
+                }
+                DismissQuotaWarning => self.quota_warning = false,
+                SearchComplete(generation, v, cached_at) => {
+                    if generation == self.generation {
+                        let images = self.show_results(generation, v, cached_at);
+
+                        return Action::Task(Task::batch([
+                            scrollable::scroll_to(
+                                scrollable::Id::new(RESULTS_SCROLL_ID),
+                                AbsoluteOffset::default(),
+                            ),
+                            images,
+                            self.watch_terms_task(),
+                            self.quota_task(),
+                        ]));
+                    }
+
+                    // results for a tab in the background
+                    if let Some(index) = self.tabs.iter().position(|t| t.generation == generation) {
+                        let images = self
+                            .with_tab(index, |page| page.show_results(generation, v, cached_at));
+
+                        return Action::Task(Task::batch([
+                            images,
+                            self.watch_terms_task(),
+                            self.quota_task(),
+                        ]));
+                    }
+
+                    // a newer request was made since, its results are on the way
+                    return Action::Task(self.quota_task());
+                }
+                SourcesFetched(v) => {
+                    if let Ok(data) = &v {
+                        self.enabled_sources.clear();
+
+                        for s in &data.sources {
+                            self.enabled_sources.insert(s.id.clone(), false);
+                        }
+                    }
+
+                    self.source_data = Some(v);
+                    return Action::Task(self.quota_task());
+                }
+                // Toggle specific source
+                SourceToggled(id, state) => {
+                    self.enabled_sources.insert(id, state);
+                    // refocus input box
+                    return Action::Task(focus(SOURCE_FILTER_ID));
+                }
+                // Reset source filter
+                DisableAllSources => {
+                    for i in self.enabled_sources.values_mut() {
+                        *i = false;
+                    }
+                }
+                // Toggle the source filter page
+                ToggleSourcePage => {
+                    self.source_page = !self.source_page;
+                    if self.source_page {
+                        return Action::Task(focus(SOURCE_FILTER_ID));
+                    }
+
+                    self.source_filter = String::new();
+                    return Action::Task(focus(SEARCH_BAR_ID));
+                }
+                // Toggle the archive browse page, reloading the index when it opens
+                ToggleArchivePage => {
+                    self.archive_page = !self.archive_page;
+                    if !self.archive_page {
+                        return Action::Task(focus(SEARCH_BAR_ID));
+                    }
+
+                    let archive = self.archive.clone();
+                    return Action::Task(Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || archive.index())
+                                .await
+                                .map_err(|e| e.to_string())?
+                                .map_err(|e| e.to_string())
+                        },
+                        |v| M(ArchiveIndexLoaded(v)),
+                    ));
+                }
+                ArchiveIndexLoaded(v) => self.archive_index = Some(v),
+                ArchiveModeSelected(mode) => self.archive_mode = mode,
+                // Show archived articles in the card grid
+                ArchiveBrowse(filter) => {
+                    self.archive_page = false;
+
+                    let archive = self.archive.clone();
+                    let generation = self.next_generation();
+                    // the results are remembered by what was browsed, not by the search bar
+                    match &filter {
+                        ArchiveFilter::Query(query) => self.requested_search.0 = query.clone(),
+                        ArchiveFilter::Date(day) => {
+                            self.requested_search.0 = String::new();
+                            self.requested_label = Some(format!("Archive: {day}"));
+                        }
+                        ArchiveFilter::Source(key) => {
+                            let name = match &self.archive_index {
+                                Some(Ok(index)) => index
+                                    .sources
+                                    .iter()
+                                    .find(|(k, ..)| k == key)
+                                    .map(|(_, name, _)| name.as_str()),
+                                _ => None,
+                            };
+                            self.requested_search.0 = String::new();
+                            self.requested_label =
+                                Some(format!("Archive: {}", name.unwrap_or(key)));
+                        }
+                    }
+                    return Action::Task(Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || archive.browse(&filter))
+                                .await
+                                .map_err(|e| e.to_string())?
+                                .map_err(|e| e.to_string())
+                        },
+                        move |v| M(SearchComplete(generation, v, None)),
+                    ));
+                }
+                // Toggle offline mode, going offline reruns the current search from the cache.
+                // Going back online only switches the mode, the next search spends a request.
+                ToggleOffline => {
+                    self.offline = !self.offline;
+                    if self.offline {
+                        return Action::Task(self.search_task());
+                    }
+                }
+                ToggleLocalSearch => {
+                    self.local_search = !self.local_search;
+                    return Action::Task(focus(SEARCH_BAR_ID));
+                }
+                // Open the builder with the current query loaded into it
+                ToggleQueryBuilder => {
+                    self.query_builder_open = !self.query_builder_open;
+                    if self.query_builder_open {
+                        self.import_query_into_builder();
+                    }
+                }
+                QueryBuilder(QueryBuilderMessage::ImportFromSearch) => {
+                    self.import_query_into_builder();
+                }
+                QueryBuilder(message) => {
+                    self.query_builder.update(message);
+                    self.search_query = self.query_builder.to_query_string();
+                    self.query_error = None;
+                }
+                SortBySelected(sort_by) => self.everything_params.sort_by = sort_by,
+                LanguageSelected(language) => self.everything_params.language = language.0,
+                ToggleSavedSidebar => self.saved_sidebar = !self.saved_sidebar,
+                SavedNameOnInput(s) => self.saved_name = s,
+                // Save the current query, sources and parameters under the entered name
+                SaveCurrentSearch => {
+                    let name = match self.saved_name.trim() {
+                        "" => match self.search_query.trim() {
+                            "" => "Top headlines".to_string(),
+                            query => query.to_string(),
+                        },
+                        name => name.to_string(),
+                    };
+                    self.saved_name = String::new();
+
+                    return Action::Task(self.save_search_task(
+                        name,
+                        self.search_query.clone(),
+                        self.enabled_sources_param(),
+                    ));
+                }
+                SavedSearchSaved(v) => match v {
+                    Ok(saved) => {
+                        // saving the search on screen means its results have been seen
+                        if saved.query == self.search_query
+                            && saved.sources == self.enabled_sources_param()
+                        {
+                            self.viewing_saved = Some(saved.id);
+                        }
+                        self.saved_searches.push(saved);
+                    }
+                    Err(e) => self.archive_error = Some(format!("Failed to save search: {e}")),
+                },
+                // Show a saved search, restoring its query, sources and parameters
+                SavedSearchSelected(id) => {
+                    let Some(saved) = self.saved_searches.iter().find(|s| s.id == id) else {
+                        return Action::None;
+                    };
+
+                    self.search_query = saved.query.clone();
+                    self.everything_params = saved.params.clone();
+                    let sources = saved.sources.clone();
+                    self.select_sources(&sources);
+
+                    self.viewing_saved = Some(id);
+                    self.query_error = None;
+                    return self.start_search();
+                }
+                ResultsScrolled(offset) => self.results_scroll = offset,
+                ToggleHistoryPage => {
+                    self.history_page = !self.history_page;
+                }
+                // Toggle the trends page, recounting the terms when it opens
+                ToggleTrendsPage => {
+                    self.trends_page = !self.trends_page;
+                    if !self.trends_page {
+                        return Action::Task(focus(SEARCH_BAR_ID));
+                    }
+
+                    return Action::Task(Task::batch([
+                        self.reload_trends(),
+                        focus(TREND_INPUT_ID),
+                    ]));
+                }
+                ToggleDashboardPage => {
+                    self.dashboard_page = !self.dashboard_page;
+                }
+                // Show only the articles behind a clicked dashboard bar
+                GridFilterSelected(filter) => {
+                    self.dashboard_page = false;
+                    self.grid_filter = Some(filter);
+                    self.group_stories();
+                    self.expanded_stories.clear();
+                }
+                ClearGridFilter => {
+                    self.grid_filter = None;
+                    self.group_stories();
+                    self.expanded_stories.clear();
+                }
+                ToggleFacetSidebar => self.facet_sidebar = !self.facet_sidebar,
+                // Clicking a facet filtered on removes the filter
+                FacetPressed(kind, key) => {
+                    let before = self.facet_filters.len();
+                    self.facet_filters
+                        .retain(|f| f.kind != kind || f.key != key);
+                    if self.facet_filters.len() == before {
+                        self.facet_filters.push(FacetFilter {
+                            kind,
+                            key,
+                            exclude: self.modifiers.shift(),
+                        });
+                    }
+                    self.group_stories();
+                    self.expanded_stories.clear();
+                }
+                ClearFacetFilters => {
+                    self.facet_filters.clear();
+                    self.group_stories();
+                    self.expanded_stories.clear();
+                }
+                GazetteerInputOnInput(s) => self.gazetteer_input = s,
+                GazetteerEntryAdded => {
+                    let entry = self.gazetteer_input.trim().to_string();
+                    self.gazetteer_input.clear();
+                    if entry.is_empty() || self.gazetteer_entries.contains(&entry) {
+                        return Action::None;
+                    }
+
+                    self.gazetteer_entries.push(entry);
+                    return Action::Task(self.gazetteer_changed());
+                }
+                GazetteerEntryRemoved(index) => {
+                    if index < self.gazetteer_entries.len() {
+                        self.gazetteer_entries.remove(index);
+                        return Action::Task(self.gazetteer_changed());
+                    }
+                }
+                ModifiersChanged(modifiers) => self.modifiers = modifiers,
+                TrendInputOnInput(s) => self.trends.input = s,
+                TrendTermAdded => {
+                    let term = self.trends.input.trim().to_string();
+                    self.trends.input.clear();
+
+                    if term.is_empty() || self.trends.terms.contains(&term) {
+                        return Action::None;
+                    }
+
+                    self.trends.terms.push(term.clone());
+                    return Action::Task(self.trend_counts_task(term));
+                }
+                TrendTermRemoved(term) => {
+                    self.trends.terms.retain(|t| *t != term);
+                    self.trends.counts.remove(&term);
+                }
+                TrendRangeSelected(range) => {
+                    self.trends.range = range;
+                    return Action::Task(self.reload_trends());
+                }
+                TrendIntervalSelected(interval) => {
+                    self.trends.interval = interval;
+                    return Action::Task(self.reload_trends());
+                }
+                ChartKindSelected(kind) => self.trends.kind = kind,
+                TrendBySourceToggled(by_source) => self.trends.by_source = by_source,
+                TrendCountsLoaded(generation, term, v) => {
+                    if generation == self.trends.generation && self.trends.terms.contains(&term) {
+                        self.trends.counts.insert(term, v);
+                    }
+                }
+                HistoryRerun(id) => {
+                    let Some(entry) = self.history.iter().find(|h| h.id == id).cloned() else {
+                        return Action::None;
+                    };
+
+                    self.search_query = entry.query;
+                    self.select_sources(&entry.sources);
+                    self.history_page = false;
+                    return self.update(M(SearchSubmit));
+                }
+                HistorySave(id) => {
+                    let Some(entry) = self.history.iter().find(|h| h.id == id).cloned() else {
+                        return Action::None;
+                    };
+
+                    let name = match entry.query.as_str() {
+                        "" => "Top headlines".to_string(),
+                        q => q.to_string(),
+                    };
+                    return Action::Task(self.save_search_task(name, entry.query, entry.sources));
+                }
+                ClearHistory => {
+                    self.history.clear();
+                    self.history_cursor = None;
+
+                    return Action::Task(self.archive_task(
+                        "Failed to clear search history",
+                        |archive| archive.clear_search_history(),
+                        |()| Message::NoOp,
+                    ));
+                }
+                HistoryKeyPressed(up) => {
+                    if self.history_page
+                        || self.trends_page
+                        || self.dashboard_page
+                        || self.compare_page
+                        || self.alerts_page
+                        || self.archive_page
+                        || self.source_page
+                        || self.query_builder_open
+                        || self.active_article.is_some()
+                    {
+                        return Action::None;
+                    }
+
+                    // only recall history while the search bar has focus
+                    let search_bar = iced::advanced::widget::Id::new(SEARCH_BAR_ID);
+                    return Action::Task(
+                        iced::advanced::widget::operate(
+                            iced::advanced::widget::operation::focusable::find_focused(),
+                        )
+                        .map(move |id| {
+                            if id == search_bar {
+                                M(HistoryRecall(up))
+                            } else {
+                                Message::NoOp
+                            }
+                        }),
+                    );
+                }
+                HistoryRecall(up) => {
+                    let current = self.history_cursor.map(|i| i.min(self.history.len()));
+
+                    // skip entries that only differ from the shown query by their sources
+                    let next = if up {
+                        let start = current.map_or(0, |i| i + 1);
+                        match self
+                            .history
+                            .iter()
+                            .enumerate()
+                            .skip(start)
+                            .find(|(_, h)| h.query != self.search_query)
+                        {
+                            Some((i, _)) => Some(i),
+                            None => return Action::None,
+                        }
+                    } else {
+                        let Some(i) = current else {
+                            return Action::None;
+                        };
+                        self.history[..i]
+                            .iter()
+                            .rposition(|h| h.query != self.search_query)
+                    };
+
+                    if current.is_none() {
+                        self.history_draft = self.search_query.clone();
+                    }
+
+                    self.search_query = match next {
+                        Some(i) => self.history[i].query.clone(),
+                        None => std::mem::take(&mut self.history_draft),
+                    };
+                    self.history_cursor = next;
+                    self.query_error = None;
+                    self.suggestions.clear();
+                    return Action::Task(move_cursor_to_end(SEARCH_BAR_ID));
+                }
+                SavedSearchDeleted(id) => {
+                    self.saved_searches.retain(|s| s.id != id);
+                    if self.viewing_saved == Some(id) {
+                        self.viewing_saved = None;
+                    }
+
+                    return Action::Task(self.archive_task(
+                        "Failed to delete saved search",
+                        move |archive| archive.delete_saved_search(id),
+                        |()| Message::NoOp,
+                    ));
+                }
+                RefreshIntervalSelected(interval) => {
+                    self.refresh_interval = interval;
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save refresh interval",
+                        REFRESH_INTERVAL_KEY,
+                        &interval.0.to_string(),
+                    ));
+                }
+                RefreshBudgetSelected(budget) => {
+                    self.refresh_budget = budget;
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save refresh budget",
+                        REFRESH_BUDGET_KEY,
+                        &budget.0.to_string(),
+                    ));
+                }
+                // Timer tick, refresh as many saved searches as the background budget allows,
+                // taking turns so every saved search gets refreshed when they can't all be
+                RefreshSavedSearches => {
+                    if self.offline || self.saved_searches.is_empty() {
+                        return Action::None;
+                    }
+
+                    let n = self.saved_searches.len();
+                    let allowance = self
+                        .quota
+                        .as_of(Utc::now())
+                        .background_allowance(
+                            self.refresh_budget.0,
+                            Duration::from_secs(self.refresh_interval.0 * 60),
+                            Utc::now(),
+                        )
+                        .min(n);
+
+                    let start = self.refresh_cursor % n;
+                    self.refresh_cursor = (start + allowance) % n;
+
+                    return Action::Task(Task::batch(
+                        (start..start + allowance)
+                            .map(|i| self.refresh_task(&self.saved_searches[i % n])),
+                    ));
+                }
+                SavedSearchRefreshed(id, v) => {
+                    match v {
+                        Ok(unseen) => {
+                            if let Some(saved) = self.saved_searches.iter_mut().find(|s| s.id == id)
+                            {
+                                // anything new in the search being viewed will be seen on the next view
+                                saved.unseen = unseen;
+                            }
+
+                            return Action::Task(Task::batch([
+                                self.watch_terms_task(),
+                                self.quota_task(),
+                            ]));
+                        }
+                        Err(e) => {
+                            self.archive_error =
+                                Some(format!("Failed to refresh saved search: {e}"));
+                            return Action::Task(self.quota_task());
+                        }
+                    }
+                }
+                // Toggle the alerts page, reloading the watch terms when it opens
+                ToggleAlertsPage => {
+                    self.alerts_page = !self.alerts_page;
+                    if !self.alerts_page {
+                        return Action::Task(focus(SEARCH_BAR_ID));
+                    }
+
+                    let mut tasks = vec![self.watch_terms_task()];
+                    if let Some(id) = self.alerts_term {
+                        tasks.push(self.alerts_task(id));
+                    }
+                    return Action::Task(Task::batch(tasks));
+                }
+                WatchTermsLoaded(v) => match v {
+                    Ok(terms) => {
+                        // alerts being looked at right now were marked read when they loaded
+                        self.watch_terms = terms;
+                        if self.alerts_page
+                            && let Some(id) = self.alerts_term
+                            && let Some(term) = self.watch_terms.iter().find(|t| t.id == id)
+                            && term.unread > 0
+                        {
+                            return Action::Task(self.alerts_task(id));
+                        }
+                    }
+                    Err(e) => self.archive_error = Some(format!("Failed to load watch terms: {e}")),
+                },
+                WatchInputOnInput(s) => {
+                    self.watch_input = s;
+                    self.watch_error = None;
+                }
+                // Validate the entered term with the query parser and start watching it
+                AddWatchTerm => {
+                    let term = self.watch_input.trim().to_string();
+                    if let Err(e) = Query::parse(&term) {
+                        self.watch_error = Some(e);
+                        return Action::None;
+                    }
+
+                    self.watch_input = String::new();
+                    let added = self.archive_task(
+                        "Failed to add watch term",
+                        move |archive| archive.add_watch_term(&term),
+                        |()| Message::NoOp,
+                    );
+                    return Action::Task(added.chain(self.watch_terms_task()));
+                }
+                WatchTermRemoved(id) => {
+                    self.watch_terms.retain(|t| t.id != id);
+                    if self.alerts_term == Some(id) {
+                        self.alerts_term = None;
+                        self.alerts = None;
+                    }
+
+                    return Action::Task(self.archive_task(
+                        "Failed to remove watch term",
+                        move |archive| archive.remove_watch_term(id),
+                        |()| Message::NoOp,
+                    ));
+                }
+                WatchTermSelected(id) => {
+                    self.alerts_term = Some(id);
+                    self.alerts = None;
+                    return Action::Task(self.alerts_task(id));
+                }
+                AlertsLoaded(id, v) => {
+                    // ignore alerts for a term that is no longer selected
+                    if self.alerts_term != Some(id) {
+                        return Action::None;
+                    }
+
+                    if let Some(term) = self.watch_terms.iter_mut().find(|t| t.id == id) {
+                        term.unread = 0;
+                    }
+                    self.alerts = Some(v);
+                }
+                // Show the alerts of the selected term as results, opening the pressed one
+                AlertOpened(index) => {
+                    let action = self.update(M(ShowAlertArticles));
+                    let images = self.open_article(Some(index));
+                    return match action {
+                        Action::Task(task) => Action::Task(Task::batch([task, images])),
+                        _ => Action::Task(images),
+                    };
+                }
+                ShowAlertArticles => {
+                    let Some(Ok(alerts)) = &self.alerts else {
+                        return Action::None;
+                    };
+
+                    let data = NewsAPIArticlesSuccess {
+                        status: "alerts".to_string(),
+                        total_results: alerts.len() as i32,
+                        articles: alerts.iter().map(|a| a.article.clone()).collect(),
+                    };
+
+                    self.alerts_page = false;
+                    self.viewing_saved = None;
+                    let generation = self.next_generation();
+                    let term = self
+                        .watch_terms
+                        .iter()
+                        .find(|t| Some(t.id) == self.alerts_term)
+                        .map_or("", |t| t.term.as_str());
+                    self.requested_search.0 = String::new();
+                    self.requested_label = Some(format!("Alerts: {term}"));
+                    return self.update(M(SearchComplete(generation, Ok(data), None)));
+                }
+                ImageLoaded(generation, data) => {
+                    let Some((i, handle)) = data else {
+                        return Action::None;
+                    };
+
+                    let images_loaded = if generation == self.generation {
+                        &mut self.images_loaded
+                    } else if let Some(tab) =
+                        self.tabs.iter_mut().find(|t| t.generation == generation)
+                    {
+                        &mut tab.results.images_loaded
+                    } else {
+                        return Action::None;
+                    };
+
+                    if let Some(image) = images_loaded.get_mut(i) {
+                        *image = Some(handle);
+                    }
+                }
+                PopOutArticle(index) => {
+                    if let Some(Ok(data)) = &self.search_result
+                        && let Some(article) = data.articles.get(index)
+                    {
+                        let content_blocks = article
+                            .content
+                            .as_deref()
+                            .map(|content| content_blocks(content, article.url.as_deref()))
+                            .unwrap_or_default();
+                        let content_images = content_blocks
+                            .iter()
+                            .filter_map(|block| match block {
+                                ContentBlock::Image(url) => {
+                                    Some((url.clone(), self.content_images.get(url)?.clone()))
+                                }
+                                ContentBlock::Text(..) => None,
+                            })
+                            .collect();
+                        // reader mode content that finished loading moves along with the article
+                        let reader = self
+                            .reader
+                            .as_ref()
+                            .filter(|reader| {
+                                article.url.as_ref() == Some(&reader.url)
+                                    && matches!(reader.result, Some(Ok(_)))
+                            })
+                            .cloned();
+
+                        let article_window = ArticleWindow {
+                            article: article.clone(),
+                            image: self.images_loaded.get(index).cloned().flatten(),
+                            content_images,
+                            content_blocks,
+                            summary: article_summary(article, reader.as_ref(), self.summary_length),
+                            reader,
+                            summary_length: self.summary_length,
+                            offline: self.offline,
+                        };
+
+                        // keep browsing the results while the article is open in its window
+                        self.active_article = None;
+                        return Action::PopOutArticle(Box::new(article_window));
+                    }
+                }
+                OpenReader(index) => {
+                    if let Some(Ok(data)) = &self.search_result
+                        && let Some(url) = data.articles.get(index).and_then(|a| a.url.clone())
+                    {
+                        self.reader = Some(ReaderView::new(url.clone()));
+
+                        // content extracted earlier is kept in the archive
+                        let archived = url.clone();
+                        return Action::Task(self.archive_task(
+                            "Failed to read reader mode content",
+                            move |archive| Ok(archive.reader_article(&archived)),
+                            move |v| M(ReaderArchived(url.clone(), v.map_err(|e| e.to_string()))),
+                        ));
+                    }
+                }
+                ReaderArchived(url, v) => {
+                    // the reader was closed or opened for another article in the meantime
+                    if self.reader.as_ref().is_none_or(|r| r.url != url) {
+                        return Action::None;
+                    }
+
+                    match v {
+                        Ok(Some(article)) => return self.update(M(ReaderLoaded(url, Ok(article)))),
+                        Ok(None) => (),
+                        // the page can still be downloaded again
+                        Err(e) => {
+                            self.archive_error =
+                                Some(format!("Failed to read reader mode content: {e}"));
+                        }
+                    }
+
+                    if self.offline {
+                        return Action::Task(Task::done(Message::MainPage(ReaderLoaded(
+                            url,
+                            Err(NewsAPIError::Offline.to_string()),
+                        ))));
+                    }
+
+                    return Action::Task(Task::perform(
+                        async move {
+                            let result =
+                                fetch_reader_article(&url).await.map_err(|e| e.to_string());
+                            (url, result)
+                        },
+                        |(url, result)| Message::MainPage(ReaderLoaded(url, result)),
+                    ));
+                }
+                CloseReader => {
+                    self.reader = None;
+                    self.update_summary();
+                }
+                ReaderLoaded(url, result) => {
+                    let stored = match &result {
+                        Ok(article) => {
+                            let (url, article) = (url.clone(), article.clone());
+                            self.archive_task(
+                                "Failed to store reader mode content",
+                                move |archive| archive.store_reader_article(&url, &article),
+                                |()| Message::NoOp,
+                            )
+                        }
+                        Err(_) => Task::none(),
+                    };
+
+                    // the reader was closed or opened for another article in the meantime
+                    let Some(reader) = self.reader.as_mut().filter(|r| r.url == url) else {
+                        return Action::Task(stored);
+                    };
+
+                    let images = match &result {
+                        Ok(article) => Task::batch(
+                            article
+                                .image_urls()
+                                .map(|image_url| reader_image_task(&url, image_url, self.offline)),
+                        ),
+                        Err(_) => Task::none(),
+                    };
+                    reader.result = Some(result);
+                    self.update_summary();
+                    return Action::Task(Task::batch([stored, images]));
+                }
+                ReaderImageLoaded(url, image_url, image) => {
+                    if let Some(reader) = self.reader.as_mut().filter(|r| r.url == url)
+                        && let Some(image) = image
+                    {
+                        reader.images.insert(image_url, image);
+                    }
+                }
+                SplitResized(event) => {
+                    self.split_ratio = clamp_ratio(event.ratio);
+                    self.reading_panes.resize(event.split, self.split_ratio);
+
+                    self.split_serial += 1;
+                    let serial = self.split_serial;
+
+                    return Action::Task(Task::perform(
+                        tokio::time::sleep(SPLIT_SAVE_DELAY),
+                        move |_| M(SaveSplitRatio(serial)),
+                    ));
+                }
+                SaveSplitRatio(serial) => {
+                    if serial == self.split_serial {
+                        return Action::Task(self.save_setting(
+                            "Failed to save split ratio",
+                            SPLIT_RATIO_KEY,
+                            &self.split_ratio.to_string(),
+                        ));
+                    }
+                }
+                NewTab => {
+                    self.last_tab_id += 1;
+                    self.tabs.push(Tab {
+                        id: self.last_tab_id,
+                        ..Default::default()
+                    });
+
+                    return Action::Task(self.show_tab(self.tabs.len() - 1, true));
+                }
+                SelectTab(id) => {
+                    if let Some(index) = self.tabs.iter().position(|t| t.id == id)
+                        && index != self.active_tab
+                    {
+                        return Action::Task(self.show_tab(index, true));
+                    }
+                }
+                CloseTab(id) => {
+                    if let Some(index) = self.tabs.iter().position(|t| t.id == id) {
+                        return Action::Task(self.close_tab(index));
+                    }
+                }
+                CloseActiveTab => return Action::Task(self.close_tab(self.active_tab)),
+                CycleTab(next) => {
+                    if self.tabs.len() > 1 {
+                        let n = self.tabs.len();
+                        let index = if next {
+                            (self.active_tab + 1) % n
+                        } else {
+                            (self.active_tab + n - 1) % n
+                        };
+
+                        return Action::Task(self.show_tab(index, true));
+                    }
+                }
+                MoveTab(id, right) => {
+                    let Some(index) = self.tabs.iter().position(|t| t.id == id) else {
+                        return Action::None;
+                    };
+
+                    let other = if right {
+                        index + 1
+                    } else {
+                        index.wrapping_sub(1)
+                    };
+
+                    if other < self.tabs.len() {
+                        self.tabs.swap(index, other);
+
+                        if self.active_tab == index {
+                            self.active_tab = other;
+                        } else if self.active_tab == other {
+                            self.active_tab = index;
+                        }
+                    }
+                }
+                // images in the article content are loaded once it's opened
+                ActiveArticle(index) => return Action::Task(self.open_article(index)),
+                ToggleCompare(index) => {
+                    if let Some(position) = self.compare_selection.iter().position(|&i| i == index)
+                    {
+                        self.compare_selection.remove(position);
+                    } else if self.compare_selection.len() < MAX_COMPARED {
+                        self.compare_selection.push(index);
+                    }
+                }
+                OpenComparison => {
+                    self.compare_page = self.compare_selection.len() >= 2;
+                }
+                CloseComparison => {
+                    self.compare_page = false;
+                }
+                ClearCompareSelection => {
+                    self.compare_selection.clear();
+                }
+                DuplicateModeSelected(mode) => {
+                    self.duplicate_mode = mode;
+                    self.group_stories();
+                    self.expanded_stories.clear();
+                    self.compare_selection.retain(|&i| {
+                        mode == DuplicateMode::Collapse || self.duplicate_of[i].is_none()
+                    });
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save duplicates setting",
+                        DUPLICATES_KEY,
+                        mode.as_param(),
+                    ));
+                }
+                ToneMinChanged(min) => {
+                    // whole tenths, so the saved range reads well
+                    let min = (min * 10.0).round() / 10.0;
+                    self.tone_range.min = min;
+                    self.tone_range.max = self.tone_range.max.max(min);
+                    self.group_stories();
+                }
+                ToneMaxChanged(max) => {
+                    let max = (max * 10.0).round() / 10.0;
+                    self.tone_range.max = max;
+                    self.tone_range.min = self.tone_range.min.min(max);
+                    self.group_stories();
+                }
+                SaveToneRange => {
+                    return Action::Task(self.save_setting(
+                        "Failed to save tone range",
+                        TONE_RANGE_KEY,
+                        &self.tone_range.as_param(),
+                    ));
+                }
+                ResetToneRange => {
+                    self.tone_range = ToneRange::FULL;
+                    self.group_stories();
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save tone range",
+                        TONE_RANGE_KEY,
+                        &self.tone_range.as_param(),
+                    ));
+                }
+                ToneSortSelected(sort) => {
+                    self.tone_sort = sort;
+                    self.group_stories();
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save tone sort",
+                        TONE_SORT_KEY,
+                        sort.as_param(),
+                    ));
+                }
+                ToggleStory(index) => {
+                    if !self.expanded_stories.remove(&index) {
+                        self.expanded_stories.insert(index);
+                    }
+                }
+                SummaryLengthSelected(length) => {
+                    self.summary_length = length;
+                    self.update_summary();
+
+                    return Action::Task(self.save_setting(
+                        "Failed to save summary length",
+                        SUMMARY_LENGTH_KEY,
+                        &length.0.to_string(),
+                    ));
+                }
+                ContentImageLoaded(url, image) => {
+                    if let Some(image) = image {
+                        self.content_images.insert(url, image);
+                    }
+                }
+                QuotaLoaded(quota) => self.quota = quota,
+                SearchRecorded(entry) => {
+                    self.history.retain(|h| h.id != entry.id);
+                    self.history.insert(0, entry);
+                }
+                ArchiveFailed(e) => {
+                    eprintln!("{e}");
+                    self.archive_error = Some(e);
+                }
+                DismissArchiveError => self.archive_error = None,
+                BackToApiKeyPage => {
+                    return Action::PushPage((
+                        Box::new(TokenPage::over_main_page()),
+                        focus(TOKEN_INPUT_ID),
+                    ));
+                }
+            }
+        }
+
+        Action::None
+    }
+}
+
+/// Create a task that loads an image of reader mode content.
+///
+/// Parameters:
+/// - url: the url of the article the content belongs to
+/// - image_url: the url of the image
+/// - offline: only read the image from the local cache
+///
+/// Returns:
+/// - a Task dispatching Message::MainPage(MainPageMessage::ReaderImageLoaded(url, image_url, image)), with None when the image couldn't be loaded
+fn reader_image_task(url: &str, image_url: &str, offline: bool) -> Task<Message> {
+    let url = url.to_string();
+    let image_url = image_url.to_string();
+
+    Task::perform(
+        async move {
+            let image = match get_image_from_url(&image_url, offline).await {
+                Ok(bytes) => Some(Handle::from_bytes(bytes)),
+                Err(NewsAPIError::Offline) => None,
+                Err(e) => {
+                    eprintln!("Error getting image: {e:#?}");
+                    None
+                }
+            };
+            (url, image_url, image)
+        },
+        |(url, image_url, image)| {
+            Message::MainPage(MainPageMessage::ReaderImageLoaded(url, image_url, image))
+        },
+    )
+}
+
+/// Create a task that loads an image of article content.
+///
+/// Parameters:
+/// - url: the url of the image
+/// - offline: only read the image from the local cache
+///
+/// Returns:
+/// - a Task dispatching Message::MainPage(MainPageMessage::ContentImageLoaded(url, image)), with None when the image couldn't be loaded
+fn content_image_task(url: String, offline: bool) -> Task<Message> {
+    Task::perform(
+        async move {
+            let image = match get_image_from_url(&url, offline).await {
+                Ok(bytes) => Some(Handle::from_bytes(bytes)),
+                Err(NewsAPIError::Offline) => None,
+                Err(e) => {
+                    eprintln!("Error getting image: {e:#?}");
+                    None
+                }
+            };
+            (url, image)
+        },
+        |(url, image)| Message::MainPage(MainPageMessage::ContentImageLoaded(url, image)),
+    )
+}
+
+/// Run a NewsAPI request, counting it against the daily quota.
+///
+/// The request is counted before it is sent, so requests aborted while in flight are never missed,
+/// and refunded if it turns out to have never reached NewsAPI.
+async fn counted<T>(
+    archive: &Archive,
+    key_id: &str,
+    kind: RequestKind,
+    request: impl Future<Output = Result<T, NewsAPIError>>,
+) -> Result<T, NewsAPIError> {
+    let count = |refund: bool| {
+        let archive = archive.clone();
+        let key_id = key_id.to_string();
+
+        async move {
+            let counted = tokio::task::spawn_blocking(move || match refund {
+                false => archive.record_request(&key_id, kind),
+                true => archive.refund_request(&key_id, kind),
+            })
+            .await;
+
+            match counted {
+                Ok(Ok(_)) => (),
+                Ok(Err(e)) => eprintln!("Failed to count request: {e:?}"),
+                Err(e) => eprintln!("{e:#?}"),
+            }
+        }
+    };
+
+    count(false).await;
+    let result = request.await;
+
+    if result.as_ref().is_err_and(|e| !e.reached_api()) {
+        count(true).await;
+    }
+
+    result
 }
 
 /// Create a Task that loads an article image and sends an ImageLoaded message.
 ///
 /// Parameters:
 /// - input: a tuple of (index, article)
+/// - offline: only read images from the local cache
+/// - generation: the generation of the results the article belongs to
 ///
 /// Returns:
-/// - a Task that, if the article has an image URL, fetches the image bytes, converts them to a Handle and dispatches Message::MainPage(MainPageMessage::ImageLoaded(Some((index, Handle)))) on success; logs errors and dispatches None on failure. If the article has no image URL returns Task::none().
-fn image_task(input: (usize, &Article)) -> Task<Message> {
+/// - a Task that, if the article has an image URL, fetches the image bytes, converts them to a Handle and dispatches Message::MainPage(MainPageMessage::ImageLoaded(generation, Some((index, Handle)))) on success; logs errors and dispatches None on failure. If the article has no image URL returns Task::none().
+fn image_task(input: (usize, &Article), offline: bool, generation: u64) -> Task<Message> {
     let (index, article) = input;
 
     match &article.url_to_image {
@@ -377,15 +3277,17 @@ fn image_task(input: (usize, &Article)) -> Task<Message> {
 
             Task::perform(
                 async move {
-                    match get_image_from_url(&url).await {
+                    match get_image_from_url(&url, offline).await {
                         Ok(bytes) => Some((index, Handle::from_bytes(bytes))),
+                        // uncached images are expected to be missing while offline
+                        Err(NewsAPIError::Offline) => None,
                         Err(e) => {
                             eprintln!("Error getting image: {e:#?}");
                             None
                         }
                     }
                 },
-                |data| Message::MainPage(MainPageMessage::ImageLoaded(data)),
+                move |data| Message::MainPage(MainPageMessage::ImageLoaded(generation, data)),
             )
         }
         None => Task::none(),
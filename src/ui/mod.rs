@@ -1,23 +1,65 @@
 use crate::DEFAULT_SIZE;
+use crate::newsapi::NewsAPIError;
+use crate::newsapi::article::Article;
+use crate::newsapi::html::ContentBlock;
+use crate::ui::article::LoadedContent;
+use crate::ui::article::SummaryLength;
+use crate::ui::article::article_view;
+use crate::ui::article::get_image_from_url;
 use crate::ui::main_page::MainPageMessage;
+use crate::ui::reader::ReaderView;
 use crate::ui::token_page::TokenPage;
 use crate::ui::token_page::TokenPageMessage;
 use iced::Element;
+use iced::Event;
+use iced::Font;
+use iced::Length;
+use iced::Size;
 use iced::Subscription;
 use iced::Task;
+use iced::event::Status;
+use iced::keyboard::Key;
+use iced::keyboard::Modifiers;
 use iced::keyboard::key::Named;
+use iced::widget::container;
+use iced::widget::image::Handle;
 use iced::widget::text_input::focus;
+use iced::window;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 
+mod alerts;
+mod archive;
 mod article;
+mod chart;
+mod compare;
+mod dashboard;
+mod facets;
+mod history;
+mod html;
 mod main_page;
+mod navigation;
+mod query_builder;
+mod reader;
+mod reading;
+mod saved;
 mod source;
+mod stories;
 mod style;
+mod suggestions;
+mod tabs;
 mod token_page;
+mod tone;
+mod trends;
 
 /// Common interface for a UI page in the application.
 pub trait Page {
     fn update(&mut self, message: Message) -> Action;
     fn view(&self, size: (f32, f32)) -> Element<'_, Message>;
+    /// Subscriptions specific to the page, merged with the application wide ones.
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::none()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,74 +67,321 @@ pub enum Message {
     TokenPage(TokenPageMessage),
     MainPage(MainPageMessage),
     OpenLink(String),
-    Resized(f32, f32),
+    Resized(window::Id, f32, f32),
     Escape,
+    // mouse back/forward buttons, Alt+Left/Right or the navigation buttons
+    Back,
+    Forward,
+    // a message from input in a window, the main window's go to the page
+    Window(window::Id, Box<Message>),
+    WindowClosed(window::Id),
+    // an image of the article in a popped out window finished loading, by url
+    WindowImageLoaded(window::Id, String, Handle),
     NoOp,
 }
 
 pub enum Action {
+    /// Replace the page and every page under it
     SwitchPage((Box<dyn Page>, Task<Message>)),
+    /// Show a page on top of the current one, which is kept to return to
+    PushPage((Box<dyn Page>, Task<Message>)),
+    /// Return to the page under the current one, if any
+    PopPage,
+    /// Open an article in a window of its own
+    PopOutArticle(Box<ArticleWindow>),
     Task(Task<Message>),
     None,
 }
 
+/// An article popped out into its own window.
+pub struct ArticleWindow {
+    pub article: Article,
+    pub image: Option<Handle>,
+    /// images of the article content by url
+    pub content_images: HashMap<String, Handle>,
+    /// blocks of the article content, parsed when the article was popped out
+    pub content_blocks: Vec<ContentBlock>,
+    /// reader mode content loaded for the article before it was popped out
+    pub reader: Option<ReaderView>,
+    /// sentences of the summary, picked when the article was popped out
+    pub summary: Vec<String>,
+    /// number of sentences in the summary, as set when the article was popped out
+    pub summary_length: SummaryLength,
+    /// whether images still missing when the article was popped out are only read from the cache
+    pub offline: bool,
+}
+
+impl ArticleWindow {
+    /// Urls of the images the article shows that haven't loaded yet.
+    fn missing_images(&self) -> Vec<String> {
+        let mut urls = Vec::new();
+
+        if self.image.is_none()
+            && let Some(url) = &self.article.url_to_image
+        {
+            urls.push(url.clone());
+        }
+        for block in &self.content_blocks {
+            if let ContentBlock::Image(url) = block
+                && !self.content_images.contains_key(url)
+            {
+                urls.push(url.clone());
+            }
+        }
+        if let Some(reader) = &self.reader
+            && let Some(Ok(content)) = &reader.result
+        {
+            urls.extend(
+                content
+                    .image_urls()
+                    .filter(|url| !reader.images.contains_key(*url))
+                    .map(str::to_string),
+            );
+        }
+
+        urls.sort();
+        urls.dedup();
+        urls
+    }
+
+    /// Show a loaded image everywhere the article uses it.
+    fn image_loaded(&mut self, url: String, image: Handle) {
+        if self.article.url_to_image.as_ref() == Some(&url) {
+            self.image = Some(image.clone());
+        }
+        if let Some(reader) = &mut self.reader {
+            reader.images.insert(url.clone(), image.clone());
+        }
+        if self
+            .content_blocks
+            .iter()
+            .any(|block| matches!(block, ContentBlock::Image(u) if *u == url))
+        {
+            self.content_images.insert(url, image);
+        }
+    }
+}
+
 pub struct App {
+    /// the shown page, receives every message
     page: Box<dyn Page>,
+    /// pages `page` was pushed on top of, the most recent last
+    below: Vec<Box<dyn Page>>,
+    /// window the pages are shown in, closing it quits the application
+    main_window: window::Id,
     window_size: (f32, f32),
+    /// popped out articles by window
+    article_windows: BTreeMap<window::Id, ArticleWindow>,
+}
+
+/// Map Ctrl+T, Ctrl+W and Ctrl+Tab / Ctrl+Shift+Tab to tab messages.
+fn tab_shortcut(key: &Key, modifiers: Modifiers) -> Option<MainPageMessage> {
+    match key {
+        Key::Character(c) if modifiers.command() && c.as_str() == "t" => {
+            Some(MainPageMessage::NewTab)
+        }
+        Key::Character(c) if modifiers.command() && c.as_str() == "w" => {
+            Some(MainPageMessage::CloseActiveTab)
+        }
+        Key::Named(Named::Tab) if modifiers.control() => {
+            Some(MainPageMessage::CycleTab(!modifiers.shift()))
+        }
+        _ => None,
+    }
 }
 
+/// Turn input events into messages tagged with the window they happened in.
+///
+/// Escape and Alt+Left/Right only count when no widget used the key, the tab
+/// shortcuts and the mouse back/forward buttons also work while typing.
+fn window_event(event: Event, status: Status, window: window::Id) -> Option<Message> {
+    use iced::keyboard::Event::KeyPressed;
+    use iced::mouse::Button;
+    use iced::mouse::Event::ButtonPressed;
+
+    let message = match event {
+        Event::Mouse(ButtonPressed(Button::Back)) => Message::Back,
+        Event::Mouse(ButtonPressed(Button::Forward)) => Message::Forward,
+        Event::Keyboard(KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Escape) if status == Status::Ignored => Message::Escape,
+            Key::Named(Named::ArrowLeft) if modifiers.alt() && status == Status::Ignored => {
+                Message::Back
+            }
+            Key::Named(Named::ArrowRight) if modifiers.alt() && status == Status::Ignored => {
+                Message::Forward
+            }
+            _ => Message::MainPage(tab_shortcut(&key, modifiers)?),
+        },
+        _ => return None,
+    };
+
+    Some(Message::Window(window, Box::new(message)))
+}
+
+/// Font text is shown in unless a widget sets another, bundled with the application.
+pub const APP_FONT: Font = Font::with_name("Roboto");
+
 pub const TOKEN_INPUT_ID: &str = "token_input_box";
 pub const SEARCH_BAR_ID: &str = "search_box";
 
 impl App {
     pub fn new() -> (Self, Task<Message>) {
+        let (main_window, open) = window::open(window::Settings {
+            size: Size::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1),
+            ..Default::default()
+        });
+
         (
             Self {
                 page: Box::new(TokenPage::new()),
+                below: Vec::new(),
+                main_window,
                 window_size: (DEFAULT_SIZE.0, DEFAULT_SIZE.1),
+                article_windows: BTreeMap::new(),
             },
             // starting task
-            focus(TOKEN_INPUT_ID),
+            open.then(|_| focus(TOKEN_INPUT_ID)),
         )
     }
 
+    pub fn title(&self, window: window::Id) -> String {
+        match self.article_windows.get(&window) {
+            Some(article_window) => article_window.article.title.clone(),
+            None => "NewsAPI Demo".to_string(),
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         use Action::*;
 
-        if let Message::Resized(w, h) = message {
-            self.window_size = (w, h);
-            return iced::Task::none();
-        }
+        match message {
+            Message::Resized(window, w, h) => {
+                if window == self.main_window {
+                    self.window_size = (w, h);
+                }
+                return iced::Task::none();
+            }
+            Message::Window(window, message) => {
+                if window == self.main_window {
+                    return self.update(*message);
+                }
+
+                // article windows only react to escape
+                if let Message::Escape = *message {
+                    return window::close(window);
+                }
+                return iced::Task::none();
+            }
+            Message::WindowClosed(window) => {
+                if window == self.main_window {
+                    return iced::exit();
+                }
 
-        if let Message::OpenLink(link) = message {
-            if let Err(error) = open::that(&link) {
-                eprintln!("Error opening link: {error:?}");
+                self.article_windows.remove(&window);
+                return iced::Task::none();
+            }
+            Message::WindowImageLoaded(window, url, image) => {
+                if let Some(article_window) = self.article_windows.get_mut(&window) {
+                    article_window.image_loaded(url, image);
+                }
+                return iced::Task::none();
+            }
+            Message::OpenLink(link) => {
+                if let Err(error) = open::that(&link) {
+                    eprintln!("Error opening link: {error:?}");
+                }
+                return iced::Task::none();
             }
-            return iced::Task::none();
+            _ => (),
         }
 
         match self.page.update(message) {
             SwitchPage((page, task)) => {
                 self.page = page;
+                self.below.clear();
                 task
             }
+            PushPage((page, task)) => {
+                let previous = std::mem::replace(&mut self.page, page);
+                self.below.push(previous);
+                task
+            }
+            PopPage => {
+                if let Some(page) = self.below.pop() {
+                    self.page = page;
+                }
+                iced::Task::none()
+            }
+            PopOutArticle(article_window) => {
+                let (window, open) = window::open(window::Settings {
+                    size: Size::new(900.0, 800.0),
+                    ..Default::default()
+                });
+
+                // images still loading on the page are fetched again for the window,
+                // from the image cache once the page's requests have filled it
+                let images = article_window
+                    .missing_images()
+                    .into_iter()
+                    .map(|url| window_image_task(window, url, article_window.offline));
+                let images = iced::Task::batch(images);
+
+                self.article_windows.insert(window, *article_window);
+                iced::Task::batch([open.discard(), images])
+            }
             Task(task) => task,
             None => iced::Task::none(),
         }
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
-        self.page.view(self.window_size)
+    pub fn view(&self, window: window::Id) -> Element<'_, Message> {
+        match self.article_windows.get(&window) {
+            Some(article_window) => container(article_view(
+                &article_window.article,
+                article_window.image.as_ref(),
+                None,
+                LoadedContent {
+                    reader: article_window.reader.as_ref(),
+                    content_images: &article_window.content_images,
+                    content_blocks: &article_window.content_blocks,
+                    summary: &article_window.summary,
+                    summary_length: article_window.summary_length,
+                },
+            ))
+            .padding(10)
+            .center_x(Length::Fill)
+            .into(),
+            None => self.page.view(self.window_size),
+        }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
         Subscription::batch([
-            iced::window::resize_events()
-                .map(|(_id, size)| Message::Resized(size.width, size.height)),
-            iced::keyboard::on_key_press(|key, _mods| match key {
-                iced::keyboard::Key::Named(Named::Escape) => Some(Message::Escape),
-                _ => None,
-            }),
+            window::resize_events()
+                .map(|(window, size)| Message::Resized(window, size.width, size.height)),
+            window::close_events().map(Message::WindowClosed),
+            iced::event::listen_with(window_event),
+            self.page.subscription(),
         ])
     }
 }
+
+fn window_image_task(window: window::Id, url: String, offline: bool) -> Task<Message> {
+    Task::perform(
+        async move {
+            let image = match get_image_from_url(&url, offline).await {
+                Ok(bytes) => Some(Handle::from_bytes(bytes)),
+                Err(NewsAPIError::Offline) => None,
+                Err(e) => {
+                    eprintln!("Error getting image: {e:#?}");
+                    None
+                }
+            };
+            (url, image)
+        },
+        move |(url, image)| match image {
+            Some(image) => Message::WindowImageLoaded(window, url, image),
+            None => Message::NoOp,
+        },
+    )
+}
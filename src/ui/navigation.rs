@@ -0,0 +1,126 @@
+use crate::analysis::facets::Facet;
+use crate::newsapi::NewsAPIArticlesSuccess;
+use crate::ui::Message;
+use crate::ui::dashboard::GridFilter;
+use crate::ui::facets::FacetFilter;
+use crate::ui::style::BACK_ICON;
+use crate::ui::style::FORWARD_ICON;
+use crate::ui::style::button_style;
+use chrono::DateTime;
+use chrono::Utc;
+use iced::Element;
+use iced::Length;
+use iced::widget::button;
+use iced::widget::image::Handle;
+use iced::widget::row;
+use iced::widget::scrollable::AbsoluteOffset;
+use iced::widget::svg;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Most result sets kept to go back to, older ones are dropped.
+const MAX_SNAPSHOTS: usize = 20;
+
+/// A result list that was shown, kept so it can be returned to without a new request.
+#[derive(Default)]
+pub struct ResultSnapshot {
+    /// query the results were searched with
+    pub query: String,
+    /// title of results that weren't searched for with `query`, like archive browsing or alerts
+    pub label: Option<String>,
+    /// map from source id to enabled state at the time of the search
+    pub enabled_sources: HashMap<String, bool>,
+    /// the results, or the error that was shown in their place
+    pub search_result: Option<Result<NewsAPIArticlesSuccess, String>>,
+    /// images loaded so far by article index, missing ones are fetched again on return
+    pub images_loaded: Vec<Option<Handle>>,
+    /// when the results were served from the archive, the time they were fetched
+    pub cached_at: Option<DateTime<Utc>>,
+    /// scroll position of the result list
+    pub scroll: AbsoluteOffset,
+    /// for every article the earlier article it duplicates
+    pub duplicate_of: Vec<Option<usize>>,
+    /// names and key phrases mentioned in the results
+    pub facets: Vec<Facet>,
+    /// tone of every article
+    pub tones: Vec<f32>,
+    /// dashboard filter narrowing the results
+    pub grid_filter: Option<GridFilter>,
+    /// facets narrowing the results
+    pub facet_filters: Vec<FacetFilter>,
+    /// first article of every story whose other versions are shown
+    pub expanded_stories: HashSet<usize>,
+    /// articles selected for comparison
+    pub compare_selection: Vec<usize>,
+}
+
+/// Back and forward history of the result lists shown on the main page.
+#[derive(Default)]
+pub struct Navigation {
+    /// earlier result lists, most recent last
+    back: Vec<ResultSnapshot>,
+    /// result lists navigated back from, the next one last
+    forward: Vec<ResultSnapshot>,
+}
+
+impl Navigation {
+    /// Remember the shown results before new ones replace them, the forward history is dropped.
+    pub fn visit(&mut self, current: ResultSnapshot) {
+        self.back.push(current);
+        if self.back.len() > MAX_SNAPSHOTS {
+            self.back.remove(0);
+        }
+
+        self.forward.clear();
+    }
+
+    /// Step back, the shown results become the next forward entry.
+    ///
+    /// Returns:
+    /// - the results to show, or None when there are no earlier ones and `current` is dropped
+    pub fn back(&mut self, current: ResultSnapshot) -> Option<ResultSnapshot> {
+        let previous = self.back.pop()?;
+        self.forward.push(current);
+
+        Some(previous)
+    }
+
+    /// Step forward, the shown results become the previous back entry.
+    ///
+    /// Returns:
+    /// - the results to show, or None when nothing was navigated back from and `current` is dropped
+    pub fn forward(&mut self, current: ResultSnapshot) -> Option<ResultSnapshot> {
+        let next = self.forward.pop()?;
+        self.back.push(current);
+
+        Some(next)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+/// Back and forward buttons, disabled when there is nowhere to go.
+pub fn navigation_buttons(navigation: &Navigation) -> Element<'_, Message> {
+    row![
+        button(svg(svg::Handle::from_memory(BACK_ICON)))
+            .on_press_maybe(navigation.can_go_back().then_some(Message::Back))
+            .padding(10)
+            .width(48)
+            .height(Length::Fill)
+            .style(button_style),
+        button(svg(svg::Handle::from_memory(FORWARD_ICON)))
+            .on_press_maybe(navigation.can_go_forward().then_some(Message::Forward))
+            .padding(10)
+            .width(48)
+            .height(Length::Fill)
+            .style(button_style),
+    ]
+    .spacing(5)
+    .into()
+}
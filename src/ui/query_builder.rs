@@ -0,0 +1,494 @@
+use crate::newsapi::query::Query;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use crate::ui::style::text_input_style;
+use iced::Alignment;
+use iced::Border;
+use iced::Element;
+use iced::Length;
+use iced::Theme;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::checkbox;
+use iced::widget::pick_list;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text_input;
+use iced::widget::{column, container, row, text};
+use std::fmt;
+
+/// How a term or group is prefixed in the generated query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Modifier {
+    #[default]
+    Normal,
+    /// Prefixed with +
+    Required,
+    /// Prefixed with -
+    Excluded,
+}
+
+impl Modifier {
+    const ALL: [Modifier; 3] = [Modifier::Normal, Modifier::Required, Modifier::Excluded];
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Modifier::Normal => "Normal",
+            Modifier::Required => "Required (+)",
+            Modifier::Excluded => "Excluded (-)",
+        })
+    }
+}
+
+/// Operator joining the children of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Operator {
+    #[default]
+    And,
+    Or,
+}
+
+impl Operator {
+    const ALL: [Operator; 2] = [Operator::And, Operator::Or];
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::And => "All of (AND)",
+            Operator::Or => "Any of (OR)",
+        })
+    }
+}
+
+/// A single entry in the builder, either a word/phrase or a nested group.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuilderNode {
+    Term {
+        text: String,
+        /// quote the text so it has to appear as written
+        phrase: bool,
+        modifier: Modifier,
+    },
+    Group(BuilderGroup),
+}
+
+/// A group of terms joined by one operator, written in parentheses when nested.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BuilderGroup {
+    pub operator: Operator,
+    pub modifier: Modifier,
+    pub children: Vec<BuilderNode>,
+}
+
+#[derive(Debug, Clone)]
+pub enum QueryBuilderMessage {
+    /// Add an empty term to the group at the path
+    AddTerm(Vec<usize>),
+    /// Add an empty group to the group at the path
+    AddGroup(Vec<usize>),
+    /// Remove the node at the path
+    Remove(Vec<usize>),
+    TermInput(Vec<usize>, String),
+    PhraseToggled(Vec<usize>, bool),
+    ModifierSelected(Vec<usize>, Modifier),
+    OperatorSelected(Vec<usize>, Operator),
+    /// Replace the builder contents with the query in the search box
+    ImportFromSearch,
+}
+
+fn empty_term() -> BuilderNode {
+    BuilderNode::Term {
+        text: String::new(),
+        phrase: false,
+        modifier: Modifier::Normal,
+    }
+}
+
+fn prefix(modifier: Modifier) -> &'static str {
+    match modifier {
+        Modifier::Normal => "",
+        Modifier::Required => "+",
+        Modifier::Excluded => "-",
+    }
+}
+
+/// Whether the text parses back as a single term when written without quotes.
+///
+/// Anything else, like several words, parentheses, an operator or a leading + or -,
+/// is written as a phrase so it can't change the structure of the query.
+fn is_plain_word(text: &str) -> bool {
+    !text.starts_with(['+', '-'])
+        && !text.contains(|c: char| c.is_whitespace() || "()\"".contains(c))
+        && !matches!(text, "AND" | "OR" | "NOT")
+}
+
+impl BuilderGroup {
+    /// Build the builder tree for a parsed query.
+    ///
+    /// The root is always a plain group, a single term or a prefixed group becomes its only child.
+    pub fn from_query(query: &Query) -> Self {
+        match BuilderNode::from_query(query, Modifier::Normal) {
+            BuilderNode::Group(group) if group.modifier == Modifier::Normal => group,
+            node => BuilderGroup {
+                children: vec![node],
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Generate the query string in the NewsAPI syntax.
+    ///
+    /// Empty terms and groups are skipped, so a partially filled in builder still produces a valid query.
+    pub fn to_query_string(&self) -> String {
+        let separator = match self.operator {
+            Operator::And => " AND ",
+            Operator::Or => " OR ",
+        };
+
+        self.children
+            .iter()
+            .filter_map(BuilderNode::to_query_string)
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    fn group_mut(&mut self, path: &[usize]) -> Option<&mut BuilderGroup> {
+        match path.split_first() {
+            None => Some(self),
+            Some((i, rest)) => match self.children.get_mut(*i)? {
+                BuilderNode::Group(group) => group.group_mut(rest),
+                BuilderNode::Term { .. } => None,
+            },
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut BuilderNode> {
+        let (last, parent) = path.split_last()?;
+        self.group_mut(parent)?.children.get_mut(*last)
+    }
+
+    /// Apply an edit, the caller regenerates the query string afterwards.
+    ///
+    /// `ImportFromSearch` needs the search box contents and is handled by the main page.
+    pub fn update(&mut self, message: QueryBuilderMessage) {
+        use QueryBuilderMessage::*;
+
+        match message {
+            AddTerm(path) => {
+                if let Some(group) = self.group_mut(&path) {
+                    group.children.push(empty_term());
+                }
+            }
+            AddGroup(path) => {
+                if let Some(group) = self.group_mut(&path) {
+                    group.children.push(BuilderNode::Group(BuilderGroup {
+                        children: vec![empty_term()],
+                        ..Default::default()
+                    }));
+                }
+            }
+            Remove(path) => {
+                if let Some((last, parent)) = path.split_last()
+                    && let Some(group) = self.group_mut(parent)
+                    && *last < group.children.len()
+                {
+                    group.children.remove(*last);
+                }
+            }
+            TermInput(path, input) => {
+                if let Some(BuilderNode::Term { text, .. }) = self.node_mut(&path) {
+                    *text = input;
+                }
+            }
+            PhraseToggled(path, state) => {
+                if let Some(BuilderNode::Term { phrase, .. }) = self.node_mut(&path) {
+                    *phrase = state;
+                }
+            }
+            ModifierSelected(path, selected) => match self.node_mut(&path) {
+                Some(BuilderNode::Term { modifier, .. }) => *modifier = selected,
+                Some(BuilderNode::Group(group)) => group.modifier = selected,
+                None => (),
+            },
+            OperatorSelected(path, operator) => {
+                if let Some(group) = self.group_mut(&path) {
+                    group.operator = operator;
+                }
+            }
+            ImportFromSearch => (),
+        }
+    }
+}
+
+impl BuilderNode {
+    fn from_query(query: &Query, modifier: Modifier) -> Self {
+        match query {
+            Query::Term(text) => BuilderNode::Term {
+                text: text.clone(),
+                phrase: false,
+                modifier,
+            },
+            Query::Phrase(text) => BuilderNode::Term {
+                text: text.clone(),
+                phrase: true,
+                modifier,
+            },
+            Query::Required(inner) => BuilderNode::from_query(inner, Modifier::Required),
+            Query::Excluded(inner) | Query::Not(inner) => {
+                BuilderNode::from_query(inner, Modifier::Excluded)
+            }
+            Query::Group(inner) => match BuilderNode::from_query(inner, modifier) {
+                BuilderNode::Group(group) => BuilderNode::Group(group),
+                term => BuilderNode::Group(BuilderGroup {
+                    modifier,
+                    children: vec![term],
+                    ..Default::default()
+                }),
+            },
+            Query::And(parts) | Query::Or(parts) => BuilderNode::Group(BuilderGroup {
+                operator: match query {
+                    Query::Or(_) => Operator::Or,
+                    _ => Operator::And,
+                },
+                modifier,
+                children: parts
+                    .iter()
+                    .map(|p| BuilderNode::from_query(p, Modifier::Normal))
+                    .collect(),
+            }),
+        }
+    }
+
+    fn to_query_string(&self) -> Option<String> {
+        match self {
+            BuilderNode::Term {
+                text,
+                phrase,
+                modifier,
+            } => {
+                // quotes can't be escaped in the NewsAPI syntax, so they are dropped
+                let text = text.replace('"', "");
+                let text = text.trim();
+                match (text.is_empty(), *phrase || !is_plain_word(text)) {
+                    (true, _) => None,
+                    (false, true) => Some(format!("{}\"{text}\"", prefix(*modifier))),
+                    (false, false) => Some(format!("{}{text}", prefix(*modifier))),
+                }
+            }
+            BuilderNode::Group(group) => {
+                let inner = group.to_query_string();
+                (!inner.is_empty()).then(|| format!("{}({inner})", prefix(group.modifier)))
+            }
+        }
+    }
+}
+
+/// Render the editor for a single term.
+fn term_view<'a>(
+    path: Vec<usize>,
+    text_value: &'a str,
+    phrase: bool,
+    modifier: Modifier,
+) -> Element<'a, Message> {
+    use MainPageMessage::QueryBuilder as B;
+    use Message::MainPage as M;
+    use QueryBuilderMessage::*;
+
+    let (p1, p2, p3, p4) = (path.clone(), path.clone(), path.clone(), path.clone());
+
+    row![
+        pick_list(Modifier::ALL, Some(modifier), move |m| M(B(
+            ModifierSelected(p1.clone(), m)
+        ))),
+        text_input("Word or phrase", text_value)
+            .on_input(move |s| M(B(TermInput(p2.clone(), s))))
+            .style(text_input_style)
+            .width(Length::Fill),
+        checkbox("Exact phrase", phrase).on_toggle(move |s| M(B(PhraseToggled(p3.clone(), s)))),
+        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+            .width(36)
+            .style(close_button_style)
+            .on_press(M(B(Remove(p4)))),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Render a group with its operator, modifier, children and add buttons, nested groups recurse.
+fn group_view(group: &BuilderGroup, path: Vec<usize>) -> Element<'_, Message> {
+    use MainPageMessage::QueryBuilder as B;
+    use Message::MainPage as M;
+    use QueryBuilderMessage::*;
+
+    let is_root = path.is_empty();
+    let (p1, p2) = (path.clone(), path.clone());
+
+    let header = row![pick_list(Operator::ALL, Some(group.operator), move |o| M(
+        B(OperatorSelected(p1.clone(), o))
+    ))]
+    .push_maybe((!is_root).then(|| {
+        pick_list(Modifier::ALL, Some(group.modifier), move |m| {
+            M(B(ModifierSelected(p2.clone(), m)))
+        })
+    }))
+    .push(
+        button(text("+ Term"))
+            .style(button_style)
+            .on_press(M(B(AddTerm(path.clone())))),
+    )
+    .push(
+        button(text("+ Group"))
+            .style(button_style)
+            .on_press(M(B(AddGroup(path.clone())))),
+    )
+    .push_maybe((!is_root).then(|| {
+        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+            .width(36)
+            .style(close_button_style)
+            .on_press(M(B(Remove(path.clone()))))
+    }))
+    .spacing(5)
+    .align_y(Alignment::Center);
+
+    let children = Column::with_children(group.children.iter().enumerate().map(|(i, child)| {
+        let mut child_path = path.clone();
+        child_path.push(i);
+
+        match child {
+            BuilderNode::Term {
+                text,
+                phrase,
+                modifier,
+            } => term_view(child_path, text, *phrase, *modifier),
+            BuilderNode::Group(group) => group_view(group, child_path),
+        }
+    }))
+    .spacing(5)
+    .padding(iced::Padding::ZERO.left(20));
+
+    container(column![header, children].spacing(5))
+        .padding(5)
+        .style(|theme: &Theme| container::Style {
+            border: Border::default()
+                .color(theme.palette().primary)
+                .rounded(8)
+                .width(1),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Query builder panel shown under the top bar.
+///
+/// Parameters:
+/// - `builder`: root group of the builder
+///
+/// Returns:
+/// - an Element with the editable group tree, a button to load the query from the search box and a close button
+pub fn query_builder_panel(builder: &BuilderGroup) -> Element<'_, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    container(
+        column![
+            row![
+                text("Query builder").size(20).width(Length::Fill),
+                button(text("Load from search box"))
+                    .style(button_style)
+                    .on_press(M(QueryBuilder(QueryBuilderMessage::ImportFromSearch))),
+                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                    .width(36)
+                    .style(close_button_style)
+                    .on_press(M(ToggleQueryBuilder)),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+            scrollable(group_view(builder, Vec::new())).spacing(5),
+        ]
+        .spacing(5),
+    )
+    .padding([0, 15])
+    .max_height(320)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(text: &str, phrase: bool, modifier: Modifier) -> BuilderNode {
+        BuilderNode::Term {
+            text: text.to_string(),
+            phrase,
+            modifier,
+        }
+    }
+
+    fn round_trip(builder: &BuilderGroup) -> BuilderGroup {
+        BuilderGroup::from_query(&Query::parse(&builder.to_query_string()).unwrap())
+    }
+
+    #[test]
+    fn query_string_round_trips() {
+        let builder = BuilderGroup {
+            operator: Operator::And,
+            modifier: Modifier::Normal,
+            children: vec![
+                term("covid-19", false, Modifier::Required),
+                term("vaccine trial", true, Modifier::Normal),
+                BuilderNode::Group(BuilderGroup {
+                    operator: Operator::Or,
+                    modifier: Modifier::Excluded,
+                    children: vec![
+                        term("rumor", false, Modifier::Normal),
+                        term("AND (or NOT)", true, Modifier::Required),
+                    ],
+                }),
+            ],
+        };
+
+        assert_eq!(
+            builder.to_query_string(),
+            "+covid-19 AND \"vaccine trial\" AND -(rumor OR +\"AND (or NOT)\")"
+        );
+        assert_eq!(round_trip(&builder), builder);
+    }
+
+    #[test]
+    fn unquoted_text_that_is_not_a_word_becomes_a_phrase() {
+        let builder = BuilderGroup {
+            children: vec![
+                term("covid 19", false, Modifier::Required),
+                term("OR", false, Modifier::Normal),
+                term("(a", false, Modifier::Normal),
+                term("-b", false, Modifier::Normal),
+                term("say \"hi\"", false, Modifier::Normal),
+                term("\"\"", true, Modifier::Normal),
+            ],
+            ..Default::default()
+        };
+
+        let query = builder.to_query_string();
+        assert_eq!(
+            query,
+            "+\"covid 19\" AND \"OR\" AND \"(a\" AND \"-b\" AND \"say hi\""
+        );
+        assert_eq!(
+            Query::parse(&query).unwrap(),
+            Query::And(vec![
+                Query::Required(Box::new(Query::Phrase("covid 19".to_string()))),
+                Query::Phrase("OR".to_string()),
+                Query::Phrase("(a".to_string()),
+                Query::Phrase("-b".to_string()),
+                Query::Phrase("say hi".to_string()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::reader::ReaderArticle;
+use crate::reader::ReaderBlock;
+use crate::ui::Message;
+use crate::ui::main_page::error_element;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Length;
+use iced::Length::Shrink;
+use iced::Theme;
+use iced::widget::Column;
+use iced::widget::Image;
+use iced::widget::image::Handle;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{container, text};
+use std::collections::HashMap;
+
+/// Reader mode content of the open article.
+#[derive(Debug, Clone)]
+pub struct ReaderView {
+    /// url of the article the content belongs to
+    pub url: String,
+    /// the extracted content, None while it's being fetched
+    pub result: Option<Result<ReaderArticle, String>>,
+    /// loaded images of the content by url
+    pub images: HashMap<String, Handle>,
+}
+
+impl ReaderView {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            result: None,
+            images: HashMap::new(),
+        }
+    }
+}
+
+/// Font size of a heading, `level` 1 to 6.
+fn heading_size(level: u8) -> u16 {
+    match level {
+        1 => 36,
+        2 => 30,
+        3 => 26,
+        _ => 22,
+    }
+}
+
+/// Render a block of extracted article content.
+///
+/// Parameters:
+/// - `block`: the block to render
+/// - `images`: loaded images by url, images that aren't loaded yet are left out
+///
+/// Returns:
+/// - `Element<'a, Message>` for the block, or None for an image that isn't loaded
+fn reader_block<'a>(
+    block: &'a ReaderBlock,
+    images: &HashMap<String, Handle>,
+) -> Option<Element<'a, Message>> {
+    let element = match block {
+        ReaderBlock::Heading(level, heading) => text(heading)
+            .size(heading_size(*level))
+            .shaping(Advanced)
+            .into(),
+        ReaderBlock::Paragraph(paragraph) => text(paragraph).size(20).shaping(Advanced).into(),
+        ReaderBlock::ListItem(item) => text(format!("• {item}")).size(20).shaping(Advanced).into(),
+        ReaderBlock::Quote(quote) => container(text(quote).size(20).shaping(Advanced))
+            .padding([5, 15])
+            .style(|theme: &Theme| container::Style {
+                background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.04))),
+                border: Border::default()
+                    .color(theme.palette().primary)
+                    .width(1)
+                    .rounded(5),
+                ..Default::default()
+            })
+            .into(),
+        ReaderBlock::Preformatted(code) => {
+            container(text(code).size(16).font(iced::Font::MONOSPACE))
+                .padding(10)
+                .width(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.06))),
+                    border: Border::default().rounded(5),
+                    ..Default::default()
+                })
+                .into()
+        }
+        ReaderBlock::Image { url, caption } => Column::with_capacity(2)
+            .push(container(Image::new(images.get(url)?.clone()).height(Shrink)).max_height(800))
+            .push_maybe(
+                caption
+                    .as_ref()
+                    .map(|caption| text(caption).size(16).shaping(Advanced)),
+            )
+            .spacing(5)
+            .into(),
+    };
+
+    Some(element)
+}
+
+/// Render reader mode content in place of the article's description and content.
+///
+/// Parameters:
+/// - `reader`: the reader mode state of the article
+///
+/// Returns:
+/// - `Element<'a, Message>` with the byline and article body, a loading notice while it's fetched,
+///   or an error element when the page couldn't be fetched or had no article text
+pub fn reader_content(reader: &ReaderView) -> Element<'_, Message> {
+    match &reader.result {
+        None => container(text("Loading the full article...").size(20))
+            .padding(20)
+            .center_x(Length::Fill)
+            .into(),
+        Some(Err(error)) => error_element(error),
+        Some(Ok(article)) => Column::with_capacity(article.blocks.len() + 1)
+            .push_maybe(
+                article
+                    .byline
+                    .as_ref()
+                    .map(|byline| text(byline).size(18).shaping(Advanced)),
+            )
+            .extend(
+                article
+                    .blocks
+                    .iter()
+                    .filter_map(|block| reader_block(block, &reader.images)),
+            )
+            .spacing(12)
+            .into(),
+    }
+}
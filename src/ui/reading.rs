@@ -0,0 +1,220 @@
+use crate::newsapi::NewsAPIArticlesSuccess;
+use crate::newsapi::article::Article;
+use crate::ui::Message;
+use crate::ui::article::LoadedContent;
+use crate::ui::article::RESULTS_SCROLL_ID;
+use crate::ui::article::article_view;
+use crate::ui::compare::compare_checkbox;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::main_page::error_element;
+use crate::ui::stories::StoryEntry;
+use crate::ui::stories::coverage_strip;
+use crate::ui::style::button_style;
+use crate::ui::tone::tone_indicator;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use iced::Background;
+use iced::Color;
+use iced::Element;
+use iced::Length;
+use iced::Theme;
+use iced::color;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::image::Handle;
+use iced::widget::pane_grid;
+use iced::widget::pane_grid::Axis;
+use iced::widget::pane_grid::Configuration;
+use iced::widget::scrollable;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{column, container, row, text};
+
+/// Width of the result area from which the split reading layout replaces the card grid.
+pub const SPLIT_LAYOUT_MIN_WIDTH: f32 = 1100.0;
+
+/// Setting key the split ratio is persisted under.
+pub const SPLIT_RATIO_KEY: &str = "split_ratio";
+
+/// Share of the width the article list gets until the split is resized.
+pub const DEFAULT_SPLIT_RATIO: f32 = 0.35;
+
+/// Narrowest share of the width either pane can be resized to.
+const MIN_SPLIT_RATIO: f32 = 0.15;
+
+/// A pane of the split reading layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingPane {
+    /// Compact list of the results
+    List,
+    /// The selected article
+    Article,
+}
+
+/// Create the panes of the split reading layout, the list on the left taking `ratio` of the width.
+pub fn reading_panes(ratio: f32) -> pane_grid::State<ReadingPane> {
+    pane_grid::State::with_configuration(Configuration::Split {
+        axis: Axis::Vertical,
+        ratio: clamp_ratio(ratio),
+        a: Box::new(Configuration::Pane(ReadingPane::List)),
+        b: Box::new(Configuration::Pane(ReadingPane::Article)),
+    })
+}
+
+/// Keep both panes wide enough to be usable.
+pub fn clamp_ratio(ratio: f32) -> f32 {
+    ratio.clamp(MIN_SPLIT_RATIO, 1.0 - MIN_SPLIT_RATIO)
+}
+
+/// Build a row of the compact article list: title, source, publishing time and tone.
+///
+/// Stories covered by several articles get a strip to expand them, their other versions are indented.
+fn list_entry<'a>(
+    entry: StoryEntry,
+    article: &'a Article,
+    active: bool,
+    compare_selection: &[usize],
+) -> Element<'a, Message> {
+    let index = entry.index;
+    let published = article
+        .published_at
+        .as_ref()
+        .and_then(|t| t.parse::<DateTime<Utc>>().ok())
+        .map(|dt| {
+            DateTime::<Local>::from(dt)
+                .format("%b %d, %H:%M")
+                .to_string()
+        });
+
+    let details = match (&article.source.name, published) {
+        (Some(source), Some(published)) => format!("{source} · {published}"),
+        (Some(source), None) => source.clone(),
+        (None, Some(published)) => published,
+        (None, None) => String::new(),
+    };
+
+    let entry_button = button(
+        column![
+            row![
+                text(&article.title)
+                    .size(16)
+                    .shaping(Advanced)
+                    .width(Length::Fill),
+                compare_checkbox(index, compare_selection),
+            ],
+            row![
+                text(details)
+                    .size(13)
+                    .shaping(Advanced)
+                    .color(color!(0x555555))
+                    .width(Length::Fill),
+                tone_indicator(entry.tone),
+            ],
+        ]
+        .push_maybe(
+            entry
+                .coverage
+                .map(|coverage| coverage_strip(index, coverage)),
+        )
+        .spacing(3),
+    )
+    .on_press(Message::MainPage(MainPageMessage::ActiveArticle(Some(
+        index,
+    ))))
+    .width(Length::Fill)
+    .style(move |theme: &Theme, status| {
+        let style = button_style(theme, status);
+
+        if active {
+            button::Style {
+                background: Some(Background::Color(Color::from_rgb(0.88, 0.88, 1.0))),
+                ..style
+            }
+        } else {
+            style
+        }
+    });
+
+    container(entry_button)
+        .padding(iced::Padding::ZERO.left(if entry.grouped { 20.0 } else { 0.0 }))
+        .into()
+}
+
+/// Split reading layout: a compact list of the results next to the selected article, in resizable panes.
+///
+/// Parameters:
+/// - `panes`: pane state, holding the split ratio
+/// - `search_result`: the results to list. If None, returns None.
+/// - `stories`: the articles to list in order, one entry per story unless it's expanded
+/// - `active_article`: index of the article shown in the right pane, a hint is shown when None
+/// - `images_loaded`: image handles by article index
+/// - `compare_selection`: indices of the articles selected for comparison
+/// - `loaded`: reader mode content and content images loaded for the selected article
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with the panes, or an error element when the search failed.
+/// - `None` when there are no results.
+pub fn split_view<'a>(
+    panes: &'a pane_grid::State<ReadingPane>,
+    search_result: Option<&'a Result<NewsAPIArticlesSuccess, String>>,
+    stories: Vec<StoryEntry>,
+    active_article: Option<usize>,
+    images_loaded: &'a [Option<Handle>],
+    compare_selection: &'a [usize],
+    loaded: LoadedContent<'a>,
+) -> Option<Element<'a, Message>> {
+    let data = match search_result? {
+        Ok(data) => data,
+        Err(error) => return Some(error_element(error)),
+    };
+
+    Some(
+        pane_grid(panes, move |_pane, kind, _maximized| {
+            pane_grid::Content::new(match kind {
+                ReadingPane::List => Into::<Element<'a, Message>>::into(
+                    scrollable(
+                        Column::with_children(stories.iter().filter_map(|entry| {
+                            let article = data.articles.get(entry.index)?;
+                            Some(list_entry(
+                                *entry,
+                                article,
+                                active_article == Some(entry.index),
+                                compare_selection,
+                            ))
+                        }))
+                        .spacing(5)
+                        .padding(5),
+                    )
+                    .id(scrollable::Id::new(RESULTS_SCROLL_ID))
+                    .on_scroll(|viewport| {
+                        Message::MainPage(MainPageMessage::ResultsScrolled(
+                            viewport.absolute_offset(),
+                        ))
+                    })
+                    .spacing(5)
+                    .height(Length::Fill),
+                ),
+                ReadingPane::Article => match active_article
+                    .and_then(|i| Some((i, data.articles.get(i)?, images_loaded.get(i)?)))
+                {
+                    Some((i, article, image)) => {
+                        container(article_view(article, image.as_ref(), Some(i), loaded))
+                            .padding(5)
+                            .center_x(Length::Fill)
+                            .into()
+                    }
+                    None => container(text("Select an article to read it here").size(20))
+                        .center(Length::Fill)
+                        .into(),
+                },
+            })
+        })
+        .on_resize(10, |event| {
+            Message::MainPage(MainPageMessage::SplitResized(event))
+        })
+        .spacing(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into(),
+    )
+}
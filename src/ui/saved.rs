@@ -0,0 +1,218 @@
+use crate::archive::saved::SavedSearch;
+use crate::newsapi::quota::DAILY_REQUEST_LIMIT;
+use crate::newsapi::quota::QuotaUsage;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use crate::ui::style::text_input_style;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Element;
+use iced::Length;
+use iced::Theme;
+use iced::color;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::horizontal_rule;
+use iced::widget::pick_list;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::text_input;
+use iced::widget::{column, container, row, text};
+use std::fmt;
+
+/// Setting key the refresh interval is persisted under.
+pub const REFRESH_INTERVAL_KEY: &str = "saved_search_refresh_minutes";
+
+/// How often saved searches are refreshed in the background, in minutes, 0 disables refreshing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshInterval(pub u64);
+
+impl RefreshInterval {
+    pub const ALL: [RefreshInterval; 6] = [
+        RefreshInterval(0),
+        RefreshInterval(15),
+        RefreshInterval(30),
+        RefreshInterval(60),
+        RefreshInterval(180),
+        RefreshInterval(720),
+    ];
+
+    pub const DEFAULT: RefreshInterval = RefreshInterval(60);
+}
+
+impl fmt::Display for RefreshInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "Never"),
+            m if m % 60 == 0 && m > 60 => write!(f, "Every {} hours", m / 60),
+            60 => write!(f, "Every hour"),
+            m => write!(f, "Every {m} minutes"),
+        }
+    }
+}
+
+/// Setting key the background refresh budget is persisted under.
+pub const REFRESH_BUDGET_KEY: &str = "saved_search_refresh_budget_percent";
+
+/// Share of the daily request quota background refreshes may use, in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshBudget(pub u32);
+
+impl RefreshBudget {
+    pub const ALL: [RefreshBudget; 4] = [
+        RefreshBudget(10),
+        RefreshBudget(25),
+        RefreshBudget(50),
+        RefreshBudget(75),
+    ];
+
+    pub const DEFAULT: RefreshBudget = RefreshBudget(25);
+
+    /// Number of requests the share amounts to.
+    pub fn requests(&self) -> u32 {
+        DAILY_REQUEST_LIMIT * self.0 / 100
+    }
+}
+
+impl fmt::Display for RefreshBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% of requests", self.0)
+    }
+}
+
+/// Small pill showing a count of new articles.
+pub fn badge<'a>(count: usize) -> Element<'a, Message> {
+    container(text(count).size(14).color(color!(0xffffff)))
+        .padding([1, 6])
+        .style(|_theme: &Theme| container::Style {
+            background: Some(Background::Color(color!(0xd93025))),
+            border: Border::default().rounded(8),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Build the sidebar entry for a saved search: its name, a badge with new articles and a delete button.
+fn saved_entry(saved: &SavedSearch, active: bool) -> Element<'_, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let label = match saved.query.as_str() {
+        "" => "Top headlines",
+        q => q,
+    };
+
+    row![
+        button(
+            column![
+                row![
+                    text(&saved.name)
+                        .size(18)
+                        .shaping(Advanced)
+                        .width(Length::Fill)
+                ]
+                .push_maybe((saved.unseen > 0).then(|| badge(saved.unseen)))
+                .align_y(Alignment::Center),
+                text(label).size(13).shaping(Advanced),
+            ]
+            .spacing(2),
+        )
+        .on_press_maybe((!active).then_some(M(SavedSearchSelected(saved.id))))
+        .style(button_style)
+        .width(Length::Fill),
+        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+            .width(32)
+            .style(close_button_style)
+            .on_press(M(SavedSearchDeleted(saved.id))),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Sidebar listing the saved searches.
+///
+/// Parameters:
+/// - `saved`: the saved searches
+/// - `name`: content of the name input used when saving the current search
+/// - `interval`: current background refresh interval
+/// - `budget`: share of the daily quota background refreshes may use
+/// - `quota`: today's request usage
+/// - `active`: id of the saved search currently shown, if any
+///
+/// Returns:
+/// - an Element with a save form, the refresh interval and budget pickers and one entry per saved search
+pub fn saved_sidebar<'a>(
+    saved: &'a [SavedSearch],
+    name: &'a str,
+    interval: RefreshInterval,
+    budget: RefreshBudget,
+    quota: &QuotaUsage,
+    active: Option<i64>,
+) -> Element<'a, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let list: Element<'a, Message> = if saved.is_empty() {
+        text("Save a search to follow it here.").into()
+    } else {
+        scrollable(
+            Column::with_children(saved.iter().map(|s| saved_entry(s, active == Some(s.id))))
+                .spacing(5)
+                .padding(iced::Padding::ZERO.right(10)),
+        )
+        .height(Length::Fill)
+        .into()
+    };
+
+    container(
+        column![
+            text("Saved searches").size(20),
+            row![
+                text_input("Name", name)
+                    .on_input(|s| M(SavedNameOnInput(s)))
+                    .on_submit(M(SaveCurrentSearch))
+                    .style(text_input_style)
+                    .width(Length::Fill),
+                button(text("Save"))
+                    .style(button_style)
+                    .on_press(M(SaveCurrentSearch)),
+            ]
+            .spacing(5),
+            row![
+                text("Refresh").width(Length::Fill),
+                pick_list(RefreshInterval::ALL, Some(interval), |i| M(
+                    RefreshIntervalSelected(i)
+                )),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+            row![
+                text("Budget").width(Length::Fill),
+                pick_list(RefreshBudget::ALL, Some(budget), |b| M(
+                    RefreshBudgetSelected(b)
+                )),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+            text(format!(
+                "{} of {} background requests used today",
+                quota.background,
+                budget.requests()
+            ))
+            .size(13),
+            horizontal_rule(6),
+            list,
+        ]
+        .spacing(5),
+    )
+    .padding(iced::Padding::ZERO.left(15).bottom(5))
+    .width(280)
+    .height(Length::Fill)
+    .into()
+}
@@ -1,4 +1,7 @@
+use crate::newsapi::EverythingParams;
+use crate::newsapi::LANGUAGES;
 use crate::newsapi::NewsAPISourcesSuccess;
+use crate::newsapi::SortBy;
 use crate::newsapi::source::Source;
 use crate::ui::Message;
 use crate::ui::main_page::MainPageMessage;
@@ -16,7 +19,9 @@ use iced::color;
 use iced::widget::Column;
 use iced::widget::Row;
 use iced::widget::button;
+use iced::widget::checkbox;
 use iced::widget::horizontal_rule;
+use iced::widget::pick_list;
 use iced::widget::scrollable;
 use iced::widget::svg;
 use iced::widget::text::Shaping::Advanced;
@@ -25,6 +30,58 @@ use iced::widget::{column, container, mouse_area, row, text, toggler, tooltip};
 use iced::{Color, Element, Length};
 use std::collections::HashMap;
 use std::f32::consts::FRAC_PI_4;
+use std::fmt;
+
+/// Language option for the everything endpoint, None means all languages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language(pub Option<String>);
+
+impl Language {
+    /// Every selectable option, all languages first.
+    fn options() -> Vec<Language> {
+        std::iter::once(Language(None))
+            .chain(LANGUAGES.iter().map(|l| Language(Some(l.to_string()))))
+            .collect()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            None => write!(f, "All languages"),
+            Some(code) => write!(f, "{code}"),
+        }
+    }
+}
+
+/// Row of pickers for the everything endpoint parameters.
+///
+/// Parameters:
+/// - `params`: current sort order and language
+/// - `live_search`: whether searches run while typing
+///
+/// Returns:
+/// - an Element with a sort order and a language picker, sending `SortBySelected` and `LanguageSelected`,
+///   and a search-as-you-type checkbox sending `LiveSearchToggled`
+fn params_row(params: &EverythingParams, live_search: bool) -> Element<'_, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    row![
+        text("Search results"),
+        pick_list(SortBy::ALL, Some(params.sort_by), |s| M(SortBySelected(s))),
+        pick_list(
+            Language::options(),
+            Some(Language(params.language.clone())),
+            |l| M(LanguageSelected(l))
+        ),
+        checkbox("Search as you type", live_search).on_toggle(|b| M(LiveSearchToggled(b))),
+    ]
+    .spacing(10)
+    .padding(5)
+    .align_y(Alignment::Center)
+    .into()
+}
 
 /// Build a UI element for a Source: displays name, URL and a description tooltip, with a toggle control.
 ///
@@ -83,6 +140,8 @@ pub fn source_toggle(source: &Source, is_enabled: bool) -> Element<'_, Message>
 /// - `enabled_sources`: map of source.id -> enabled state (used to set each toggle; missing keys are treated as false).
 /// - `source_chunks`: number of source items per row when laying out the list.
 /// - `source_filter`: filter text applied to source name/description/id; the code matches the filter against a lowercased haystack, so provide a lowercased filter for expected results.
+/// - `params`: everything endpoint parameters, shown as pickers above the source list.
+/// - `live_search`: state of the search-as-you-type option, shown next to the pickers.
 ///
 /// Returns:
 /// - `Some(Element<'a, Message>)` when `source_page` is true and `source_data` is Some(...): a page with a filter input and toggles (or an error text).
@@ -93,6 +152,8 @@ pub fn source_page<'a>(
     enabled_sources: &'a HashMap<String, bool>,
     source_chunks: usize,
     source_filter: &'a str,
+    params: &'a EverythingParams,
+    live_search: bool,
 ) -> Option<Element<'a, Message>> {
     use MainPageMessage::*;
     use Message::MainPage as M;
@@ -126,6 +187,7 @@ pub fn source_page<'a>(
                             .height(48)
                             .padding(5)
                             .spacing(5),
+                            params_row(params, live_search),
                             horizontal_rule(6),
                             {
                                 // basic filter, this does mean only lowercase works
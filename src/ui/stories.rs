@@ -0,0 +1,149 @@
+use crate::analysis::cluster::source_count;
+use crate::newsapi::article::Article;
+use crate::newsapi::dedup::DuplicateMode;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::button_style;
+use iced::Alignment;
+use iced::Element;
+use iced::Length;
+use iced::widget::pick_list;
+use iced::widget::{button, container, row, text};
+use std::collections::HashSet;
+
+/// How widely the story led by an article is covered.
+#[derive(Debug, Clone, Copy)]
+pub struct Coverage {
+    /// number of articles about the story
+    pub articles: usize,
+    /// number of different sources the articles come from
+    pub sources: usize,
+    /// the other articles about the story are shown
+    pub expanded: bool,
+}
+
+/// An article shown in the results, in the order they're shown.
+#[derive(Debug, Clone, Copy)]
+pub struct StoryEntry {
+    /// index into the shown results
+    pub index: usize,
+    /// set when the article leads a story covered by several articles
+    pub coverage: Option<Coverage>,
+    /// the article is one of the other versions of an expanded story
+    pub grouped: bool,
+    /// tone of the article, see `analysis::sentiment`
+    pub tone: f32,
+}
+
+/// List the articles to show, one per story unless the story is expanded.
+///
+/// Parameters:
+/// - `articles`: the shown results
+/// - `clusters`: the results grouped into stories, see `analysis::cluster::story_clusters`
+/// - `expanded`: index of the first article of every expanded story
+/// - `tones`: tone of every shown article
+pub fn story_entries(
+    articles: &[Article],
+    clusters: &[Vec<usize>],
+    expanded: &HashSet<usize>,
+    tones: &[f32],
+) -> Vec<StoryEntry> {
+    let mut entries = Vec::with_capacity(clusters.len());
+
+    for cluster in clusters {
+        let Some((&lead, others)) = cluster.split_first() else {
+            continue;
+        };
+        let expanded = expanded.contains(&lead);
+
+        entries.push(StoryEntry {
+            index: lead,
+            coverage: (!others.is_empty()).then(|| Coverage {
+                articles: cluster.len(),
+                sources: source_count(articles, cluster),
+                expanded,
+            }),
+            grouped: false,
+            tone: tones.get(lead).copied().unwrap_or_default(),
+        });
+
+        if expanded {
+            entries.extend(others.iter().map(|&index| StoryEntry {
+                index,
+                coverage: None,
+                grouped: true,
+                tone: tones.get(index).copied().unwrap_or_default(),
+            }));
+        }
+    }
+
+    entries
+}
+
+/// Button under the leading article of a story, showing how many sources cover it.
+///
+/// Parameters:
+/// - `index`: index of the leading article
+/// - `coverage`: how widely the story is covered
+///
+/// Returns:
+/// - an Element that expands or collapses the other versions of the story when pressed
+pub fn coverage_strip(index: usize, coverage: Coverage) -> Element<'static, Message> {
+    let label = if coverage.sources > 1 {
+        format!("Covered by {} sources", coverage.sources)
+    } else {
+        format!("{} related articles", coverage.articles)
+    };
+    let action = if coverage.expanded {
+        "hide"
+    } else {
+        "show all"
+    };
+
+    button(text(format!("{label} · {action}")).size(14))
+        .padding([3, 8])
+        .width(Length::Fill)
+        .style(button_style)
+        .on_press(Message::MainPage(MainPageMessage::ToggleStory(index)))
+        .into()
+}
+
+/// Bar above the results telling how many duplicates were found.
+///
+/// Parameters:
+/// - `duplicates`: number of articles duplicating another shown article
+/// - `mode`: how duplicates are shown
+///
+/// Returns:
+/// - `Some(Element)` with the count and a choice of how to show duplicates, None without duplicates
+pub fn duplicates_notice(
+    duplicates: usize,
+    mode: DuplicateMode,
+) -> Option<Element<'static, Message>> {
+    if duplicates == 0 {
+        return None;
+    }
+
+    let plural = if duplicates == 1 { "" } else { "s" };
+    let label = match mode {
+        DuplicateMode::Collapse => {
+            format!("{duplicates} duplicate{plural} grouped with the original")
+        }
+        DuplicateMode::Hide => format!("{duplicates} duplicate{plural} hidden"),
+    };
+
+    Some(
+        container(
+            row![
+                text(label).size(16).width(Length::Fill),
+                pick_list(DuplicateMode::ALL, Some(mode), |m| Message::MainPage(
+                    MainPageMessage::DuplicateModeSelected(m)
+                )),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .padding([5, 15])
+        .into(),
+    )
+}
@@ -26,6 +26,12 @@ pub const CLOSE_ICON: &[u8] =
 pub const LIST_ICON: &[u8] = include_bytes!(
     "../../assets/material-icons/format_list_bulleted_24dp_1F1F1F_FILL0_wght400_GRAD0_opsz24.svg"
 );
+pub const BACK_ICON: &[u8] = include_bytes!(
+    "../../assets/material-icons/arrow_back_24dp_1F1F1F_FILL0_wght400_GRAD0_opsz24.svg"
+);
+pub const FORWARD_ICON: &[u8] = include_bytes!(
+    "../../assets/material-icons/arrow_forward_24dp_1F1F1F_FILL0_wght400_GRAD0_opsz24.svg"
+);
 pub const NO_IMAGE_ICON: &[u8] = include_bytes!(
     "../../assets/material-icons/hide_image_24dp_E3E3E3_FILL0_wght400_GRAD0_opsz24.svg"
 );
@@ -0,0 +1,120 @@
+use crate::archive::history::HistoryEntry;
+use crate::archive::saved::SavedSearch;
+use crate::newsapi::source::Source;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::button_style;
+use iced::Background;
+use iced::Border;
+use iced::Element;
+use iced::Length;
+use iced::Theme;
+use iced::color;
+use iced::widget::Column;
+use iced::widget::button;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{container, row, text};
+use std::collections::HashSet;
+
+/// Most suggestions shown at once.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// An entry in the search bar suggestions dropdown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Suggestion {
+    /// A previously run query
+    History(String),
+    /// A saved search, by id and name
+    Saved(i64, String),
+    /// A source to filter by, by id and name
+    Source(String, String),
+}
+
+/// Collect suggestions for the text in the search bar.
+///
+/// Matching is a case-insensitive substring match, history comes first, then saved searches and sources.
+///
+/// Parameters:
+/// - `input`: text in the search bar, no suggestions are made when it's blank
+/// - `history`: search history, most recent first, each query is suggested once
+/// - `saved`: saved searches, matched by name and query
+/// - `sources`: available sources, matched by name and id
+pub fn suggestions(
+    input: &str,
+    history: &[HistoryEntry],
+    saved: &[SavedSearch],
+    sources: &[Source],
+) -> Vec<Suggestion> {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let matches = |s: &str| s.to_lowercase().contains(&needle);
+
+    // the same query may be in the history with several source sets
+    let mut seen = HashSet::new();
+    let history = history
+        .iter()
+        .map(|entry| &entry.query)
+        .filter(|q| matches(q) && q.trim() != input.trim() && seen.insert(q.as_str()))
+        .map(|q| Suggestion::History(q.clone()));
+
+    let saved = saved
+        .iter()
+        .filter(|s| matches(&s.name) || matches(&s.query))
+        .map(|s| Suggestion::Saved(s.id, s.name.clone()));
+
+    let sources = sources
+        .iter()
+        .filter(|s| matches(&s.name) || matches(&s.id))
+        .map(|s| Suggestion::Source(s.id.clone(), s.name.clone()));
+
+    history
+        .chain(saved)
+        .chain(sources)
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// Build a dropdown entry, labelled with what kind of suggestion it is.
+fn suggestion_entry(suggestion: &Suggestion) -> Element<'_, Message> {
+    let (kind, label) = match suggestion {
+        Suggestion::History(query) => ("Recent", query.as_str()),
+        Suggestion::Saved(_, name) => ("Saved", name.as_str()),
+        Suggestion::Source(_, name) => ("Source", name.as_str()),
+    };
+
+    button(
+        row![
+            text(label).size(18).shaping(Advanced).width(Length::Fill),
+            text(kind).size(14).color(color!(0x777777)),
+        ]
+        .spacing(10),
+    )
+    .on_press(Message::MainPage(MainPageMessage::SuggestionSelected(
+        suggestion.clone(),
+    )))
+    .style(button_style)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Dropdown listing suggestions under the search bar.
+///
+/// Returns:
+/// - an Element with one entry per suggestion
+pub fn suggestions_dropdown(suggestions: &[Suggestion]) -> Element<'_, Message> {
+    container(Column::with_children(suggestions.iter().map(suggestion_entry)).spacing(2))
+        .padding(5)
+        .max_width(600)
+        .style(|theme: &Theme| container::Style {
+            background: Some(Background::Color(color!(0xffffff))),
+            border: Border::default()
+                .color(theme.palette().primary)
+                .rounded(8)
+                .width(1),
+            ..Default::default()
+        })
+        .into()
+}
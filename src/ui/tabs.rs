@@ -0,0 +1,174 @@
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::navigation::Navigation;
+use crate::ui::navigation::ResultSnapshot;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use iced::Alignment;
+use iced::Background;
+use iced::Color;
+use iced::Element;
+use iced::Length;
+use iced::Theme;
+use iced::widget::Row;
+use iced::widget::button;
+use iced::widget::mouse_area;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::{row, text};
+use std::collections::HashMap;
+
+/// Height of the tab strip above the top bar.
+pub const TAB_STRIP_HEIGHT: f32 = 40.0;
+
+/// Longest tab title in characters, longer queries are cut off.
+const MAX_TITLE_LENGTH: usize = 24;
+
+/// State of a tab in the background. The active tab's state lives in the main page
+/// and is moved into a Tab when another tab is selected.
+#[derive(Default)]
+pub struct Tab {
+    /// stable id, tabs move around when reordered or closed
+    pub id: u64,
+    /// content of the search box
+    pub search_query: String,
+    /// map from source id to enabled state
+    pub enabled_sources: HashMap<String, bool>,
+    /// the shown results, with the query and sources they were searched with
+    pub results: ResultSnapshot,
+    /// article open on the article page
+    pub active_article: Option<usize>,
+    /// saved search whose results are shown
+    pub viewing_saved: Option<i64>,
+    /// the articles selected for comparison are shown side by side
+    pub compare_page: bool,
+    /// earlier and later result lists
+    pub navigation: Navigation,
+    /// generation of the latest request for results, responses are routed to the tab by it
+    pub generation: u64,
+    /// aborts the search in flight
+    pub search_handle: Option<iced::task::Handle>,
+    /// aborts the image downloads of the shown results
+    pub image_tasks: Option<iced::task::Handle>,
+    /// query and sources of the request for results in flight
+    pub requested_search: (String, HashMap<String, bool>),
+    /// title of the results in flight when they aren't searched for with the query
+    pub requested_label: Option<String>,
+}
+
+/// Title of a tab, from the query its results were searched with.
+///
+/// Parameters:
+/// - `query`: query of the shown results
+/// - `has_results`: whether anything was searched in the tab yet
+pub fn tab_title(query: &str, has_results: bool) -> String {
+    match query.trim() {
+        "" if !has_results => "New tab".to_string(),
+        "" => "Top headlines".to_string(),
+        q if q.chars().count() > MAX_TITLE_LENGTH => {
+            format!("{}…", q.chars().take(MAX_TITLE_LENGTH).collect::<String>())
+        }
+        q => q.to_string(),
+    }
+}
+
+/// Button style of a tab, the active one is highlighted.
+fn tab_style(theme: &Theme, status: button::Status, active: bool) -> button::Style {
+    let style = button_style(theme, status);
+
+    if active {
+        button::Style {
+            background: Some(Background::Color(Color::from_rgb(0.88, 0.88, 1.0))),
+            ..style
+        }
+    } else {
+        style
+    }
+}
+
+/// Build a tab: its title and a close button. Middle clicking closes the tab too.
+fn tab_entry(id: u64, title: String, active: bool, closable: bool) -> Element<'static, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    mouse_area(
+        button(
+            row![text(title).size(16).shaping(Advanced)]
+                .push_maybe(closable.then(|| {
+                    button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                        .on_press(M(CloseTab(id)))
+                        .padding(2)
+                        .width(22)
+                        .height(22)
+                        .style(close_button_style)
+                }))
+                .spacing(8)
+                .align_y(Alignment::Center),
+        )
+        .on_press(M(SelectTab(id)))
+        .padding([2, 10])
+        .height(Length::Fill)
+        .style(move |theme, status| tab_style(theme, status, active)),
+    )
+    .on_middle_press(M(CloseTab(id)))
+    .into()
+}
+
+/// Tab strip shown above the top bar.
+///
+/// Parameters:
+/// - `tabs`: id and title of every tab, in order
+/// - `active`: index of the active tab
+///
+/// Returns:
+/// - an Element with one entry per tab, a new tab button and buttons moving the active tab left or right
+pub fn tab_strip(tabs: Vec<(u64, String)>, active: usize) -> Element<'static, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let closable = tabs.len() > 1;
+    let active_id = tabs.get(active).map(|(id, _)| *id);
+    let last = tabs.len().saturating_sub(1);
+
+    let entries = tabs
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, title))| tab_entry(id, title, i == active, closable));
+
+    Row::with_children(entries)
+        .push(
+            button(text("+").size(18))
+                .on_press(M(NewTab))
+                .padding([2, 10])
+                .height(Length::Fill)
+                .style(button_style),
+        )
+        .push(iced::widget::horizontal_space())
+        .push(
+            button(text("‹").size(18).shaping(Advanced))
+                .on_press_maybe(
+                    active_id
+                        .filter(|_| active > 0)
+                        .map(|id| M(MoveTab(id, false))),
+                )
+                .padding([2, 10])
+                .height(Length::Fill)
+                .style(button_style),
+        )
+        .push(
+            button(text("›").size(18).shaping(Advanced))
+                .on_press_maybe(
+                    active_id
+                        .filter(|_| active < last)
+                        .map(|id| M(MoveTab(id, true))),
+                )
+                .padding([2, 10])
+                .height(Length::Fill)
+                .style(button_style),
+        )
+        .spacing(5)
+        .padding(iced::Padding::new(15.0).bottom(0.0).top(5.0))
+        .height(Length::Fixed(TAB_STRIP_HEIGHT))
+        .into()
+}
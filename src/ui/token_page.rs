@@ -1,5 +1,4 @@
-use crate::fetch_top;
-use crate::newsapi::fetch_sources;
+use crate::ui::style::BACK_ICON;
 use crate::ui::style::SUBMIT_ICON;
 use crate::ui::style::text_input_style;
 use iced::Length;
@@ -26,6 +25,8 @@ use iced::widget::{column, row, text, text_input};
 pub struct TokenPage {
     token: String,
     error: Option<NewsAPIError>,
+    /// the page was opened on top of a main page, which can be returned to
+    can_go_back: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -39,7 +40,19 @@ impl TokenPage {
         // empty string by default
         let token = var("NEWS_API_TOKEN").unwrap_or_default();
 
-        Self { token, error: None }
+        Self {
+            token,
+            error: None,
+            can_go_back: false,
+        }
+    }
+
+    /// Create a TokenPage opened from a main page, with a button to return to it.
+    pub fn over_main_page() -> Self {
+        Self {
+            can_go_back: true,
+            ..Self::new()
+        }
     }
 }
 
@@ -48,24 +61,35 @@ impl Page for TokenPage {
         use crate::ui::Message::TokenPage as T;
         use TokenPageMessage::*;
 
-        let input = row![
-            text_input("NewsAPI Token", &self.token)
-                .on_input(|s| T(OnInput(s)))
-                .on_submit(T(Submit))
-                .id(TOKEN_INPUT_ID)
-                .style(text_input_style)
-                .width(Length::Fill)
-                .size(24),
-            button(svg(Handle::from_memory(SUBMIT_ICON)))
-                .on_press(T(Submit))
-                .padding(10)
-                .width(48)
-                .height(Length::Fill)
-                .style(button_style),
-        ]
-        .spacing(5)
-        .height(Length::Fixed(72.0))
-        .padding(15);
+        let input = row![]
+            .push_maybe(self.can_go_back.then(|| {
+                button(svg(Handle::from_memory(BACK_ICON)))
+                    .on_press(Message::Back)
+                    .padding(10)
+                    .width(48)
+                    .height(Length::Fill)
+                    .style(button_style)
+            }))
+            .push(
+                text_input("NewsAPI Token", &self.token)
+                    .on_input(|s| T(OnInput(s)))
+                    .on_submit(T(Submit))
+                    .id(TOKEN_INPUT_ID)
+                    .style(text_input_style)
+                    .width(Length::Fill)
+                    .size(24),
+            )
+            .push(
+                button(svg(Handle::from_memory(SUBMIT_ICON)))
+                    .on_press(T(Submit))
+                    .padding(10)
+                    .width(48)
+                    .height(Length::Fill)
+                    .style(button_style),
+            )
+            .spacing(5)
+            .height(Length::Fixed(72.0))
+            .padding(15);
 
         let error_text: Element<'_, Message> = match &self.error {
             None => Space::new(0, 0).into(),
@@ -77,6 +101,12 @@ impl Page for TokenPage {
     }
 
     fn update(&mut self, msg: Message) -> Action {
+        if let Message::Back | Message::Escape = msg
+            && self.can_go_back
+        {
+            return Action::PopPage;
+        }
+
         if let Message::TokenPage(message) = msg {
             use TokenPageMessage::*;
             match message {
@@ -84,48 +114,14 @@ impl Page for TokenPage {
                     self.token = input;
                 }
                 Submit => match MainPage::new(&self.token) {
-                    Ok(page) => {
-                        // need to pass these into async move blocks, client is a shallow clone
-                        let search_client = page.client.clone();
-                        let source_client = page.client.clone();
+                    Ok(mut page) => {
+                        // begin fetching the top headlines and sources
+                        let search_task = page.search_task();
+                        let sources_task = page.sources_task();
 
                         return Action::SwitchPage((
                             Box::new(page),
-                            Task::batch(vec![
-                                focus(SEARCH_BAR_ID),
-                                // begin fetching the top headlines
-                                Task::perform(
-                                    async move {
-                                        fetch_top(&search_client, None).await.map_err(|e| {
-                                            eprintln!("{e:#?}");
-                                            e.to_string()
-                                        })
-                                    },
-                                    |v| {
-                                        Message::MainPage(
-                                            crate::ui::main_page::MainPageMessage::SearchComplete(
-                                                v,
-                                            ),
-                                        )
-                                    },
-                                ),
-                                // begin fetching sources
-                                Task::perform(
-                                    async move {
-                                        fetch_sources(&source_client).await.map_err(|e| {
-                                            eprintln!("{e:#?}");
-                                            e.to_string()
-                                        })
-                                    },
-                                    |v| {
-                                        Message::MainPage(
-                                            crate::ui::main_page::MainPageMessage::SourcesFetched(
-                                                v,
-                                            ),
-                                        )
-                                    },
-                                ),
-                            ]),
+                            Task::batch(vec![focus(SEARCH_BAR_ID), search_task, sources_task]),
                         ));
                     }
                     Err(e) => {
@@ -0,0 +1,154 @@
+use crate::analysis::sentiment::Tone;
+use crate::ui::Message;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::button_style;
+use iced::Alignment;
+use iced::Element;
+use iced::Length;
+use iced::color;
+use iced::widget::button;
+use iced::widget::container;
+use iced::widget::pick_list;
+use iced::widget::row;
+use iced::widget::slider;
+use iced::widget::text;
+use std::fmt;
+
+/// Setting key the tone range is persisted under, as "min,max".
+pub const TONE_RANGE_KEY: &str = "tone_range";
+
+/// Setting key the tone sort order is persisted under.
+pub const TONE_SORT_KEY: &str = "tone_sort";
+
+/// Step of the tone range sliders.
+const STEP: f32 = 0.1;
+
+/// Scores of the articles shown, other articles are hidden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ToneRange {
+    /// Every score, hiding nothing.
+    pub const FULL: ToneRange = ToneRange {
+        min: -1.0,
+        max: 1.0,
+    };
+
+    pub fn contains(&self, score: f32) -> bool {
+        (self.min..=self.max).contains(&score)
+    }
+
+    pub fn as_param(&self) -> String {
+        format!("{},{}", self.min, self.max)
+    }
+
+    pub fn from_param(param: &str) -> Option<Self> {
+        let (min, max) = param.split_once(',')?;
+        let (min, max) = (min.parse::<f32>().ok()?, max.parse::<f32>().ok()?);
+
+        (-1.0 <= min && min <= max && max <= 1.0).then_some(Self { min, max })
+    }
+}
+
+/// Order of the stories in the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneSort {
+    /// the order NewsAPI or the archive returned
+    #[default]
+    Results,
+    MostPositive,
+    MostNegative,
+}
+
+impl ToneSort {
+    pub const ALL: [ToneSort; 3] = [
+        ToneSort::Results,
+        ToneSort::MostPositive,
+        ToneSort::MostNegative,
+    ];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            ToneSort::Results => "results",
+            ToneSort::MostPositive => "positive",
+            ToneSort::MostNegative => "negative",
+        }
+    }
+
+    pub fn from_param(param: &str) -> Self {
+        match param {
+            "positive" => ToneSort::MostPositive,
+            "negative" => ToneSort::MostNegative,
+            _ => ToneSort::Results,
+        }
+    }
+}
+
+impl fmt::Display for ToneSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ToneSort::Results => "Result order",
+            ToneSort::MostPositive => "Most positive first",
+            ToneSort::MostNegative => "Most negative first",
+        })
+    }
+}
+
+/// Small colored label with the tone of an article and its score.
+pub fn tone_indicator(score: f32) -> Element<'static, Message> {
+    let tone = Tone::of(score);
+
+    text(format!("{tone} {score:+.2}"))
+        .size(13)
+        .color(match tone {
+            Tone::Negative => color!(0xd93025),
+            Tone::Neutral => color!(0x80868b),
+            Tone::Positive => color!(0x188038),
+        })
+        .into()
+}
+
+/// Bar above the results for hiding articles by tone and sorting stories by it.
+///
+/// Parameters:
+/// - `range`: scores of the articles shown
+/// - `sort`: order of the stories
+/// - `hidden`: number of articles the range hides
+pub fn tone_bar(range: ToneRange, sort: ToneSort, hidden: usize) -> Element<'static, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    let plural = if hidden == 1 { "" } else { "s" };
+
+    container(
+        row![
+            text("Tone").size(16),
+            text(format!("{:+.1}", range.min)).size(13).width(30),
+            slider(-1.0..=1.0, range.min, |v| M(ToneMinChanged(v)))
+                .step(STEP)
+                .on_release(M(SaveToneRange))
+                .width(120),
+            text("to").size(13),
+            slider(-1.0..=1.0, range.max, |v| M(ToneMaxChanged(v)))
+                .step(STEP)
+                .on_release(M(SaveToneRange))
+                .width(120),
+            text(format!("{:+.1}", range.max)).size(13).width(30),
+            pick_list(ToneSort::ALL, Some(sort), |s| M(ToneSortSelected(s))).text_size(13),
+        ]
+        .push_maybe((hidden > 0).then(|| text(format!("{hidden} article{plural} hidden")).size(13)))
+        .push_maybe((range != ToneRange::FULL).then(|| {
+            button(text("Show all").size(13))
+                .style(button_style)
+                .on_press(M(ResetToneRange))
+        }))
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .width(Length::Fill),
+    )
+    .padding([5, 15])
+    .into()
+}
@@ -0,0 +1,428 @@
+use crate::archive::trends::TermCount;
+use crate::archive::trends::TrendInterval;
+use crate::ui::Message;
+use crate::ui::chart::Chart;
+use crate::ui::chart::ChartKind;
+use crate::ui::chart::SERIES_COLORS;
+use crate::ui::chart::Series;
+use crate::ui::main_page::MainPageMessage;
+use crate::ui::style::CLOSE_ICON;
+use crate::ui::style::button_style;
+use crate::ui::style::close_button_style;
+use crate::ui::style::text_input_style;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use chrono::TimeDelta;
+use chrono::Timelike;
+use chrono::Utc;
+use iced::Alignment;
+use iced::Background;
+use iced::Border;
+use iced::Color;
+use iced::Element;
+use iced::Gradient;
+use iced::Length;
+use iced::color;
+use iced::widget::Column;
+use iced::widget::Row;
+use iced::widget::button;
+use iced::widget::canvas;
+use iced::widget::checkbox;
+use iced::widget::horizontal_rule;
+use iced::widget::pick_list;
+use iced::widget::scrollable;
+use iced::widget::svg;
+use iced::widget::text::Shaping::Advanced;
+use iced::widget::text_input;
+use iced::widget::{column, container, mouse_area, row, text};
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_4;
+use std::fmt;
+
+pub const TREND_INPUT_ID: &str = "trend_input";
+
+/// Sources shown separately in a per-source breakdown, the others are added up.
+const MAX_SOURCES: usize = SERIES_COLORS.len() - 1;
+
+/// How far back the trends page counts articles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrendRange {
+    Day,
+    #[default]
+    Week,
+    Month,
+    Quarter,
+}
+
+impl TrendRange {
+    pub const ALL: [TrendRange; 4] = [
+        TrendRange::Day,
+        TrendRange::Week,
+        TrendRange::Month,
+        TrendRange::Quarter,
+    ];
+
+    pub fn duration(&self) -> TimeDelta {
+        match self {
+            TrendRange::Day => TimeDelta::days(1),
+            TrendRange::Week => TimeDelta::days(7),
+            TrendRange::Month => TimeDelta::days(30),
+            TrendRange::Quarter => TimeDelta::days(90),
+        }
+    }
+}
+
+impl fmt::Display for TrendRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrendRange::Day => "Last 24 hours",
+            TrendRange::Week => "Last 7 days",
+            TrendRange::Month => "Last 30 days",
+            TrendRange::Quarter => "Last 90 days",
+        })
+    }
+}
+
+/// State of the trends page, kept while it's closed.
+#[derive(Debug, Default)]
+pub struct TrendsView {
+    /// content of the new term input
+    pub input: String,
+    /// charted terms, in the order they were added
+    pub terms: Vec<String>,
+    pub range: TrendRange,
+    pub interval: TrendInterval,
+    pub kind: ChartKind,
+    /// chart every term on its own, with a line per source
+    pub by_source: bool,
+    /// counts of every term that finished loading
+    pub counts: HashMap<String, Result<Vec<TermCount>, String>>,
+    /// incremented whenever the counts are reloaded, so counts for an old range are dropped
+    pub generation: u64,
+    /// when the shown counts start
+    pub since: DateTime<Utc>,
+}
+
+/// Start of every bucket between `since` and now, in local time.
+fn buckets(since: DateTime<Utc>, interval: TrendInterval) -> Vec<NaiveDateTime> {
+    let since = DateTime::<Local>::from(since).naive_local();
+    let now = Local::now().naive_local();
+
+    let (mut bucket, step) = match interval {
+        TrendInterval::Hour => (
+            since
+                .with_minute(0)
+                .and_then(|t| t.with_second(0))
+                .and_then(|t| t.with_nanosecond(0))
+                .unwrap_or(since),
+            TimeDelta::hours(1),
+        ),
+        TrendInterval::Day => (since.date().into(), TimeDelta::days(1)),
+    };
+
+    let mut buckets = Vec::new();
+    while bucket <= now {
+        buckets.push(bucket);
+        bucket += step;
+    }
+    buckets
+}
+
+/// Values of a series: the counts accepted by `filter`, added up per bucket.
+fn series_values(
+    counts: &[TermCount],
+    buckets: &[NaiveDateTime],
+    filter: impl Fn(&TermCount) -> bool,
+) -> Vec<f32> {
+    let mut values = vec![0.0; buckets.len()];
+    for count in counts.iter().filter(|c| filter(c)) {
+        if let Ok(i) = buckets.binary_search(&count.bucket) {
+            values[i] += count.count as f32;
+        }
+    }
+    values
+}
+
+/// Series of a term's most frequent sources, with the remaining sources added up.
+fn source_series(counts: &[TermCount], buckets: &[NaiveDateTime]) -> Vec<Series> {
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for count in counts {
+        *totals.entry(&count.source).or_default() += count.count;
+    }
+    let mut sources: Vec<(&str, usize)> = totals.into_iter().collect();
+    sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let (top, rest) = if sources.len() > MAX_SOURCES + 1 {
+        sources.split_at(MAX_SOURCES)
+    } else {
+        (&sources[..], &[][..])
+    };
+
+    let mut series: Vec<Series> = top
+        .iter()
+        .zip(SERIES_COLORS)
+        .map(|((source, _), color)| Series {
+            name: source.to_string(),
+            color,
+            values: series_values(counts, buckets, |c| c.source == *source),
+        })
+        .collect();
+
+    if !rest.is_empty() {
+        series.push(Series {
+            name: format!("{} other sources", rest.len()),
+            color: SERIES_COLORS[MAX_SOURCES],
+            values: series_values(counts, buckets, |c| {
+                rest.iter().any(|(source, _)| c.source == *source)
+            }),
+        });
+    }
+
+    series
+}
+
+/// Small colored square with a series name and its total.
+fn legend_entry(series: &Series) -> Element<'static, Message> {
+    let color = series.color;
+
+    row![
+        container(text(""))
+            .width(14)
+            .height(14)
+            .style(move |_theme| container::Style {
+                background: Some(Background::Color(color)),
+                border: Border::default().rounded(3),
+                ..Default::default()
+            }),
+        text(format!(
+            "{} ({})",
+            series.name,
+            series.values.iter().sum::<f32>()
+        ))
+        .size(14)
+        .shaping(Advanced),
+    ]
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// A chart with its legend and an optional title.
+fn chart_block(title: Option<&str>, chart: Chart<Message>) -> Element<'static, Message> {
+    Column::with_capacity(3)
+        .push_maybe(title.map(|title| text(title.to_string()).size(18).shaping(Advanced)))
+        .push(Row::with_children(chart.series.iter().map(legend_entry)).spacing(15))
+        .push(canvas(chart).width(Length::Fill).height(320))
+        .spacing(8)
+        .into()
+}
+
+/// Build the charts of the loaded terms.
+fn charts(view: &TrendsView) -> Vec<Element<'static, Message>> {
+    let buckets = buckets(view.since, view.interval);
+    let format = match (view.interval, view.range) {
+        (TrendInterval::Hour, TrendRange::Day) => "%H:%M",
+        (TrendInterval::Hour, _) => "%b %d %H:00",
+        (TrendInterval::Day, _) => "%b %d",
+    };
+    let labels: Vec<String> = buckets
+        .iter()
+        .map(|bucket| bucket.format(format).to_string())
+        .collect();
+
+    let loaded =
+        view.terms
+            .iter()
+            .enumerate()
+            .filter_map(|(i, term)| match view.counts.get(term) {
+                Some(Ok(counts)) => Some((i, term, counts)),
+                _ => None,
+            });
+
+    if view.by_source {
+        loaded
+            .map(|(_, term, counts)| {
+                let chart = Chart {
+                    labels: labels.clone(),
+                    series: source_series(counts, &buckets),
+                    kind: view.kind,
+                    on_press: Vec::new(),
+                    selected: None,
+                };
+                chart_block(Some(term), chart)
+            })
+            .collect()
+    } else {
+        let chart = Chart {
+            labels,
+            series: loaded
+                .map(|(i, term, counts)| Series {
+                    name: term.clone(),
+                    color: SERIES_COLORS[i % SERIES_COLORS.len()],
+                    values: series_values(counts, &buckets, |_| true),
+                })
+                .collect(),
+            kind: view.kind,
+            on_press: Vec::new(),
+            selected: None,
+        };
+
+        if chart.series.is_empty() {
+            Vec::new()
+        } else {
+            vec![chart_block(None, chart)]
+        }
+    }
+}
+
+/// Button of a charted term, removing it when pressed, with the reason it couldn't be counted.
+fn term_entry<'a>(term: &'a str, error: Option<&'a String>) -> Element<'a, Message> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    row![
+        text(term).size(16).shaping(Advanced),
+        button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+            .width(28)
+            .style(close_button_style)
+            .on_press(M(TrendTermRemoved(term.to_string()))),
+    ]
+    .push_maybe(error.map(|e| text(e).size(14).color(color!(0xd93025))))
+    .spacing(5)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Render the trends page when `trends_page` is true.
+///
+/// Parameters:
+/// - `trends_page`: whether the page should be shown. If false, returns None.
+/// - `view`: the charted terms, their counts and the chart options
+///
+/// Returns:
+/// - `Some(Element<'a, Message>)` with the term input and chart options above charts of how many
+///   archived articles mention each term over time.
+/// - `None` when the page is hidden.
+pub fn trends_page(trends_page: bool, view: &TrendsView) -> Option<Element<'_, Message>> {
+    use MainPageMessage::*;
+    use Message::MainPage as M;
+
+    if !trends_page {
+        return None;
+    }
+
+    let controls = row![
+        text_input(
+            "Add a term, like \"climate change\" OR warming",
+            &view.input
+        )
+        .id(TREND_INPUT_ID)
+        .on_input(|s| M(TrendInputOnInput(s)))
+        .on_submit(M(TrendTermAdded))
+        .style(text_input_style)
+        .width(Length::Fill),
+        button(text("Add"))
+            .style(button_style)
+            .on_press_maybe((!view.input.trim().is_empty()).then_some(M(TrendTermAdded))),
+        pick_list(TrendRange::ALL, Some(view.range), |r| M(
+            TrendRangeSelected(r)
+        )),
+        pick_list(TrendInterval::ALL, Some(view.interval), |i| M(
+            TrendIntervalSelected(i)
+        )),
+        pick_list(ChartKind::ALL, Some(view.kind), |k| M(ChartKindSelected(k))),
+        checkbox("By source", view.by_source).on_toggle(|b| M(TrendBySourceToggled(b))),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let terms = Row::with_children(view.terms.iter().map(|term| {
+        let error = match view.counts.get(term) {
+            Some(Err(e)) => Some(e),
+            _ => None,
+        };
+        term_entry(term, error)
+    }))
+    .spacing(20)
+    .wrap();
+
+    let loading = view.terms.iter().any(|t| !view.counts.contains_key(t));
+    let charts = charts(view);
+    let counted = view
+        .counts
+        .values()
+        .flatten()
+        .any(|counts| !counts.is_empty());
+
+    let body: Element<'_, Message> = if view.terms.is_empty() {
+        text("Add a term to chart how often your archived articles mention it. Terms use the search syntax.")
+            .into()
+    } else if loading {
+        text("Loading...").into()
+    } else if !counted {
+        text("No archived articles in this range mention these terms.").into()
+    } else {
+        scrollable(
+            Column::with_children(charts)
+                .spacing(25)
+                .padding(iced::Padding::ZERO.right(10)),
+        )
+        .height(Length::Fill)
+        .into()
+    };
+
+    Some(
+        mouse_area(
+            container(
+                mouse_area(
+                    container(column![
+                        row![
+                            text("Trends").size(24),
+                            container(
+                                button(svg(svg::Handle::from_memory(CLOSE_ICON)))
+                                    .width(48)
+                                    .style(close_button_style)
+                                    .on_press(M(ToggleTrendsPage)),
+                            )
+                            .align_right(Length::Fill),
+                        ]
+                        .padding(5)
+                        .align_y(Alignment::Center),
+                        horizontal_rule(6),
+                        column![controls, terms, body].spacing(15).padding(5),
+                    ])
+                    .padding([10, 10]) // top/bottom, left/right
+                    .width(Length::Fill)
+                    .max_width(1400)
+                    .style(|theme| container::Style {
+                        background: Some(Background::Gradient(Gradient::Linear(
+                            iced::gradient::Linear::new(FRAC_PI_4)
+                                .add_stop(0.0, Color::from_rgb(1.0, 1.0, 1.0))
+                                .add_stop(1.0, Color::from_rgb(0.95, 0.95, 1.0)),
+                        ))),
+                        text_color: Some(theme.palette().text),
+                        border: Border::default()
+                            .color(theme.palette().primary)
+                            .rounded(10)
+                            .width(2),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(Message::NoOp),
+            )
+            .padding(40)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .style(|_theme| container::Style {
+                background: None,
+                ..Default::default()
+            }),
+        )
+        .interaction(iced::mouse::Interaction::Idle)
+        .on_right_press(M(ToggleTrendsPage))
+        .on_press(M(ToggleTrendsPage))
+        .into(),
+    )
+}